@@ -1,8 +1,8 @@
 #![feature(test)]
+use std::fs::File;
 use std::io::Cursor;
 use std::io::Read;
-use std::{cell::Cell, fs::File};
-use wav::{bit_depth::BitDepth, header::Header, read, write};
+use wav::read;
 
 extern crate test;
 use test::{black_box, Bencher};
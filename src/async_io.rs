@@ -0,0 +1,312 @@
+//! Contains async equivalents of the reading and writing functions, built on the `AsyncRead`,
+//! `AsyncSeek` and `AsyncWrite` traits of the `futures-io` crate.
+//!
+//! Only available with the `async` feature enabled. Tokio types can be used through the `compat`
+//! module of the `tokio-util` crate.
+//!
+//! The chunk walk, header parsing and sample decoding are shared with the blocking functions, so
+//! both behave identically.
+//!
+//! ## Example
+//!
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! # futures_executor::block_on(async {
+//! use futures_util::io::Cursor;
+//!
+//! let raw: &[u8] = include_bytes!("../data/sine.wav");
+//! let (header, data) = wav::async_io::read(&mut Cursor::new(raw)).await?;
+//!
+//! let mut out = Cursor::new(Vec::new());
+//! wav::async_io::write(header, &data, &mut out).await?;
+//! # Ok(())
+//! # })
+//! # }
+//! ```
+
+use std::io::{self, SeekFrom};
+
+use futures_util::io::{
+    AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt,
+};
+
+use crate::{
+    chunk::{self, ChunkHeader, Walker},
+    decode_data, find_data_chunk, find_fmt_chunk, layout, parse_header, prepare_write,
+    reader::FrameCursor,
+    BitDepth, Chunk, Header, ReadOptions, Warning,
+};
+
+/// Async equivalent of [`crate::read`].
+///
+/// ## Errors
+///
+/// See [`crate::read`].
+pub async fn read<R>(reader: &mut R) -> io::Result<(Header, BitDepth)>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let (header, data, _) = read_with_options(reader, ReadOptions::default()).await?;
+    Ok((header, data))
+}
+
+/// Async equivalent of [`crate::read_with_options`].
+///
+/// ## Errors
+///
+/// See [`crate::read_with_options`].
+pub async fn read_with_options<R>(
+    reader: &mut R,
+    options: ReadOptions,
+) -> io::Result<(Header, BitDepth, Vec<Warning>)>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let mut warnings = Vec::new();
+    let (header, chunks) = parse_fmt(reader, options, &mut warnings).await?;
+    let bytes = if chunks.iter().any(|c| c.id == *b"data") {
+        let data = find_data_chunk(&chunks, &header, options, &mut warnings)?;
+        read_contents(reader, &data).await?
+    } else {
+        read_wave_list(reader, &chunks, &header, options, &mut warnings).await?
+    };
+    let data = decode_data(&header, bytes)?;
+
+    Ok((header, data, warnings))
+}
+
+/// Async equivalent of [`crate::write`].
+///
+/// ## Errors
+///
+/// See [`crate::write`].
+pub async fn write<W>(header: Header, track: &BitDepth, writer: &mut W) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    write_with_chunks(header, track, &[], writer).await
+}
+
+/// Async equivalent of [`crate::write_with_chunks`].
+///
+/// ## Errors
+///
+/// See [`crate::write_with_chunks`].
+pub async fn write_with_chunks<W>(
+    header: Header,
+    track: &BitDepth,
+    chunks: &[Chunk],
+    writer: &mut W,
+) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let d_vec = prepare_write(header, track, chunks)?;
+    let h_vec = Vec::from(header);
+    let contents = layout(&h_vec, chunks, &d_vec);
+    let (riff, headers) = chunk::riff_layout(&contents)?;
+
+    writer.write_all(&riff).await?;
+    for ((_, data), header) in contents.iter().zip(headers) {
+        writer.write_all(&header).await?;
+        writer.write_all(data).await?;
+        if data.len() % 2 == 1 {
+            writer.write_all(&[0]).await?;
+        }
+    }
+
+    writer.flush().await
+}
+
+/// Async equivalent of [`crate::WavReader`], giving sample-accurate access to the frames of a wave
+/// file without decoding the whole `"data"` chunk.
+#[derive(Debug)]
+pub struct AsyncWavReader<R> {
+    reader: R,
+    header: Header,
+    frames: FrameCursor,
+    warnings: Vec<Warning>,
+}
+
+impl<R> AsyncWavReader<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    /// Async equivalent of [`crate::WavReader::new`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`crate::WavReader::new`].
+    pub async fn new(reader: R) -> io::Result<Self> {
+        Self::with_options(reader, ReadOptions::default()).await
+    }
+
+    /// Async equivalent of [`crate::WavReader::with_options`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`crate::WavReader::with_options`].
+    pub async fn with_options(mut reader: R, options: ReadOptions) -> io::Result<Self> {
+        let mut warnings = Vec::new();
+        let (header, data) = parse(&mut reader, options, &mut warnings).await?;
+        let frames = FrameCursor::new(&header, &data)?;
+        reader.seek(SeekFrom::Start(frames.data_offset)).await?;
+
+        Ok(Self {
+            reader,
+            header,
+            frames,
+            warnings,
+        })
+    }
+
+    /// Returns the header of the wave file.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the warnings raised while parsing the file.
+    #[must_use]
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns the total number of complete frames in the `"data"` chunk.
+    #[must_use]
+    pub fn frame_count(&self) -> u64 {
+        self.frames.frame_count()
+    }
+
+    /// Returns the index of the frame that will be read next.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.frames.position
+    }
+
+    /// Async equivalent of [`crate::WavReader::seek`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`crate::WavReader::seek`].
+    pub async fn seek(&mut self, frame: u64) -> io::Result<()> {
+        let offset = self.frames.seek_offset(frame)?;
+        self.reader.seek(SeekFrom::Start(offset)).await?;
+        self.frames.position = frame;
+
+        Ok(())
+    }
+
+    /// Async equivalent of [`crate::WavReader::read_frames`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`crate::WavReader::read_frames`].
+    pub async fn read_frames(&mut self, count: u64) -> io::Result<BitDepth> {
+        let (count, len) = self.frames.read_len(count)?;
+
+        let mut bytes = vec![0; len];
+        self.reader.read_exact(&mut bytes).await?;
+        self.frames.position += count;
+
+        decode_data(&self.header, bytes)
+    }
+
+    /// Consumes the `AsyncWavReader`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+async fn parse<R>(
+    reader: &mut R,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<(Header, ChunkHeader)>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let (header, chunks) = parse_fmt(reader, options, warnings).await?;
+    let data = find_data_chunk(&chunks, &header, options, warnings)?;
+
+    Ok((header, data))
+}
+
+async fn parse_fmt<R>(
+    reader: &mut R,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<(Header, Vec<ChunkHeader>)>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let chunks = walk(reader, options.recover, warnings).await?;
+    let header_bytes = read_contents(reader, find_fmt_chunk(&chunks)?).await?;
+    let header = parse_header(&header_bytes, options, warnings)?;
+
+    Ok((header, chunks))
+}
+
+async fn read_wave_list<R>(
+    reader: &mut R,
+    chunks: &[ChunkHeader],
+    header: &Header,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<Vec<u8>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    for list in chunks.iter().filter(|c| c.id == *b"LIST") {
+        let contents = read_contents(reader, list).await?;
+        if contents.starts_with(b"wavl") {
+            return chunk::expand_wave_list(&contents, header, options.recover, warnings);
+        }
+    }
+
+    Err(io::Error::other("Could not parse audio data"))
+}
+
+async fn walk<R>(
+    reader: &mut R,
+    recover: bool,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<Vec<ChunkHeader>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let stream_len = reader.seek(SeekFrom::End(0)).await?;
+    reader.seek(SeekFrom::Start(0)).await?;
+
+    let mut riff = [0; 12];
+    reader.read_exact(&mut riff).await?;
+    let mut walker = Walker::new(&riff, stream_len, recover, warnings)?;
+
+    while let Some(pos) = walker.next_chunk() {
+        let mut header = [0; 8];
+        reader.seek(SeekFrom::Start(pos)).await?;
+        reader.read_exact(&mut header).await?;
+
+        if let Some((pos, len)) = walker.chunk(header, warnings) {
+            let mut bytes = vec![0; len];
+            reader.seek(SeekFrom::Start(pos)).await?;
+            reader.read_exact(&mut bytes).await?;
+            walker.probe(&bytes, warnings)?;
+        }
+    }
+
+    Ok(walker.chunks)
+}
+
+async fn read_contents<R>(reader: &mut R, chunk: &ChunkHeader) -> io::Result<Vec<u8>>
+where
+    R: AsyncRead + AsyncSeek + Unpin,
+{
+    let stream_len = reader.seek(SeekFrom::End(0)).await?;
+    let mut data = vec![0; chunk.checked_len(stream_len)?];
+
+    reader.seek(SeekFrom::Start(chunk.data_offset())).await?;
+    reader.read_exact(&mut data).await?;
+
+    Ok(data)
+}
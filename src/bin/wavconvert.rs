@@ -0,0 +1,375 @@
+//! Converts the sample format, channel count and sampling rate of wave files, keeping their other
+//! chunks. Channels are mixed into the conventional layout for the new channel count following
+//! ITU-R BS.775.
+//!
+//! ```text
+//! wavconvert [--format 8|16|24|float] [--channels N] [--rate HZ] [--quality low|medium|high]
+//!            INPUT OUTPUT
+//! ```
+//!
+//! If `INPUT` is a directory, every `.wav` file directly inside it is converted into the directory
+//! `OUTPUT`, which is created if needed. The audio is streamed in blocks, so files of any size can
+//! be converted, except for files storing it as a wave list, which are read whole and written with
+//! a `"data"` chunk.
+//!
+//! Chunks describing the audio are updated when the sampling rate or channel count changes: cue
+//! points, playlists, cart timers and the `bext` time reference are rescaled to the new rate, the
+//! `bext` loudness is cleared, the iXML speed and track list are updated, and the `levl` peaks,
+//! `fact` sample count and ADM `chna` and `axml` chunks, which can't be updated, are dropped.
+
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+
+use std::{
+    convert::TryFrom,
+    env, fs,
+    fs::File,
+    io::{self, BufReader, BufWriter, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process,
+};
+
+use wav::{
+    channels::{ChannelLayout, Matrix},
+    metadata::{Axml, Bext, Cart, Chna, Cue, Ixml, Levl, Plst},
+    resample::{Quality, Resampler},
+    BitDepth, Chunk, Header, Sample, SampleFormat, WavReader, WavWriter,
+};
+
+const USAGE: &str = "Usage: wavconvert [--format 8|16|24|float] [--channels N] [--rate HZ] \
+                     [--quality low|medium|high] INPUT OUTPUT";
+
+/// Number of frames converted at once.
+const BLOCK_FRAMES: u64 = 65_536;
+
+/// The conversion asked for on the command line, `None` keeping the input's settings.
+#[derive(Debug, Default, Copy, Clone)]
+struct Conversion {
+    format: Option<SampleFormat>,
+    channels: Option<u16>,
+    rate: Option<u32>,
+    quality: Quality,
+}
+
+impl Conversion {
+    fn header(self, input: &Header) -> io::Result<Header> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+        let Some(format) = self.format.or_else(|| input.sample_format()) else {
+            return Err(invalid("Unsupported data format or bit depth".to_string()));
+        };
+        let channels = self.channels.unwrap_or(input.channel_count);
+
+        let mut builder = Header::builder(format)
+            .channel_count(channels)
+            .sampling_rate(self.rate.unwrap_or(input.sampling_rate));
+        // Keep the input's format tag, extensible files keeping their speaker positions
+        if let Some(ext) = input.extension {
+            builder = builder.extensible(true);
+            if channels == input.channel_count {
+                builder = builder.channel_mask(ext.channel_mask);
+            }
+        }
+
+        builder.build().map_err(|e| invalid(e.to_string()))
+    }
+
+    fn run(self, input: &Path, output: &Path) -> io::Result<()> {
+        let mut reader = BufReader::new(File::open(input)?);
+        let chunks = wav::read_chunks(&mut reader)?;
+        reader.seek(SeekFrom::Start(0))?;
+        let mut source = if chunks.iter().any(is_wave_list) {
+            let (header, data) = wav::read(&mut reader)?;
+            Source::WaveList(header, Some(data))
+        } else {
+            Source::Stream(WavReader::new(reader)?)
+        };
+
+        let from = source.header();
+        let to = self.header(&from)?;
+        let format = to.sample_format().unwrap_or(SampleFormat::ThirtyTwoFloat);
+        let chunks = update_chunks(chunks, &from, &to);
+
+        let matrix = if from.channel_count == to.channel_count {
+            None
+        } else {
+            Some(downmix(&from, &to)?)
+        };
+        let mut resampler = if from.sampling_rate == to.sampling_rate {
+            None
+        } else {
+            let resampler = Resampler::new(
+                from.sampling_rate,
+                to.sampling_rate,
+                to.channel_count,
+                self.quality,
+            );
+            Some(resampler.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?)
+        };
+
+        let file = BufWriter::new(File::create(output)?);
+        let mut writer = WavWriter::with_chunks(file, to, &chunks)?;
+        while let Some(mut frames) = source.next_block()? {
+            if let Some(matrix) = &matrix {
+                frames = BitDepth::ThirtyTwoFloat(matrix.mix(&f32::from_bit_depth(&frames)));
+            }
+            if let Some(resampler) = &mut resampler {
+                frames = BitDepth::ThirtyTwoFloat(resampler.process(&f32::from_bit_depth(&frames)));
+            }
+            writer.write_frames(&frames.convert(format))?;
+        }
+        if let Some(resampler) = resampler {
+            writer.write_frames(&BitDepth::ThirtyTwoFloat(resampler.finish()).convert(format))?;
+        }
+        writer.finish()?;
+
+        Ok(())
+    }
+}
+
+/// The audio of the input, streamed from its `"data"` chunk or read whole from its wave list.
+enum Source {
+    Stream(WavReader<BufReader<File>>),
+    WaveList(Header, Option<BitDepth>),
+}
+
+impl Source {
+    fn header(&self) -> Header {
+        match self {
+            Source::Stream(reader) => *reader.header(),
+            Source::WaveList(header, _) => *header,
+        }
+    }
+
+    /// Returns the next block of frames, or `None` once all have been read.
+    fn next_block(&mut self) -> io::Result<Option<BitDepth>> {
+        match self {
+            Source::Stream(reader) if reader.position() < reader.frame_count() => {
+                reader.read_frames(BLOCK_FRAMES).map(Some)
+            }
+            Source::Stream(_) => Ok(None),
+            Source::WaveList(_, data) => Ok(data.take()),
+        }
+    }
+}
+
+fn is_wave_list(chunk: &Chunk) -> bool {
+    chunk.id == *b"LIST" && chunk.data.starts_with(b"wavl")
+}
+
+/// Updates the chunks kept from the input to describe the audio converted from `from` to `to`,
+/// dropping those that can't be updated. The wave list is always dropped, the audio being written
+/// as a `"data"` chunk.
+fn update_chunks(chunks: Vec<Chunk>, from: &Header, to: &Header) -> Vec<Chunk> {
+    let rate_changed = from.sampling_rate != to.sampling_rate;
+    let channels_changed = from.channel_count != to.channel_count;
+    let rescale = |frames: u64| {
+        let scaled =
+            u128::from(frames) * u128::from(to.sampling_rate) + u128::from(from.sampling_rate / 2);
+        u64::try_from(scaled / u128::from(from.sampling_rate)).unwrap_or(u64::MAX)
+    };
+    let rescale_u32 = |frames: u32| u32::try_from(rescale(frames.into())).unwrap_or(u32::MAX);
+
+    // Cue points in a wave list are positioned within its chunks, which are merged here, so only
+    // those in the first chunk keep their position
+    let cue_lost = chunks.iter().any(is_wave_list)
+        && !chunks
+            .iter()
+            .find(|c| c.id == Cue::ID)
+            .and_then(|c| Cue::try_from(c).ok())
+            .is_some_and(|cue| {
+                cue.points
+                    .iter()
+                    .all(|p| p.chunk_id == *b"data" && p.chunk_start == 0)
+            });
+
+    let mut updated = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let chunk = match chunk.id {
+            _ if is_wave_list(&chunk) => None,
+            Levl::ID if rate_changed || channels_changed => None,
+            Chna::ID | Axml::ID if channels_changed => None,
+            id if id == *b"fact" && rate_changed => None,
+            Cue::ID | Plst::ID if cue_lost => None,
+            Cue::ID if rate_changed => Cue::try_from(&chunk).ok().and_then(|mut cue| {
+                for point in &mut cue.points {
+                    point.position = rescale_u32(point.position);
+                    point.sample_offset = rescale_u32(point.sample_offset);
+                }
+                Chunk::try_from(&cue).ok()
+            }),
+            Plst::ID if rate_changed => Plst::try_from(&chunk).ok().and_then(|mut plst| {
+                for segment in &mut plst.segments {
+                    segment.length = rescale_u32(segment.length);
+                }
+                Chunk::try_from(&plst).ok()
+            }),
+            Cart::ID if rate_changed => Cart::try_from(&chunk).ok().and_then(|mut cart| {
+                for timer in &mut cart.post_timers {
+                    timer.value = rescale_u32(timer.value);
+                }
+                Chunk::try_from(&cart).ok()
+            }),
+            Bext::ID if rate_changed || channels_changed => {
+                Bext::try_from(&chunk).ok().and_then(|mut bext| {
+                    bext.time_reference = rescale(bext.time_reference);
+                    bext.loudness_value = Bext::UNSET_LOUDNESS;
+                    bext.loudness_range = Bext::UNSET_LOUDNESS;
+                    bext.max_true_peak_level = Bext::UNSET_LOUDNESS;
+                    bext.max_momentary_loudness = Bext::UNSET_LOUDNESS;
+                    bext.max_short_term_loudness = Bext::UNSET_LOUDNESS;
+                    Chunk::try_from(&bext).ok()
+                })
+            }
+            Ixml::ID if rate_changed || channels_changed => {
+                Ixml::try_from(&chunk).ok().map(|mut ixml| {
+                    if rate_changed {
+                        let mut speed = ixml.speed();
+                        speed.file_sample_rate = Some(to.sampling_rate);
+                        ixml.set_speed(&speed);
+                    }
+                    if channels_changed {
+                        ixml.remove("TRACK_LIST");
+                    }
+                    Chunk::from(&ixml)
+                })
+            }
+            _ => Some(chunk),
+        };
+        updated.extend(chunk);
+    }
+
+    updated
+}
+
+/// Returns the matrix mixing the channels described by `from` into those described by `to`.
+fn downmix(from: &Header, to: &Header) -> io::Result<Matrix> {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+    let from = ChannelLayout::from_header(from).ok_or_else(|| invalid("Unknown channel layout"))?;
+    let to = ChannelLayout::from_header(to)
+        .ok_or_else(|| invalid("No conventional layout for the channel count"))?;
+    Matrix::downmix(from, to).map_err(invalid)
+}
+
+/// Lists the pairs of input and output files to convert.
+fn jobs(input: &Path, output: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    if !input.is_dir() {
+        return Ok(vec![(input.to_path_buf(), output.to_path_buf())]);
+    }
+
+    fs::create_dir_all(output)?;
+    let mut jobs = Vec::new();
+    for entry in fs::read_dir(input)? {
+        let path = entry?.path();
+        let is_wav = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+        if is_wav && path.is_file() {
+            if let Some(name) = path.file_name() {
+                jobs.push((path.clone(), output.join(name)));
+            }
+        }
+    }
+    jobs.sort();
+
+    Ok(jobs)
+}
+
+/// Checks that writing `output` won't truncate `input` before it has been read.
+fn is_same_file(input: &Path, output: &Path) -> bool {
+    match (fs::canonicalize(input), fs::canonicalize(output)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn parse_args() -> Result<(Conversion, PathBuf, PathBuf), String> {
+    let mut conversion = Conversion::default();
+    let mut paths = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                conversion.format = Some(match args.next().as_deref() {
+                    Some("8") => SampleFormat::Eight,
+                    Some("16") => SampleFormat::Sixteen,
+                    Some("24") => SampleFormat::TwentyFour,
+                    Some("float") => SampleFormat::ThirtyTwoFloat,
+                    other => return Err(format!("Invalid format {other:?}")),
+                });
+            }
+            "--channels" => {
+                let channels = args.next().and_then(|n| n.parse().ok());
+                match channels {
+                    Some(n) if n > 0 => conversion.channels = Some(n),
+                    _ => return Err("Invalid channel count".to_string()),
+                }
+            }
+            "--rate" => {
+                let rate = args.next().and_then(|n| n.parse().ok());
+                match rate {
+                    Some(n) if n > 0 => conversion.rate = Some(n),
+                    _ => return Err("Invalid sampling rate".to_string()),
+                }
+            }
+            "--quality" => {
+                conversion.quality = match args.next().as_deref() {
+                    Some("low") => Quality::Low,
+                    Some("medium") => Quality::Medium,
+                    Some("high") => Quality::High,
+                    other => return Err(format!("Invalid quality {other:?}")),
+                };
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    match <[PathBuf; 2]>::try_from(paths) {
+        Ok([input, output]) => Ok((conversion, input, output)),
+        Err(_) => Err("Expected an input and an output".to_string()),
+    }
+}
+
+fn main() {
+    let (conversion, input, output) = match parse_args() {
+        Ok(args) => args,
+        Err(e) if e.is_empty() => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("wavconvert: {e}\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let jobs = match jobs(&input, &output) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("wavconvert: {}: {e}", output.display());
+            process::exit(1);
+        }
+    };
+
+    let mut failed = false;
+    for (input, output) in jobs {
+        let result = if is_same_file(&input, &output) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Output would overwrite the input",
+            ))
+        } else {
+            conversion.run(&input, &output)
+        };
+        if let Err(e) = result {
+            eprintln!("wavconvert: {}: {e}", input.display());
+            failed = true;
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
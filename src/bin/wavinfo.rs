@@ -0,0 +1,408 @@
+//! Prints the format, duration, chunk list and metadata of wave files without decoding their audio.
+//!
+//! ```text
+//! wavinfo [--json] FILE...
+//! ```
+//!
+//! The `"bext"`, `"cart"`, `"iXML"` and `"cue "` chunks and the `"LIST"` chunk of type `"INFO"` are
+//! parsed and their fields printed. With `--json`, which needs the `json` feature, one JSON object
+//! is printed per file, each on its own line.
+
+#![warn(clippy::all)]
+#![warn(clippy::pedantic)]
+
+use std::{
+    convert::TryFrom,
+    env,
+    fmt::Write,
+    fs::File,
+    io::{self, BufReader},
+    process,
+};
+
+use wav::{
+    metadata::{Bext, Cart, Cue, Ixml, Track},
+    Header, WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM,
+};
+
+const USAGE: &str = "Usage: wavinfo [--json] FILE...";
+
+/// Everything printed about a single file.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+// The format name is only printed in JSON
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct Info {
+    file: String,
+    format: String,
+    format_tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+    block_align: u16,
+    bytes_per_second: u32,
+    extension: Option<Extension>,
+    frames: u64,
+    duration_seconds: f64,
+    chunks: Vec<ChunkInfo>,
+    metadata: Metadata,
+}
+
+/// The extra members of [`WAV_FORMAT_EXTENSIBLE`] headers.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct Extension {
+    valid_bits_per_sample: u16,
+    channel_mask: u32,
+    sub_format_tag: Option<u16>,
+}
+
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct ChunkInfo {
+    id: String,
+    offset: u64,
+    size: u64,
+}
+
+/// The metadata chunks found in the file, the first of each kind being used.
+#[derive(Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct Metadata {
+    bext: Option<Bext>,
+    cart: Option<Cart>,
+    ixml: Option<IxmlInfo>,
+    info: Vec<InfoEntry>,
+    cue: Option<Cue>,
+}
+
+/// The fields of an iXML document shown by `wavinfo`.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct IxmlInfo {
+    project: Option<String>,
+    scene: Option<String>,
+    take: Option<String>,
+    tape: Option<String>,
+    note: Option<String>,
+    circled: Option<bool>,
+    tracks: Vec<Track>,
+}
+
+impl From<&Ixml> for IxmlInfo {
+    fn from(ixml: &Ixml) -> Self {
+        IxmlInfo {
+            project: ixml.project().map(str::to_string),
+            scene: ixml.scene().map(str::to_string),
+            take: ixml.take().map(str::to_string),
+            tape: ixml.tape().map(str::to_string),
+            note: ixml.note().map(str::to_string),
+            circled: ixml.circled(),
+            tracks: ixml.tracks(),
+        }
+    }
+}
+
+/// An entry of the `"LIST"` chunk of type `"INFO"`, such as `"INAM"` for the title.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct InfoEntry {
+    id: String,
+    text: String,
+}
+
+impl Info {
+    /// Reads the header and metadata of the file at `path`, walking its chunks once.
+    fn read(path: &str) -> io::Result<Self> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+
+        let mut reader = BufReader::new(File::open(path)?);
+        let chunks = wav::list_chunks(&mut reader)?;
+        let fmt = chunks
+            .iter()
+            .find(|c| c.id == *b"fmt ")
+            .ok_or_else(|| invalid("File has no \"fmt \" chunk"))?;
+        let h = Header::try_from(fmt.read_contents(&mut reader)?.as_slice()).map_err(invalid)?;
+
+        let mut metadata = Metadata::default();
+        for chunk in &chunks {
+            let wanted = match &chunk.id {
+                b"bext" => metadata.bext.is_none(),
+                b"cart" => metadata.cart.is_none(),
+                b"iXML" => metadata.ixml.is_none(),
+                b"cue " => metadata.cue.is_none(),
+                b"LIST" => metadata.info.is_empty(),
+                _ => false,
+            };
+            if wanted {
+                metadata.add(chunk.id, &chunk.read_contents(&mut reader)?);
+            }
+        }
+
+        let block_align = u64::from(h.bytes_per_sample);
+        let frames = chunks
+            .iter()
+            .find(|c| c.id == *b"data")
+            .filter(|_| block_align != 0)
+            .map_or(0, |c| c.len / block_align);
+
+        Ok(Info {
+            file: path.to_string(),
+            format: format_name(h.audio_format).to_lowercase().replace(' ', "_"),
+            format_tag: h.audio_format,
+            channels: h.channel_count,
+            sample_rate: h.sampling_rate,
+            bits_per_sample: h.bits_per_sample,
+            block_align: h.bytes_per_sample,
+            bytes_per_second: h.bytes_per_second,
+            extension: h.extension.map(|ext| Extension {
+                valid_bits_per_sample: ext.valid_bits_per_sample,
+                channel_mask: ext.channel_mask,
+                sub_format_tag: ext.sub_format_tag(),
+            }),
+            frames,
+            duration_seconds: duration(frames, h.sampling_rate),
+            chunks: chunks
+                .iter()
+                .map(|c| ChunkInfo {
+                    id: String::from_utf8_lossy(&c.id).into_owned(),
+                    offset: c.offset,
+                    size: c.len,
+                })
+                .collect(),
+            metadata,
+        })
+    }
+
+    fn text(&self) -> String {
+        let mut s = String::new();
+
+        // Writing to a `String` can't fail
+        let _ = writeln!(s, "{}", self.file);
+        let _ = writeln!(
+            s,
+            "  Format:       {} (0x{:04X})",
+            format_name(self.format_tag),
+            self.format_tag
+        );
+        let _ = writeln!(s, "  Channels:     {}", self.channels);
+        let _ = writeln!(s, "  Sample rate:  {} Hz", self.sample_rate);
+        let _ = writeln!(s, "  Bit depth:    {}", self.bits_per_sample);
+        let _ = writeln!(s, "  Block align:  {} bytes", self.block_align);
+        let _ = writeln!(s, "  Byte rate:    {} bytes/s", self.bytes_per_second);
+        if let Some(ext) = &self.extension {
+            let sub_format = ext.sub_format_tag.map_or("Unknown", format_name);
+            let _ = writeln!(s, "  Valid bits:   {}", ext.valid_bits_per_sample);
+            let _ = writeln!(s, "  Channel mask: 0x{:08X}", ext.channel_mask);
+            let _ = writeln!(s, "  Sub-format:   {sub_format}");
+        }
+        let _ = writeln!(s, "  Frames:       {}", self.frames);
+        let _ = writeln!(s, "  Duration:     {:.3} s", self.duration_seconds);
+        let _ = writeln!(s, "  Chunks:");
+        for c in &self.chunks {
+            let _ = writeln!(
+                s,
+                "    \"{}\"  offset {:>10}  size {:>10}",
+                c.id, c.offset, c.size
+            );
+        }
+        self.metadata.text(&mut s);
+
+        s
+    }
+
+    #[cfg(feature = "json")]
+    fn json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl Metadata {
+    /// Parses the contents of a metadata chunk, ignoring chunks that fail to parse.
+    fn add(&mut self, id: [u8; 4], contents: &[u8]) {
+        match &id {
+            b"bext" => self.bext = Bext::try_from(contents).ok(),
+            b"cart" => self.cart = Cart::try_from(contents).ok(),
+            b"iXML" => self.ixml = Ixml::try_from(contents).ok().map(|i| IxmlInfo::from(&i)),
+            b"cue " => self.cue = Cue::try_from(contents).ok(),
+            b"LIST" if contents.starts_with(b"INFO") => self.info = info_entries(&contents[4..]),
+            _ => {}
+        }
+    }
+
+    fn text(&self, s: &mut String) {
+        if let Some(bext) = &self.bext {
+            let _ = writeln!(s, "  Broadcast extension:");
+            field(s, "Description", &bext.description);
+            field(s, "Originator", &bext.originator);
+            field(s, "Reference", &bext.originator_reference);
+            field(
+                s,
+                "Origination",
+                format!("{} {}", bext.origination_date, bext.origination_time).trim(),
+            );
+            field(s, "Time ref.", &bext.time_reference.to_string());
+            field(s, "Version", &bext.version.to_string());
+            let loudness = [
+                ("Loudness", bext.loudness_value, "LUFS"),
+                ("Range", bext.loudness_range, "LU"),
+                ("True peak", bext.max_true_peak_level, "dBTP"),
+                ("Momentary", bext.max_momentary_loudness, "LUFS"),
+                ("Short-term", bext.max_short_term_loudness, "LUFS"),
+            ];
+            for (label, value, unit) in loudness {
+                if bext.version >= 2 && value != Bext::UNSET_LOUDNESS {
+                    let value = format!("{:.2} {unit}", f64::from(value) / 100.0);
+                    field(s, label, &value);
+                }
+            }
+            for line in bext.coding_history.lines() {
+                field(s, "History", line);
+            }
+        }
+
+        if let Some(cart) = &self.cart {
+            let _ = writeln!(s, "  Cart:");
+            field(s, "Title", &cart.title);
+            field(s, "Artist", &cart.artist);
+            field(s, "Cut ID", &cart.cut_id);
+            field(s, "Client ID", &cart.client_id);
+            field(s, "Category", &cart.category);
+            field(s, "Out cue", &cart.out_cue);
+            field(
+                s,
+                "Airs from",
+                format!("{} {}", cart.start_date, cart.start_time).trim(),
+            );
+            field(
+                s,
+                "Airs until",
+                format!("{} {}", cart.end_date, cart.end_time).trim(),
+            );
+            for timer in &cart.post_timers {
+                let usage = String::from_utf8_lossy(&timer.usage);
+                field(s, "Timer", &format!("{usage} at frame {}", timer.value));
+            }
+            field(s, "URL", &cart.url);
+        }
+
+        if let Some(ixml) = &self.ixml {
+            let _ = writeln!(s, "  iXML:");
+            let texts = [
+                ("Project", &ixml.project),
+                ("Scene", &ixml.scene),
+                ("Take", &ixml.take),
+                ("Tape", &ixml.tape),
+                ("Note", &ixml.note),
+            ];
+            for (label, text) in texts {
+                field(s, label, text.as_deref().unwrap_or_default());
+            }
+            if let Some(circled) = ixml.circled {
+                field(s, "Circled", if circled { "yes" } else { "no" });
+            }
+            for track in &ixml.tracks {
+                let track = format!("{} {}", track.interleave_index, track.name);
+                field(s, "Track", &track);
+            }
+        }
+
+        if !self.info.is_empty() {
+            let _ = writeln!(s, "  Info:");
+            for entry in &self.info {
+                field(s, &entry.id, &entry.text);
+            }
+        }
+
+        if let Some(cue) = &self.cue {
+            let _ = writeln!(s, "  Cue points:");
+            for point in &cue.points {
+                let position = format!("frame {}", point.sample_offset);
+                field(s, &point.id.to_string(), &position);
+            }
+        }
+    }
+}
+
+/// Writes a field of a metadata chunk, unless its value is empty.
+fn field(s: &mut String, label: &str, value: &str) {
+    if !value.is_empty() {
+        let _ = writeln!(s, "    {:<13} {value}", format!("{label}:"));
+    }
+}
+
+/// Parses the entries of a `"LIST"` chunk of type `"INFO"`, each holding text ended by NUL bytes.
+fn info_entries(mut list: &[u8]) -> Vec<InfoEntry> {
+    let mut entries = Vec::new();
+    while list.len() >= 8 {
+        let len = u32::from_le_bytes([list[4], list[5], list[6], list[7]]);
+        let Some(text) = usize::try_from(len)
+            .ok()
+            .and_then(|len| list.get(8..8 + len))
+        else {
+            break;
+        };
+        let end = text.iter().position(|&b| b == 0).unwrap_or(text.len());
+        entries.push(InfoEntry {
+            id: String::from_utf8_lossy(&list[0..4]).into_owned(),
+            text: String::from_utf8_lossy(&text[..end]).into_owned(),
+        });
+        list = list
+            .get(8 + text.len() + text.len() % 2..)
+            .unwrap_or_default();
+    }
+
+    entries
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn duration(frames: u64, sampling_rate: u32) -> f64 {
+    if sampling_rate == 0 {
+        0.0
+    } else {
+        frames as f64 / f64::from(sampling_rate)
+    }
+}
+
+fn format_name(tag: u16) -> &'static str {
+    match tag {
+        WAV_FORMAT_PCM => "PCM",
+        WAV_FORMAT_IEEE_FLOAT => "IEEE float",
+        WAV_FORMAT_EXTENSIBLE => "Extensible",
+        _ => "Unknown",
+    }
+}
+
+fn main() {
+    let mut json = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    }
+    if json && cfg!(not(feature = "json")) {
+        eprintln!("wavinfo: --json needs wavinfo to be built with the `json` feature");
+        process::exit(2);
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        match Info::read(path) {
+            #[cfg(feature = "json")]
+            Ok(info) if json => println!("{}", info.json()),
+            Ok(info) => print!("{}", info.text()),
+            Err(e) => {
+                eprintln!("wavinfo: {path}: {e}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
@@ -1,19 +1,214 @@
 //! Contains items for dealing with the `"data"` chunk of wave files.
 
-use std::convert::TryFrom;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use crate::{
+    header::{Header, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM},
+    tuple_iterator::{PairIter, QuadrupletIter, TripletIter},
+};
 
 /// The supported bit-depths and containers for the samples at each depth.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum BitDepth {
     Eight(Vec<u8>),
     Sixteen(Vec<i16>),
     TwentyFour(Vec<i32>),
     ThirtyTwoFloat(Vec<f32>),
+    #[default]
     Empty,
 }
 
 impl BitDepth {
+    /// Decodes the raw little-endian bytes of a `"data"` chunk into samples as described by
+    /// `header`. Trailing bytes that don't make up a whole sample are ignored.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the header specifies an unsupported data format or bit-depth.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use wav::{BitDepth, Header, WAV_FORMAT_PCM};
+    ///
+    /// let header = Header::new(WAV_FORMAT_PCM, 1, 48_000, 16);
+    /// let data = BitDepth::decode(&header, &[0x01, 0x00, 0xFF, 0xFF]).unwrap();
+    /// assert_eq!(data, BitDepth::Sixteen(vec![1, -1]));
+    /// ```
+    pub fn decode(header: &Header, bytes: &[u8]) -> Result<Self, &'static str> {
+        match header.effective_format() {
+            WAV_FORMAT_PCM => match header.bits_per_sample {
+                8 => Ok(BitDepth::Eight(bytes.to_vec())),
+                16 => Ok(BitDepth::Sixteen({
+                    let mut tmpv = Vec::with_capacity(bytes.len() / 2);
+                    tmpv.extend(
+                        bytes
+                            .chunks_exact(2)
+                            .map(|i| i16::from_le_bytes([i[0], i[1]])),
+                    );
+                    tmpv
+                })),
+                24 => Ok(BitDepth::TwentyFour({
+                    let mut tmpv = Vec::with_capacity(bytes.len() / 3);
+                    tmpv.extend(
+                        bytes
+                            .chunks_exact(3)
+                            .map(|i| i32::from_le_bytes([0, i[0], i[1], i[2]])),
+                    );
+                    tmpv
+                })),
+                _ => Err("Unsupported PCM bit depth"),
+            },
+            WAV_FORMAT_IEEE_FLOAT => match header.bits_per_sample {
+                32 => Ok(BitDepth::ThirtyTwoFloat({
+                    let mut tmpv = Vec::with_capacity(bytes.len() / 4);
+                    tmpv.extend(
+                        bytes
+                            .chunks_exact(4)
+                            .map(|f| f32::from_le_bytes([f[0], f[1], f[2], f[3]])),
+                    );
+                    tmpv
+                })),
+                _ => Err("Unsupported IEEE Float bit depth"),
+            },
+            _ => Err("Unsupported WAV format"),
+        }
+    }
+
+    /// Like [`Self::decode`], but reuses the buffer for 8-bit samples instead of copying it.
+    #[cfg(feature = "std")]
+    pub(crate) fn decode_owned(header: &Header, bytes: Vec<u8>) -> Result<Self, &'static str> {
+        if header.effective_format() == WAV_FORMAT_PCM && header.bits_per_sample == 8 {
+            Ok(BitDepth::Eight(bytes))
+        } else {
+            Self::decode(header, &bytes)
+        }
+    }
+
+    /// Encodes the samples into the raw little-endian bytes of a `"data"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the bit depth is [`Self::Empty`].
+    pub fn encode(&self) -> Result<Vec<u8>, &'static str> {
+        let d_vec = match self {
+            BitDepth::Eight(v) => v.clone(),
+            BitDepth::Sixteen(v) => v
+                .iter()
+                .flat_map(|s| {
+                    let v = s.to_le_bytes();
+                    PairIter::new((v[0], v[1]))
+                })
+                .collect::<Vec<_>>(),
+            BitDepth::TwentyFour(v) => v
+                .iter()
+                .flat_map(|s| {
+                    let v = s.to_le_bytes();
+                    TripletIter::new((v[1], v[2], v[3]))
+                })
+                .collect::<Vec<_>>(),
+            BitDepth::ThirtyTwoFloat(v) => v
+                .iter()
+                .flat_map(|s| {
+                    let v = s.to_le_bytes();
+                    QuadrupletIter::new((v[0], v[1], v[2], v[3]))
+                })
+                .collect::<Vec<_>>(),
+            BitDepth::Empty => return Err("Empty audio data given"),
+        };
+
+        Ok(d_vec)
+    }
+
+    /// Converts the samples to the given format.
+    ///
+    /// Integer samples are converted by shifting, so that widening is lossless and narrowing
+    /// truncates. Float samples are scaled so that `-1.0..1.0` spans the full integer range, and
+    /// rounded and clamped when converted to integers. [`Self::Empty`] stays empty.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use wav::{BitDepth, SampleFormat};
+    ///
+    /// let data = BitDepth::Eight(vec![0, 128, 255]);
+    /// assert_eq!(
+    ///     data.convert(SampleFormat::Sixteen),
+    ///     BitDepth::Sixteen(vec![-32768, 0, 32512])
+    /// );
+    /// ```
+    #[must_use]
+    // Every value cast below has already been shifted or clamped into the range of its target type
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn convert(&self, format: SampleFormat) -> BitDepth {
+        if self.sample_format() == Some(format) {
+            return self.clone();
+        }
+
+        match (self, format) {
+            (BitDepth::Empty, _) => BitDepth::Empty,
+            (BitDepth::ThirtyTwoFloat(v), SampleFormat::Eight) => BitDepth::Eight(
+                v.iter()
+                    .map(|&s| (float_to_int(s, 7) + 128) as u8)
+                    .collect(),
+            ),
+            (BitDepth::ThirtyTwoFloat(v), SampleFormat::Sixteen) => {
+                BitDepth::Sixteen(v.iter().map(|&s| float_to_int(s, 15) as i16).collect())
+            }
+            (BitDepth::ThirtyTwoFloat(v), SampleFormat::TwentyFour) => {
+                BitDepth::TwentyFour(v.iter().map(|&s| float_to_int(s, 23) << 8).collect())
+            }
+            (_, SampleFormat::ThirtyTwoFloat) => BitDepth::ThirtyTwoFloat(
+                self.left_justified()
+                    // Integer samples have at most 24 significant bits, which `f32` holds exactly
+                    .map(|s| (f64::from(s) / f64::from(1u32 << 31)) as f32)
+                    .collect(),
+            ),
+            (_, SampleFormat::Eight) => BitDepth::Eight(
+                self.left_justified()
+                    .map(|s| ((s >> 24) + 128) as u8)
+                    .collect(),
+            ),
+            (_, SampleFormat::Sixteen) => {
+                BitDepth::Sixteen(self.left_justified().map(|s| (s >> 16) as i16).collect())
+            }
+            (_, SampleFormat::TwentyFour) => {
+                BitDepth::TwentyFour(self.left_justified().map(|s| s & !0xFF).collect())
+            }
+        }
+    }
+
+    /// Returns the integer samples scaled to the full range of `i32`.
+    fn left_justified(&self) -> impl Iterator<Item = i32> + '_ {
+        let (eight, sixteen, twenty_four): (&[u8], &[i16], &[i32]) = match self {
+            BitDepth::Eight(v) => (v, &[], &[]),
+            BitDepth::Sixteen(v) => (&[], v, &[]),
+            BitDepth::TwentyFour(v) => (&[], &[], v),
+            BitDepth::ThirtyTwoFloat(_) | BitDepth::Empty => (&[], &[], &[]),
+        };
+
+        eight
+            .iter()
+            .map(|&s| (i32::from(s) - 128) << 24)
+            .chain(sixteen.iter().map(|&s| i32::from(s) << 16))
+            .chain(twenty_four.iter().copied())
+    }
+
+    /// Returns the format of the samples, or `None` if the bit depth is [`Self::Empty`].
+    #[must_use]
+    pub fn sample_format(&self) -> Option<SampleFormat> {
+        match self {
+            Self::Eight(..) => Some(SampleFormat::Eight),
+            Self::Sixteen(..) => Some(SampleFormat::Sixteen),
+            Self::TwentyFour(..) => Some(SampleFormat::TwentyFour),
+            Self::ThirtyTwoFloat(..) => Some(SampleFormat::ThirtyTwoFloat),
+            Self::Empty => None,
+        }
+    }
+
     /// Returns `true` if the bit depth is [`Self::Eight`].
     #[must_use]
     pub fn is_eight(&self) -> bool {
@@ -137,9 +332,37 @@ impl BitDepth {
     }
 }
 
-impl Default for BitDepth {
-    fn default() -> Self {
-        BitDepth::Empty
+/// The formats of samples supported by [`BitDepth`], without the samples themselves.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum SampleFormat {
+    Eight,
+    Sixteen,
+    TwentyFour,
+    ThirtyTwoFloat,
+}
+
+impl SampleFormat {
+    /// Returns the format tag of the samples, either [`WAV_FORMAT_PCM`] or
+    /// [`WAV_FORMAT_IEEE_FLOAT`].
+    #[must_use]
+    pub fn format_tag(self) -> u16 {
+        match self {
+            Self::ThirtyTwoFloat => WAV_FORMAT_IEEE_FLOAT,
+            _ => WAV_FORMAT_PCM,
+        }
+    }
+
+    /// Returns the number of bits in each sample.
+    #[must_use]
+    pub fn bits_per_sample(self) -> u16 {
+        match self {
+            Self::Eight => 8,
+            Self::Sixteen => 16,
+            Self::TwentyFour => 24,
+            Self::ThirtyTwoFloat => 32,
+        }
     }
 }
 
@@ -226,3 +449,12 @@ impl TryFrom<BitDepth> for Vec<f32> {
         }
     }
 }
+
+/// Scales a float sample to a signed integer of `bits + 1` bits, rounding and clamping it.
+#[allow(clippy::cast_possible_truncation)]
+fn float_to_int(sample: f32, bits: u32) -> i32 {
+    let max = f64::from(1u32 << bits);
+    let scaled = (f64::from(sample) * max).clamp(-max, max - 1.0);
+    // Rounds half away from zero, as the cast truncates towards zero and `f64::round` needs `std`
+    (scaled + 0.5f64.copysign(scaled)) as i32
+}
@@ -0,0 +1,446 @@
+//! Contains items for dealing with the speaker positions of channels, and for mixing channels into
+//! other layouts.
+//!
+//! ## Example
+//!
+//! ```
+//! use wav::{
+//!     channels::{ChannelLayout, Matrix},
+//!     BitDepth, HeaderBuilder, SampleFormat,
+//! };
+//!
+//! let header = HeaderBuilder::new(SampleFormat::Sixteen)
+//!     .channel_count(6)
+//!     .build()
+//!     .unwrap();
+//! let data = BitDepth::Sixteen(vec![1_000; 6 * 480]);
+//!
+//! let from = ChannelLayout::from_header(&header).unwrap();
+//! let matrix = Matrix::downmix(from, ChannelLayout::STEREO).unwrap();
+//! let (stereo, data) = matrix.apply(&header, &data).unwrap();
+//! assert_eq!(stereo.channel_count, 2);
+//! ```
+
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+
+use crate::{
+    header::{default_channel_mask, WAV_FORMAT_EXTENSIBLE},
+    BitDepth, Header, Sample,
+};
+
+/// Gain of -3dB, used when a channel is spread over two speakers or folded into a farther one.
+const MINUS_3DB: f32 = core::f32::consts::FRAC_1_SQRT_2;
+
+/// The speaker positions of `dwChannelMask`, in the order their channels appear in the data.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Speaker {
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    LowFrequency,
+    BackLeft,
+    BackRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    BackCenter,
+    SideLeft,
+    SideRight,
+    TopCenter,
+    TopFrontLeft,
+    TopFrontCenter,
+    TopFrontRight,
+    TopBackLeft,
+    TopBackCenter,
+    TopBackRight,
+}
+
+impl Speaker {
+    /// Every speaker position, in the order of their bits in `dwChannelMask`.
+    pub const ALL: [Speaker; 18] = [
+        Speaker::FrontLeft,
+        Speaker::FrontRight,
+        Speaker::FrontCenter,
+        Speaker::LowFrequency,
+        Speaker::BackLeft,
+        Speaker::BackRight,
+        Speaker::FrontLeftOfCenter,
+        Speaker::FrontRightOfCenter,
+        Speaker::BackCenter,
+        Speaker::SideLeft,
+        Speaker::SideRight,
+        Speaker::TopCenter,
+        Speaker::TopFrontLeft,
+        Speaker::TopFrontCenter,
+        Speaker::TopFrontRight,
+        Speaker::TopBackLeft,
+        Speaker::TopBackCenter,
+        Speaker::TopBackRight,
+    ];
+
+    /// Returns the bit of the speaker in `dwChannelMask`.
+    #[must_use]
+    pub fn mask(self) -> u32 {
+        1 << self as u32
+    }
+
+    /// Returns where the speaker is folded to when the target layout doesn't have it, as groups
+    /// of speakers with their gains. The first group whose speakers are all in the target layout
+    /// is used, and the last group is folded further if needed.
+    fn fold(self) -> &'static [&'static [(Speaker, f32)]] {
+        use Speaker::{
+            BackCenter, BackLeft, BackRight, FrontCenter, FrontLeft, FrontLeftOfCenter, FrontRight,
+            FrontRightOfCenter, SideLeft, SideRight, TopBackCenter, TopBackLeft, TopBackRight,
+            TopCenter, TopFrontCenter, TopFrontLeft, TopFrontRight,
+        };
+
+        match self {
+            FrontLeft | FrontRight | TopCenter | TopFrontCenter => &[&[(FrontCenter, MINUS_3DB)]],
+            FrontCenter => &[&[(FrontLeft, MINUS_3DB), (FrontRight, MINUS_3DB)]],
+            Speaker::LowFrequency => &[],
+            BackLeft => &[&[(SideLeft, 1.0)], &[(FrontLeft, MINUS_3DB)]],
+            BackRight => &[&[(SideRight, 1.0)], &[(FrontRight, MINUS_3DB)]],
+            SideLeft => &[&[(BackLeft, 1.0)], &[(FrontLeft, MINUS_3DB)]],
+            SideRight => &[&[(BackRight, 1.0)], &[(FrontRight, MINUS_3DB)]],
+            FrontLeftOfCenter => &[
+                &[(FrontLeft, MINUS_3DB), (FrontCenter, MINUS_3DB)],
+                &[(FrontLeft, 1.0)],
+            ],
+            FrontRightOfCenter => &[
+                &[(FrontRight, MINUS_3DB), (FrontCenter, MINUS_3DB)],
+                &[(FrontRight, 1.0)],
+            ],
+            BackCenter => &[
+                &[(BackLeft, MINUS_3DB), (BackRight, MINUS_3DB)],
+                &[(SideLeft, MINUS_3DB), (SideRight, MINUS_3DB)],
+            ],
+            TopFrontLeft => &[&[(FrontLeft, MINUS_3DB)]],
+            TopFrontRight => &[&[(FrontRight, MINUS_3DB)]],
+            TopBackLeft => &[&[(BackLeft, MINUS_3DB)]],
+            TopBackRight => &[&[(BackRight, MINUS_3DB)]],
+            TopBackCenter => &[&[(BackCenter, MINUS_3DB)]],
+        }
+    }
+}
+
+/// The speaker positions of the channels of a wave file, as given by `dwChannelMask`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelLayout {
+    mask: u32,
+}
+
+impl ChannelLayout {
+    /// A single front center channel.
+    pub const MONO: ChannelLayout = ChannelLayout { mask: 0x4 };
+    /// Front left and front right.
+    pub const STEREO: ChannelLayout = ChannelLayout { mask: 0x3 };
+    /// Front left, front right, front center, low frequency, back left and back right.
+    pub const SURROUND_5_1: ChannelLayout = ChannelLayout { mask: 0x3F };
+    /// [`Self::SURROUND_5_1`] with side left and side right.
+    pub const SURROUND_7_1: ChannelLayout = ChannelLayout { mask: 0x63F };
+
+    /// Creates a layout from a `dwChannelMask`, ignoring its reserved bits.
+    #[must_use]
+    pub fn from_mask(mask: u32) -> Self {
+        ChannelLayout {
+            mask: mask & 0x3_FFFF,
+        }
+    }
+
+    /// Creates a layout from the given speakers, in any order.
+    #[must_use]
+    pub fn from_speakers(speakers: &[Speaker]) -> Self {
+        ChannelLayout {
+            mask: speakers.iter().fold(0, |mask, s| mask | s.mask()),
+        }
+    }
+
+    /// Returns the conventional layout for the given channel count, which is also the one chosen
+    /// by [`crate::HeaderBuilder`], or `None` if there isn't one.
+    #[must_use]
+    pub fn for_channel_count(channel_count: u16) -> Option<Self> {
+        match default_channel_mask(channel_count) {
+            0 => None,
+            mask => Some(ChannelLayout { mask }),
+        }
+    }
+
+    /// Returns the layout of the channels described by the header, or `None` if it is unknown.
+    ///
+    /// The layout is taken from the channel mask of [`WAV_FORMAT_EXTENSIBLE`] headers, and from
+    /// [`Self::for_channel_count`] otherwise. Channel masks that don't assign a position to every
+    /// channel are treated as unknown.
+    #[must_use]
+    pub fn from_header(header: &Header) -> Option<Self> {
+        let layout = match header.extension {
+            Some(ext) if header.audio_format == WAV_FORMAT_EXTENSIBLE => {
+                Self::from_mask(ext.channel_mask)
+            }
+            _ => Self::for_channel_count(header.channel_count)?,
+        };
+
+        if layout.channel_count() == header.channel_count {
+            Some(layout)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `dwChannelMask` of the layout.
+    #[must_use]
+    pub fn mask(self) -> u32 {
+        self.mask
+    }
+
+    /// Returns the number of channels in the layout.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn channel_count(self) -> u16 {
+        // The mask has at most 18 bits set
+        self.mask.count_ones() as u16
+    }
+
+    /// Returns the speakers of the layout, in the order of their channels.
+    pub fn speakers(self) -> impl Iterator<Item = Speaker> {
+        Speaker::ALL
+            .iter()
+            .copied()
+            .filter(move |s| self.contains(*s))
+    }
+
+    /// Returns `true` if the layout has a channel for the given speaker.
+    #[must_use]
+    pub fn contains(self, speaker: Speaker) -> bool {
+        self.mask & speaker.mask() != 0
+    }
+
+    /// Returns the index of the channel of the given speaker, or `None` if the layout doesn't have
+    /// it.
+    #[must_use]
+    pub fn index_of(self, speaker: Speaker) -> Option<usize> {
+        self.speakers().position(|s| s == speaker)
+    }
+}
+
+/// A matrix of gains mixing the channels of each frame into new channels.
+///
+/// Every output channel is the sum of the input channels, each multiplied by its gain.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix {
+    inputs: u16,
+    outputs: u16,
+    gains: Vec<f32>,
+    layout: Option<ChannelLayout>,
+}
+
+impl Matrix {
+    /// Creates a matrix from the gains of every input channel for every output channel, so that
+    /// `gains[output * inputs + input]` is the gain of `input` in `output`.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if either channel count is zero, or if `gains` doesn't hold exactly
+    /// `outputs * inputs` gains.
+    pub fn new(inputs: u16, outputs: u16, gains: Vec<f32>) -> Result<Self, &'static str> {
+        if inputs == 0 || outputs == 0 {
+            return Err("Channel count must not be zero");
+        }
+        if gains.len() != usize::from(inputs) * usize::from(outputs) {
+            return Err("Gain count doesn't match the channel counts");
+        }
+
+        Ok(Matrix {
+            inputs,
+            outputs,
+            gains,
+            layout: None,
+        })
+    }
+
+    /// Creates a matrix mixing the channels of the layout `from` into the layout `to`, following
+    /// ITU-R BS.775 for the standard downmixes.
+    ///
+    /// Speakers found in both layouts are kept as-is, and every other speaker is folded into the
+    /// nearest speakers of `to` at -3dB, e.g. the center into left and right and the surrounds
+    /// into their front side. The low frequency channel is dropped if `to` doesn't have one.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if either layout is empty, or if `to` has none of the speakers a
+    /// speaker of `from` can be folded into.
+    pub fn downmix(from: ChannelLayout, to: ChannelLayout) -> Result<Self, &'static str> {
+        let (inputs, outputs) = (from.channel_count(), to.channel_count());
+        let mut matrix = Matrix::new(
+            inputs,
+            outputs,
+            vec![0.0; usize::from(inputs) * usize::from(outputs)],
+        )?;
+
+        for (input, speaker) in from.speakers().enumerate() {
+            matrix.fold(input, speaker, 1.0, to, 0)?;
+        }
+        matrix.layout = Some(to);
+
+        Ok(matrix)
+    }
+
+    /// Adds `speaker`, read from the channel `input`, at the given gain into the speakers of `to`.
+    fn fold(
+        &mut self,
+        input: usize,
+        speaker: Speaker,
+        gain: f32,
+        to: ChannelLayout,
+        depth: u32,
+    ) -> Result<(), &'static str> {
+        if let Some(output) = to.index_of(speaker) {
+            self.gains[output * usize::from(self.inputs) + input] += gain;
+            return Ok(());
+        }
+
+        let groups = speaker.fold();
+        let group = groups
+            .iter()
+            .find(|group| group.iter().all(|(s, _)| to.contains(*s)))
+            .or_else(|| groups.last());
+        let Some(group) = group else {
+            // Only the low frequency channel has nowhere to go, and is dropped
+            return Ok(());
+        };
+        if depth > 3 {
+            return Err("Layout has no speakers to downmix into");
+        }
+
+        for &(target, g) in *group {
+            self.fold(input, target, gain * g, to, depth + 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a matrix extracting the input `channels`, in the given order, out of frames of
+    /// `inputs` channels. Channels can be reordered, repeated and left out.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `channels` is empty or holds an index out of range.
+    pub fn select(inputs: u16, channels: &[u16]) -> Result<Self, &'static str> {
+        let outputs = u16::try_from(channels.len()).map_err(|_| "Too many channels")?;
+        let mut matrix = Matrix::new(
+            inputs,
+            outputs,
+            vec![0.0; usize::from(inputs) * channels.len()],
+        )?;
+
+        for (output, &input) in channels.iter().enumerate() {
+            if input >= inputs {
+                return Err("Channel index out of range");
+            }
+            matrix.gains[output * usize::from(inputs) + usize::from(input)] = 1.0;
+        }
+
+        Ok(matrix)
+    }
+
+    /// Returns the number of channels mixed from.
+    #[must_use]
+    pub fn inputs(&self) -> u16 {
+        self.inputs
+    }
+
+    /// Returns the number of channels mixed into.
+    #[must_use]
+    pub fn outputs(&self) -> u16 {
+        self.outputs
+    }
+
+    /// Returns the gain of the channel `input` in the channel `output`.
+    #[must_use]
+    pub fn gain(&self, output: u16, input: u16) -> f32 {
+        self.gains[usize::from(output) * usize::from(self.inputs) + usize::from(input)]
+    }
+
+    /// Returns the layout of the output channels, if known.
+    #[must_use]
+    pub fn layout(&self) -> Option<ChannelLayout> {
+        self.layout
+    }
+
+    /// Mixes interleaved `f32` frames, ignoring a trailing partial frame.
+    #[must_use]
+    pub fn mix(&self, samples: &[f32]) -> Vec<f32> {
+        let inputs = usize::from(self.inputs);
+        let mut out = Vec::with_capacity(samples.len() / inputs * usize::from(self.outputs));
+
+        for frame in samples.chunks_exact(inputs) {
+            for gains in self.gains.chunks_exact(inputs) {
+                out.push(gains.iter().zip(frame).map(|(g, s)| g * s).sum());
+            }
+        }
+
+        out
+    }
+
+    /// Returns `header` changed to describe the output channels, keeping its sample format.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the header's channel count doesn't match the inputs of the matrix,
+    /// if its format is unsupported, or if the new header can't be built.
+    pub fn header(&self, header: &Header) -> Result<Header, &'static str> {
+        if header.channel_count != self.inputs {
+            return Err("Channel count doesn't match the matrix");
+        }
+        let format = header
+            .sample_format()
+            .ok_or("Unsupported data format or bit depth")?;
+
+        let mut builder = Header::builder(format)
+            .channel_count(self.outputs)
+            .sampling_rate(header.sampling_rate);
+        if let Some(ext) = header.extension {
+            // The speaker positions of selected channels are unknown
+            builder = builder
+                .extensible(true)
+                .valid_bits_per_sample(ext.valid_bits_per_sample)
+                .channel_mask(self.layout.map_or(0, ChannelLayout::mask));
+        } else if let Some(layout) = self.layout {
+            // Plain headers imply the conventional layout, others need the extension
+            if layout.mask() != default_channel_mask(self.outputs) {
+                builder = builder.channel_mask(layout.mask());
+            }
+        }
+
+        builder
+            .build()
+            .map_err(|_| "Can't build a header for the output channels")
+    }
+
+    /// Mixes all of `data`, returning the updated header and the samples in their original
+    /// format.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`Matrix::header`].
+    pub fn apply(
+        &self,
+        header: &Header,
+        data: &BitDepth,
+    ) -> Result<(Header, BitDepth), &'static str> {
+        let new_header = self.header(header)?;
+        let Some(format) = data.sample_format() else {
+            return Ok((new_header, BitDepth::Empty));
+        };
+
+        let samples = f32::from_bit_depth(data);
+
+        Ok((
+            new_header,
+            BitDepth::ThirtyTwoFloat(self.mix(&samples)).convert(format),
+        ))
+    }
+}
@@ -0,0 +1,537 @@
+//! Contains items for dealing with the chunks of RIFF data.
+//!
+//! RIFF requires every chunk with an odd length to be followed by a pad byte, which isn't counted
+//! in the chunk's length. The pad byte is always written, and skipped when reading. Files whose
+//! writer forgot the pad byte are detected by checking which of the two possible positions of the
+//! following chunk holds the more plausible chunk header, one with a printable or known ID whose
+//! size fits in the RIFF data.
+//!
+//! RF64 and BW64 files, which replace the `"RIFF"` ID to hold more than 4 GiB, are read too. Their
+//! first chunk, `"ds64"`, holds the 64-bit sizes of the RIFF data and of every chunk whose 32-bit
+//! size is `0xFFFFFFFF`.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+use crate::{Header, SampleFormat, Warning};
+
+/// A chunk with its raw contents, used for chunks other than `"fmt "` and `"data"`.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chunk {
+    /// The chunk ID, also known as `FourCC`.
+    pub id: [u8; 4],
+    /// The contents of the chunk, excluding any pad byte.
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// Creates a new chunk with the given ID and contents.
+    #[must_use]
+    pub fn new(id: [u8; 4], data: Vec<u8>) -> Self {
+        Chunk { id, data }
+    }
+}
+
+/// Location of a chunk within the stream, as listed by [`crate::list_chunks`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChunkHeader {
+    /// The chunk ID, also known as `FourCC`.
+    pub id: [u8; 4],
+    /// Offset of the chunk, including its 8-byte header, from the start of the stream.
+    pub offset: u64,
+    /// Number of bytes in the chunk's contents, excluding any pad byte.
+    pub len: u64,
+}
+
+impl ChunkHeader {
+    /// Returns the offset of the chunk's contents from the start of the stream.
+    #[must_use]
+    pub fn data_offset(&self) -> u64 {
+        self.offset + 8
+    }
+
+    /// Reads the entirety of the contents of the chunk from the stream it was found in, such as
+    /// one listed by [`crate::list_chunks`].
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the chunk extends past the end of the stream, if its contents don't
+    /// fit in memory, or if any error occurs from the `reader` during reading.
+    pub fn read_contents<R>(&self, reader: &mut R) -> io::Result<Vec<u8>>
+    where
+        R: Read + Seek,
+    {
+        let stream_len = reader.seek(SeekFrom::End(0))?;
+        let mut data = vec![0; self.checked_len(stream_len)?];
+
+        reader.seek(SeekFrom::Start(self.data_offset()))?;
+        reader.read_exact(&mut data)?;
+
+        Ok(data)
+    }
+
+    /// Returns the length of the contents, checking that they fit in memory and in a stream of
+    /// `stream_len` bytes.
+    pub(crate) fn checked_len(&self, stream_len: u64) -> io::Result<usize> {
+        if self.data_offset() + self.len > stream_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Chunk extends past the end of the stream",
+            ));
+        }
+
+        usize::try_from(self.len)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Chunk does not fit in memory"))
+    }
+}
+
+/// Checks that the stream holds RIFF `"WAVE"` data and lists its top-level chunks.
+///
+/// With `recover` set, the RIFF size and chunk sizes are clamped to the real length of the stream,
+/// and a `"data"` chunk size of `0xFFFFFFFF` is treated as extending to the end of the stream, as
+/// written by recorders that never finalized the file. So is a size of 0, unless a valid chunk
+/// header follows the `"data"` chunk, in which case it is really empty. Everything fixed that way
+/// is reported in `warnings`.
+///
+/// RF64 and BW64 files are read with the sizes of their `"ds64"` chunk.
+pub(crate) fn walk<R>(
+    reader: &mut R,
+    recover: bool,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<Vec<ChunkHeader>>
+where
+    R: Read + Seek,
+{
+    let stream_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let mut riff = [0; 12];
+    reader.read_exact(&mut riff)?;
+    let mut walker = Walker::new(&riff, stream_len, recover, warnings)?;
+
+    while let Some(pos) = walker.next_chunk() {
+        let mut header = [0; 8];
+        reader.seek(SeekFrom::Start(pos))?;
+        reader.read_exact(&mut header)?;
+
+        if let Some((pos, len)) = walker.chunk(header, warnings) {
+            let mut bytes = vec![0; len];
+            reader.seek(SeekFrom::Start(pos))?;
+            reader.read_exact(&mut bytes)?;
+            walker.probe(&bytes, warnings)?;
+        }
+    }
+
+    Ok(walker.chunks)
+}
+
+/// What the bytes following the last chunk are checked for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Probe {
+    /// Whether the chunk of odd length is followed by its pad byte.
+    Pad,
+    /// Whether the `"data"` chunk of size 0 is followed by a valid chunk header, or has an unknown
+    /// size.
+    EmptyData,
+    /// The sizes held by the `"ds64"` chunk of an RF64 or BW64 file.
+    Ds64,
+}
+
+/// The I/O-free logic of [`walk`], fed with the bytes it asks for so that it can be shared between
+/// the blocking and async readers.
+pub(crate) struct Walker {
+    recover: bool,
+    stream_len: u64,
+    end: u64,
+    pos: u64,
+    probe: Probe,
+    /// Whether the stream is an RF64 or BW64 file, whose sizes are in its `"ds64"` chunk.
+    large: bool,
+    /// The size of the `"data"` chunk and of other chunks from the `"ds64"` chunk.
+    large_sizes: Vec<([u8; 4], u64)>,
+    pub(crate) chunks: Vec<ChunkHeader>,
+}
+
+impl Walker {
+    /// Checks the 12-byte RIFF header at the start of a stream of `stream_len` bytes.
+    pub(crate) fn new(
+        riff: &[u8; 12],
+        stream_len: u64,
+        recover: bool,
+        warnings: &mut Vec<Warning>,
+    ) -> io::Result<Self> {
+        let large = riff[0..4] == *b"RF64" || riff[0..4] == *b"BW64";
+        if riff[0..4] != *b"RIFF" && !large {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Data isn't RIFF data",
+            ));
+        }
+        if riff[8..12] != *b"WAVE" {
+            return Err(io::Error::other("RIFF file type not \"WAVE\""));
+        }
+
+        let mut walker = Walker {
+            recover,
+            stream_len,
+            end: stream_len,
+            pos: 12,
+            probe: Probe::Pad,
+            large,
+            large_sizes: Vec::new(),
+            chunks: Vec::new(),
+        };
+        // The size of RF64 and BW64 files is only known once their `"ds64"` chunk is read
+        if !large {
+            let riff_len = u32::from_le_bytes([riff[4], riff[5], riff[6], riff[7]]);
+            walker.set_riff_len(riff_len.into(), warnings);
+        }
+
+        Ok(walker)
+    }
+
+    /// Ends the walk at the end of the RIFF data of size `riff_len`, or at the end of the stream if
+    /// it comes first or if recovering.
+    fn set_riff_len(&mut self, riff_len: u64, warnings: &mut Vec<Warning>) {
+        let riff_end = riff_len.saturating_add(8);
+        self.end = if self.recover {
+            if riff_end != self.stream_len {
+                warnings.push(Warning::RiffSizeMismatch {
+                    declared: riff_len,
+                    actual: self.stream_len.saturating_sub(8),
+                });
+            }
+            self.stream_len
+        } else {
+            riff_end.min(self.stream_len)
+        };
+    }
+
+    /// Returns the offset of the next 8-byte chunk header to read, or `None` once every chunk has
+    /// been found.
+    pub(crate) fn next_chunk(&self) -> Option<u64> {
+        if self.pos + 8 <= self.end {
+            Some(self.pos)
+        } else {
+            None
+        }
+    }
+
+    /// Records the chunk whose header was read at the offset given by [`Self::next_chunk`].
+    ///
+    /// Returns the offset and number of bytes to pass to [`Self::probe`] if the chunk has an odd
+    /// length and might be missing its pad byte, if it is a `"data"` chunk of size 0 that might
+    /// have an unknown size, or if it is the `"ds64"` chunk of an RF64 or BW64 file.
+    pub(crate) fn chunk(
+        &mut self,
+        header: [u8; 8],
+        warnings: &mut Vec<Warning>,
+    ) -> Option<(u64, usize)> {
+        let id = [header[0], header[1], header[2], header[3]];
+        let declared = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let available = self.end - (self.pos + 8);
+        let large_size = if declared == u32::MAX {
+            self.large_sizes
+                .iter()
+                .find(|(large_id, _)| *large_id == id)
+                .map(|&(_, len)| len)
+        } else {
+            None
+        };
+
+        // An empty `"data"` chunk can only be told apart from an unknown size by what follows it
+        let empty_data = self.recover && id == *b"data" && declared == 0 && available >= 8;
+        let unknown_size = id == *b"data"
+            && large_size.is_none()
+            && (declared == u32::MAX || (declared == 0 && !empty_data));
+        let mut len = large_size.unwrap_or_else(|| declared.into());
+        if self.recover && (unknown_size || len > available) && len != available {
+            warnings.push(Warning::ChunkSizeClamped {
+                id,
+                declared,
+                recovered: available,
+            });
+            len = available;
+        }
+
+        self.chunks.push(ChunkHeader {
+            id,
+            offset: self.pos,
+            len,
+        });
+
+        let contents = self.pos + 8;
+        self.pos += 8 + len;
+        if self.large && id == *b"ds64" && self.chunks.len() == 1 {
+            self.probe = Probe::Ds64;
+            // Only the part of the contents within the RIFF data can be read
+            let len = usize::try_from(len.min(available)).unwrap_or(usize::MAX);
+            Some((contents, len))
+        } else if empty_data {
+            self.probe = Probe::EmptyData;
+            Some((self.pos, 8))
+        } else if len % 2 == 0 || self.pos >= self.end {
+            None
+        } else if self.pos + 9 > self.end {
+            // Too close to the end for another chunk to follow the pad byte
+            self.pos += 1;
+            None
+        } else {
+            self.probe = Probe::Pad;
+            Some((self.pos, 9))
+        }
+    }
+
+    /// Checks the bytes asked for by [`Self::chunk`], which follow the last chunk or are its
+    /// contents.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the bytes are the contents of a `"ds64"` chunk too short to hold
+    /// the sizes of the RIFF data and `"data"` chunk.
+    pub(crate) fn probe(&mut self, bytes: &[u8], warnings: &mut Vec<Warning>) -> io::Result<()> {
+        match self.probe {
+            Probe::Pad => self.pad(bytes, warnings),
+            Probe::EmptyData => self.empty_data(bytes, warnings),
+            Probe::Ds64 => return self.ds64(bytes, warnings),
+        }
+        Ok(())
+    }
+
+    /// Reads the contents of a `"ds64"` chunk: the 64-bit sizes of the RIFF data and `"data"`
+    /// chunk, the sample count, and a table of the sizes of other chunks.
+    fn ds64(&mut self, bytes: &[u8], warnings: &mut Vec<Warning>) -> io::Result<()> {
+        let (Some(riff_len), Some(data_len)) = (le_u64(bytes.get(0..8)), le_u64(bytes.get(8..16)))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "\"ds64\" chunk too short",
+            ));
+        };
+
+        let table_len = bytes
+            .get(24..28)
+            .map_or(0, |b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+        let table = bytes.get(28..).unwrap_or_default();
+        self.large_sizes = core::iter::once((*b"data", data_len))
+            .chain(
+                table
+                    .chunks_exact(12)
+                    .take(usize::try_from(table_len).unwrap_or(usize::MAX))
+                    .filter_map(|entry| {
+                        let len = le_u64(entry.get(4..12))?;
+                        Some(([entry[0], entry[1], entry[2], entry[3]], len))
+                    }),
+            )
+            .collect();
+        self.set_riff_len(riff_len, warnings);
+
+        Ok(())
+    }
+
+    /// Skips the pad byte after the last chunk, unless `bytes` show that it is missing, which is
+    /// assumed when the chunk header directly after the chunk is more plausible than the one after
+    /// the pad byte.
+    fn pad(&mut self, bytes: &[u8], warnings: &mut Vec<Warning>) {
+        let unpadded = self.header_score(self.pos, &bytes[0..8]);
+        let padded = self.header_score(self.pos + 1, &bytes[1..9]);
+        if unpadded > padded {
+            if let Some(chunk) = self.chunks.last() {
+                warnings.push(Warning::MissingPadByte { id: chunk.id });
+            }
+        } else {
+            self.pos += 1;
+        }
+    }
+
+    /// Extends the last chunk, a `"data"` chunk of size 0, to the end of the stream unless `bytes`
+    /// hold a valid chunk header.
+    fn empty_data(&mut self, bytes: &[u8], warnings: &mut Vec<Warning>) {
+        if self.header_score(self.pos, bytes) >= 2 {
+            return;
+        }
+
+        if let Some(chunk) = self.chunks.last_mut() {
+            chunk.len = self.end - chunk.data_offset();
+            warnings.push(Warning::ChunkSizeClamped {
+                id: chunk.id,
+                declared: 0,
+                recovered: chunk.len,
+            });
+        }
+        self.pos = self.end;
+    }
+
+    /// Rates how plausible it is that the 8 bytes of `bytes`, read at `offset`, are a chunk header:
+    /// 0 if the ID isn't printable, 1 if the contents don't end within the RIFF data, 2 if they do,
+    /// and 3 if the ID is also a known one.
+    fn header_score(&self, offset: u64, bytes: &[u8]) -> u8 {
+        if bytes.len() < 8 || !is_chunk_id(&bytes[0..4]) {
+            return 0;
+        }
+        let len = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        if offset + 8 + u64::from(len) > self.end {
+            1
+        } else if KNOWN_IDS.iter().any(|id| bytes[0..4] == id[..]) {
+            3
+        } else {
+            2
+        }
+    }
+}
+
+/// IDs of chunks commonly found in wave files.
+const KNOWN_IDS: [&[u8; 4]; 19] = [
+    b"fmt ", b"data", b"fact", b"LIST", b"JUNK", b"PAD ", b"cue ", b"plst", b"smpl", b"inst",
+    b"bext", b"cart", b"levl", b"iXML", b"axml", b"chna", b"acid", b"id3 ", b"ID3 ",
+];
+
+/// Reads a little-endian 64-bit size from `bytes`, if they hold exactly 8 bytes.
+fn le_u64(bytes: Option<&[u8]>) -> Option<u64> {
+    bytes
+        .and_then(|b| <[u8; 8]>::try_from(b).ok())
+        .map(u64::from_le_bytes)
+}
+
+/// Chunk IDs are made of printable ASCII characters.
+fn is_chunk_id(id: &[u8]) -> bool {
+    id.iter().all(|b| (0x20..=0x7E).contains(b))
+}
+
+/// Writes a RIFF `"WAVE"` form holding the given chunks, padding each chunk of odd length.
+pub(crate) fn write_riff<W>(writer: &mut W, chunks: &[([u8; 4], &[u8])]) -> io::Result<()>
+where
+    W: Write,
+{
+    let (riff, headers) = riff_layout(chunks)?;
+
+    writer.write_all(&riff)?;
+    for ((_, data), header) in chunks.iter().zip(headers) {
+        writer.write_all(&header)?;
+        writer.write_all(data)?;
+        if data.len() % 2 == 1 {
+            writer.write_all(&[0])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Computes the 12-byte RIFF header and the 8-byte header of every chunk of a RIFF `"WAVE"` form
+/// holding the given chunks.
+pub(crate) fn riff_layout(chunks: &[([u8; 4], &[u8])]) -> io::Result<([u8; 12], Vec<[u8; 8]>)> {
+    let too_big = || io::Error::new(io::ErrorKind::InvalidData, "Data too big");
+
+    let mut headers = Vec::with_capacity(chunks.len());
+    let mut riff_len: u64 = 4;
+    for (id, data) in chunks {
+        let len = u32::try_from(data.len()).map_err(|_| too_big())?;
+        riff_len += 8 + u64::from(len) + u64::from(len % 2);
+
+        let mut header = [0; 8];
+        header[0..4].copy_from_slice(id);
+        header[4..8].copy_from_slice(&len.to_le_bytes());
+        headers.push(header);
+    }
+    let riff_len = u32::try_from(riff_len).map_err(|_| too_big())?;
+
+    let mut riff = [0; 12];
+    riff[0..4].copy_from_slice(b"RIFF");
+    riff[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    riff[8..12].copy_from_slice(b"WAVE");
+
+    Ok((riff, headers))
+}
+
+/// The most audio a wave list is expanded into, the largest size of a `"data"` chunk, so that a
+/// few bytes of `"slnt"` chunks can't claim more memory than the audio could be written back to.
+const MAX_WAVE_LIST_AUDIO: u64 = 0xFFFF_FFFF;
+
+/// Joins the `"data"` chunks of the contents of a `"LIST"` chunk of type `"wavl"` into the bytes
+/// of a single `"data"` chunk, replacing every `"slnt"` chunk with the number of frames of silence
+/// it holds. Other chunks of the list are skipped.
+///
+/// A `"data"` chunk ending with a partial frame is an error, unless `recover` is set, in which case
+/// the partial frame is dropped and reported in `warnings`. So is a list expanding to more than
+/// `0xFFFFFFFF` bytes of audio, which is checked before any of it is allocated.
+pub(crate) fn expand_wave_list(
+    list: &[u8],
+    header: &Header,
+    recover: bool,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<Vec<u8>> {
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let block_align = usize::from(header.bytes_per_sample);
+    if block_align == 0 {
+        return Err(invalid("Block align of zero"));
+    }
+    // Unsigned 8-bit samples are silent halfway through their range
+    let silence = if header.sample_format() == Some(SampleFormat::Eight) {
+        0x80
+    } else {
+        0
+    };
+
+    // The audio of each chunk, as the bytes to copy followed by a number of bytes of silence
+    let mut parts = Vec::new();
+    let mut audio_len = 0_u64;
+    let mut pos = 4;
+    while pos + 8 <= list.len() {
+        let id = &list[pos..pos + 4];
+        let len = u32::from_le_bytes([list[pos + 4], list[pos + 5], list[pos + 6], list[pos + 7]]);
+        pos += 8;
+        let contents = usize::try_from(len)
+            .ok()
+            .and_then(|len| list.get(pos..pos.checked_add(len)?))
+            .ok_or_else(|| invalid("Wave list chunk extends past the end of the list"))?;
+        pos += contents.len() + contents.len() % 2;
+
+        let (data, silent_len) = match id {
+            b"data" => {
+                let partial = contents.len() % block_align;
+                if partial != 0 {
+                    if !recover {
+                        return Err(invalid("Wave list data chunk ends with a partial frame"));
+                    }
+                    warnings.push(Warning::PartialFrameDropped {
+                        bytes: partial as u64,
+                    });
+                }
+                (&contents[..contents.len() - partial], 0)
+            }
+            b"slnt" => {
+                let frames = contents
+                    .get(..4)
+                    .ok_or_else(|| invalid("Silence chunk too short"))?;
+                let frames = u32::from_le_bytes([frames[0], frames[1], frames[2], frames[3]]);
+                (&[][..], u64::from(frames) * block_align as u64)
+            }
+            _ => continue,
+        };
+        audio_len = audio_len
+            .saturating_add(data.len() as u64)
+            .saturating_add(silent_len);
+        if audio_len > MAX_WAVE_LIST_AUDIO {
+            return Err(invalid("Wave list holds more audio than a data chunk can"));
+        }
+        parts.push((data, silent_len));
+    }
+
+    let too_large = || invalid("Wave list audio does not fit in memory");
+    let mut out = Vec::new();
+    out.try_reserve_exact(usize::try_from(audio_len).map_err(|_| too_large())?)
+        .map_err(|_| too_large())?;
+    for (data, silent_len) in parts {
+        out.extend_from_slice(data);
+        out.resize(
+            out.len() + usize::try_from(silent_len).map_err(|_| too_large())?,
+            silence,
+        );
+    }
+
+    Ok(out)
+}
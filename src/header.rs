@@ -1,26 +1,82 @@
 //! Contains items responsible for dealing with the `"fmt "` chunk of wave files.
 
-use std::convert::TryFrom;
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+
+use crate::SampleFormat;
 
 /// Value signifying PCM data.
 pub const WAV_FORMAT_PCM: u16 = 0x01;
 /// Value signifying IEEE float data.
 pub const WAV_FORMAT_IEEE_FLOAT: u16 = 0x03;
+/// Value signifying that the actual format is given by the sub-format of a [`FormatExtension`].
+pub const WAV_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// The last 12 bytes shared by the sub-format GUIDs of all formats with a registered 16-bit tag.
+const SUB_FORMAT_SUFFIX: [u8; 12] = [
+    0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
 
 /// Structure for the `"fmt "` chunk of wave files, specifying key information about the enclosed
 /// data.
 ///
 /// This struct supports only PCM and IEEE float data, which is to say there is no extra members for
-/// compressed format data.
+/// compressed format data. The only extra members supported are those of
+/// [`WAV_FORMAT_EXTENSIBLE`], held in [`Header::extension`].
 #[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub struct Header {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::format_tag"))]
     pub audio_format: u16,
     pub channel_count: u16,
     pub sampling_rate: u32,
     pub bytes_per_second: u32,
     pub bytes_per_sample: u16,
     pub bits_per_sample: u16,
+    /// Extra members of the [`WAV_FORMAT_EXTENSIBLE`] format, `None` for any other format.
+    pub extension: Option<FormatExtension>,
+}
+
+/// The extra members of the `"fmt "` chunk when using the [`WAV_FORMAT_EXTENSIBLE`] format.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormatExtension {
+    /// Number of bits of precision in each sample, at most [`Header::bits_per_sample`].
+    pub valid_bits_per_sample: u16,
+    /// Bit mask assigning the channels to speaker positions.
+    pub channel_mask: u32,
+    /// GUID of the actual data format.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::guid"))]
+    pub sub_format: [u8; 16],
+}
+
+impl FormatExtension {
+    /// Creates a new extension whose sub-format is the GUID of the 16-bit format tag
+    /// `sub_format_tag`, e.g. [`WAV_FORMAT_PCM`].
+    #[must_use]
+    pub fn new(valid_bits_per_sample: u16, channel_mask: u32, sub_format_tag: u16) -> Self {
+        let mut sub_format = [0; 16];
+        sub_format[0..2].copy_from_slice(&sub_format_tag.to_le_bytes());
+        sub_format[4..16].copy_from_slice(&SUB_FORMAT_SUFFIX);
+
+        FormatExtension {
+            valid_bits_per_sample,
+            channel_mask,
+            sub_format,
+        }
+    }
+
+    /// Returns the 16-bit format tag of the sub-format, or `None` if the sub-format GUID doesn't
+    /// correspond to one.
+    #[must_use]
+    pub fn sub_format_tag(&self) -> Option<u16> {
+        if self.sub_format[2..4] == [0, 0] && self.sub_format[4..16] == SUB_FORMAT_SUFFIX {
+            Some(u16::from_le_bytes([self.sub_format[0], self.sub_format[1]]))
+        } else {
+            None
+        }
+    }
 }
 
 impl Header {
@@ -32,15 +88,19 @@ impl Header {
     /// for the audio format, the option is given here to select any audio format for custom
     /// implementations of wave features.
     ///
+    /// The block align and byte rate are derived the way [`Header::validate`] expects them, with
+    /// samples rounded up to whole bytes. A derived field that overflows is set to `0`, which
+    /// [`Header::validate`] reports, use [`HeaderBuilder`] to catch invalid combinations up front.
+    ///
     /// ## Parameters
     ///
     /// * `audio_format` - Audio format. Only [`WAV_FORMAT_PCM`] (0x01) and
-    ///                    [`WAV_FORMAT_IEEE_FLOAT`] (0x03) are supported.
+    ///   [`WAV_FORMAT_IEEE_FLOAT`] (0x03) are supported.
     /// * `channel_count` - Channel count. The number of channels each sample has. Generally 1
-    ///                     (mono) or 2 (stereo).
+    ///   (mono) or 2 (stereo).
     /// * `sampling_rate` - Sampling rate (e.g. 44.1kHz, 48kHz, 96kHz, etc.).
     /// * `bits_per_sample` - Number of bits in each (sub-channel) sample. Generally 8, 16, 24, or
-    ///                       32.
+    ///   32.
     ///
     /// ## Example
     ///
@@ -54,17 +114,277 @@ impl Header {
         sampling_rate: u32,
         bits_per_sample: u16,
     ) -> Header {
+        let block_align = block_align(bits_per_sample, channel_count);
+        let bytes_per_second = block_align.and_then(|b| u32::from(b).checked_mul(sampling_rate));
+
         Header {
             audio_format,
             channel_count,
             sampling_rate,
             bits_per_sample,
-            bytes_per_second: u32::from((bits_per_sample >> 3) * channel_count) * sampling_rate,
-            bytes_per_sample: (bits_per_sample >> 3) * channel_count,
+            bytes_per_second: bytes_per_second.unwrap_or(0),
+            bytes_per_sample: block_align.unwrap_or(0),
+            extension: None,
+        }
+    }
+
+    /// Creates a new [`HeaderBuilder`] for the given sample format, the checked way of creating a
+    /// header.
+    #[must_use]
+    pub fn builder(sample_format: SampleFormat) -> HeaderBuilder {
+        HeaderBuilder::new(sample_format)
+    }
+
+    /// Returns the format tag describing the data, which is the sub-format tag for
+    /// [`WAV_FORMAT_EXTENSIBLE`] and [`Header::audio_format`] for any other format.
+    ///
+    /// Returns [`WAV_FORMAT_EXTENSIBLE`] itself if the extension is missing or its sub-format
+    /// doesn't correspond to a 16-bit format tag.
+    #[must_use]
+    pub fn effective_format(&self) -> u16 {
+        match (self.audio_format, self.extension) {
+            (WAV_FORMAT_EXTENSIBLE, Some(ext)) => {
+                ext.sub_format_tag().unwrap_or(WAV_FORMAT_EXTENSIBLE)
+            }
+            (format, _) => format,
+        }
+    }
+
+    /// Returns the format of the samples described by the header, or `None` if the format or
+    /// bit-depth isn't supported.
+    #[must_use]
+    pub fn sample_format(&self) -> Option<SampleFormat> {
+        match (self.effective_format(), self.bits_per_sample) {
+            (WAV_FORMAT_PCM, 8) => Some(SampleFormat::Eight),
+            (WAV_FORMAT_PCM, 16) => Some(SampleFormat::Sixteen),
+            (WAV_FORMAT_PCM, 24) => Some(SampleFormat::TwentyFour),
+            (WAV_FORMAT_IEEE_FLOAT, 32) => Some(SampleFormat::ThirtyTwoFloat),
+            _ => None,
+        }
+    }
+
+    /// Checks the header for inconsistencies, reporting every one found.
+    ///
+    /// The block align ([`Header::bytes_per_sample`]) is expected to equal `channel_count` times
+    /// `bits_per_sample` rounded up to whole bytes, and the byte rate
+    /// ([`Header::bytes_per_second`]) to equal the block align times the sampling rate.
+    ///
+    /// ## Errors
+    ///
+    /// Returns every [`HeaderIssue`] found if the header isn't consistent.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use wav::header::{Header, HeaderIssue, WAV_FORMAT_PCM};
+    ///
+    /// let mut h = Header::new(WAV_FORMAT_PCM, 2, 48_000, 16);
+    /// assert!(h.validate().is_ok());
+    ///
+    /// h.bytes_per_second = 0;
+    /// assert_eq!(
+    ///     h.validate(),
+    ///     Err(vec![HeaderIssue::BytesPerSecondMismatch { expected: 192_000, found: 0 }])
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<HeaderIssue>> {
+        let issues = self.issues();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Repairs the fields derived from the others, the block align and byte rate, if they are
+    /// inconsistent.
+    ///
+    /// ## Errors
+    ///
+    /// If the header has issues that can't be repaired, it is left untouched and every issue found
+    /// is returned.
+    ///
+    /// ## Returns
+    ///
+    /// The issues that were repaired, which is empty if the header was already consistent.
+    pub fn repair(&mut self) -> Result<Vec<HeaderIssue>, Vec<HeaderIssue>> {
+        let issues = self.issues();
+        if issues.iter().any(|i| !i.is_repairable()) {
+            return Err(issues);
+        }
+
+        for issue in &issues {
+            match *issue {
+                HeaderIssue::BlockAlignMismatch { expected, .. } => {
+                    self.bytes_per_sample = expected;
+                }
+                HeaderIssue::BytesPerSecondMismatch { expected, .. } => {
+                    self.bytes_per_second = expected;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(issues)
+    }
+
+    fn issues(&self) -> Vec<HeaderIssue> {
+        let mut issues = Vec::new();
+
+        if self.channel_count == 0 {
+            issues.push(HeaderIssue::ZeroChannels);
+        }
+        if self.sampling_rate == 0 {
+            issues.push(HeaderIssue::ZeroSamplingRate);
+        }
+        if self.bits_per_sample == 0 {
+            issues.push(HeaderIssue::ZeroBitsPerSample);
+        }
+
+        let block_align = block_align(self.bits_per_sample, self.channel_count);
+        match block_align {
+            Some(expected) if expected != self.bytes_per_sample => {
+                issues.push(HeaderIssue::BlockAlignMismatch {
+                    expected,
+                    found: self.bytes_per_sample,
+                });
+            }
+            Some(_) => {}
+            None => issues.push(HeaderIssue::BlockAlignOverflow),
+        }
+
+        let byte_rate = block_align.and_then(|b| u32::from(b).checked_mul(self.sampling_rate));
+        match byte_rate {
+            Some(expected) if expected != self.bytes_per_second => {
+                issues.push(HeaderIssue::BytesPerSecondMismatch {
+                    expected,
+                    found: self.bytes_per_second,
+                });
+            }
+            None if block_align.is_some() => issues.push(HeaderIssue::BytesPerSecondOverflow),
+            _ => {}
+        }
+
+        match (self.audio_format == WAV_FORMAT_EXTENSIBLE, self.extension) {
+            (true, Some(ext)) if ext.valid_bits_per_sample > self.bits_per_sample => {
+                issues.push(HeaderIssue::ValidBitsExceedContainer {
+                    valid: ext.valid_bits_per_sample,
+                    container: self.bits_per_sample,
+                });
+            }
+            (true, None) | (false, Some(_)) => issues.push(HeaderIssue::ExtensionMismatch),
+            _ => {}
+        }
+
+        issues
+    }
+}
+
+/// Returns the block align of `channel_count` channels of `bits_per_sample` bits rounded up to
+/// whole bytes, or `None` if it doesn't fit in a `u16`.
+fn block_align(bits_per_sample: u16, channel_count: u16) -> Option<u16> {
+    let container_bytes = (bits_per_sample >> 3) + u16::from(bits_per_sample & 7 != 0);
+    container_bytes.checked_mul(channel_count)
+}
+
+/// An inconsistency found in a [`Header`] by [`Header::validate`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HeaderIssue {
+    /// The channel count is zero.
+    ZeroChannels,
+    /// The sampling rate is zero.
+    ZeroSamplingRate,
+    /// The number of bits per sample is zero.
+    ZeroBitsPerSample,
+    /// The block align doesn't match the channel count and bits per sample.
+    BlockAlignMismatch {
+        /// Block align derived from the other fields.
+        expected: u16,
+        /// Block align stored in the header.
+        found: u16,
+    },
+    /// The block align derived from the channel count and bits per sample doesn't fit in a `u16`.
+    BlockAlignOverflow,
+    /// The byte rate doesn't match the block align and sampling rate.
+    BytesPerSecondMismatch {
+        /// Byte rate derived from the other fields.
+        expected: u32,
+        /// Byte rate stored in the header.
+        found: u32,
+    },
+    /// The byte rate derived from the block align and sampling rate doesn't fit in a `u32`.
+    BytesPerSecondOverflow,
+    /// The audio format is [`WAV_FORMAT_EXTENSIBLE`] without an extension, or the other way
+    /// around.
+    ExtensionMismatch,
+    /// The valid bits per sample of the extension exceed the bits per sample.
+    ValidBitsExceedContainer {
+        /// Valid bits per sample stored in the extension.
+        valid: u16,
+        /// Bits per sample stored in the header.
+        container: u16,
+    },
+}
+
+impl HeaderIssue {
+    /// Returns `true` if [`Header::repair`] can fix the issue by rewriting a derived field.
+    #[must_use]
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            Self::BlockAlignMismatch { .. } | Self::BytesPerSecondMismatch { .. }
+        )
+    }
+}
+
+impl fmt::Display for HeaderIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroChannels => write!(f, "Channel count is zero"),
+            Self::ZeroSamplingRate => write!(f, "Sampling rate is zero"),
+            Self::ZeroBitsPerSample => write!(f, "Bits per sample is zero"),
+            Self::BlockAlignMismatch { expected, found } => {
+                write!(f, "Block align is {found}, expected {expected}")
+            }
+            Self::BlockAlignOverflow => write!(f, "Block align overflows a u16"),
+            Self::BytesPerSecondMismatch { expected, found } => {
+                write!(f, "Bytes per second is {found}, expected {expected}")
+            }
+            Self::BytesPerSecondOverflow => write!(f, "Bytes per second overflows a u32"),
+            Self::ExtensionMismatch => write!(f, "Extension doesn't match the audio format"),
+            Self::ValidBitsExceedContainer { valid, container } => {
+                write!(
+                    f,
+                    "Valid bits per sample {valid} exceed bits per sample {container}"
+                )
+            }
+        }
+    }
+}
+
+/// Error returned when a [`Header`] fails validation, listing every issue found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidHeader {
+    /// The issues found in the header.
+    pub issues: Vec<HeaderIssue>,
+}
+
+impl fmt::Display for InvalidHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid header")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            write!(f, "{}{issue}", if i == 0 { ": " } else { "; " })?;
         }
+
+        Ok(())
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidHeader {}
+
 impl From<Header> for [u8; 16] {
     #[allow(clippy::shadow_unrelated)]
     fn from(h: Header) -> Self {
@@ -113,7 +433,25 @@ impl From<[u8; 16]> for Header {
             bytes_per_second,
             bytes_per_sample,
             bits_per_sample,
+            extension: None,
+        }
+    }
+}
+
+impl From<Header> for Vec<u8> {
+    /// Produces the full contents of the `"fmt "` chunk, which is 16 bytes long, or 40 bytes if
+    /// the header has an extension.
+    fn from(h: Header) -> Self {
+        let mut v = Vec::from(<[u8; 16]>::from(h));
+
+        if let Some(ext) = h.extension {
+            v.extend_from_slice(&22u16.to_le_bytes());
+            v.extend_from_slice(&ext.valid_bits_per_sample.to_le_bytes());
+            v.extend_from_slice(&ext.channel_mask.to_le_bytes());
+            v.extend_from_slice(&ext.sub_format);
         }
+
+        v
     }
 }
 
@@ -122,14 +460,269 @@ impl TryFrom<&[u8]> for Header {
 
     /// ## Errors
     ///
-    /// This function will return an error if the given slice is smaller than 16 bytes.
+    /// This function will return an error if the given slice is smaller than 16 bytes, or smaller
+    /// than 40 bytes when the audio format is [`WAV_FORMAT_EXTENSIBLE`].
     fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
         if v.len() < 16 {
             Err("Slice is smaller than the minimum-required 16 bytes")
         } else {
             let mut a: [u8; 16] = [0; 16];
             a.copy_from_slice(&v[0..16]);
-            Ok(Header::from(a))
+            let mut header = Header::from(a);
+
+            if header.audio_format == WAV_FORMAT_EXTENSIBLE {
+                if v.len() < 40 {
+                    return Err(
+                        "Slice is smaller than the 40 bytes required by the extensible format",
+                    );
+                }
+
+                let mut sub_format = [0; 16];
+                sub_format.copy_from_slice(&v[24..40]);
+                header.extension = Some(FormatExtension {
+                    valid_bits_per_sample: u16::from_le_bytes([v[18], v[19]]),
+                    channel_mask: u32::from_le_bytes([v[20], v[21], v[22], v[23]]),
+                    sub_format,
+                });
+            }
+
+            Ok(header)
+        }
+    }
+}
+
+/// Builder for a [`Header`] that derives every field from the sample format, checking that the
+/// result is consistent and fits in the fields of the `"fmt "` chunk.
+///
+/// The format tag and bits per sample follow from the [`SampleFormat`], so a PCM header with 32
+/// bits or a float header with 24 bits can't be built. [`WAV_FORMAT_EXTENSIBLE`] is only chosen
+/// automatically when a channel mask or a reduced number of valid bits is given, which it is
+/// needed to store, and can be turned on with [`HeaderBuilder::extensible`].
+///
+/// ## Example
+///
+/// ```
+/// use wav::{header::HeaderBuilder, SampleFormat, WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_PCM};
+///
+/// let stereo = HeaderBuilder::new(SampleFormat::Sixteen).build().unwrap();
+/// assert_eq!(stereo.audio_format, WAV_FORMAT_PCM);
+/// assert_eq!(stereo.bytes_per_second, 192_000);
+///
+/// let surround = HeaderBuilder::new(SampleFormat::TwentyFour)
+///     .channel_count(6)
+///     .sampling_rate(96_000)
+///     .extensible(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(surround.audio_format, WAV_FORMAT_EXTENSIBLE);
+/// assert_eq!(surround.effective_format(), WAV_FORMAT_PCM);
+/// ```
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct HeaderBuilder {
+    sample_format: SampleFormat,
+    channel_count: u16,
+    sampling_rate: u32,
+    valid_bits_per_sample: Option<u16>,
+    channel_mask: Option<u32>,
+    extensible: Option<bool>,
+}
+
+impl HeaderBuilder {
+    /// Creates a new builder for the given sample format, defaulting to 2 channels at 48kHz.
+    #[must_use]
+    pub fn new(sample_format: SampleFormat) -> Self {
+        HeaderBuilder {
+            sample_format,
+            channel_count: 2,
+            sampling_rate: 48_000,
+            valid_bits_per_sample: None,
+            channel_mask: None,
+            extensible: None,
+        }
+    }
+
+    /// Sets the number of channels.
+    #[must_use]
+    pub fn channel_count(mut self, channel_count: u16) -> Self {
+        self.channel_count = channel_count;
+        self
+    }
+
+    /// Sets the sampling rate.
+    #[must_use]
+    pub fn sampling_rate(mut self, sampling_rate: u32) -> Self {
+        self.sampling_rate = sampling_rate;
+        self
+    }
+
+    /// Sets the number of bits of precision in each sample, when it is below the size of the
+    /// sample container. Requires [`WAV_FORMAT_EXTENSIBLE`].
+    #[must_use]
+    pub fn valid_bits_per_sample(mut self, valid_bits_per_sample: u16) -> Self {
+        self.valid_bits_per_sample = Some(valid_bits_per_sample);
+        self
+    }
+
+    /// Sets the speaker positions of the channels. Requires [`WAV_FORMAT_EXTENSIBLE`].
+    ///
+    /// The mask may have fewer positions than there are channels, the remaining channels having
+    /// no speaker position, and is `0` if none of them has one. Without it, extensible headers get
+    /// the conventional mask for their channel count, e.g. 5.1 for 6 channels, or no speaker
+    /// positions if there isn't one.
+    #[must_use]
+    pub fn channel_mask(mut self, channel_mask: u32) -> Self {
+        self.channel_mask = Some(channel_mask);
+        self
+    }
+
+    /// Forces the use of [`WAV_FORMAT_EXTENSIBLE`] on or off, instead of choosing automatically.
+    #[must_use]
+    pub fn extensible(mut self, extensible: bool) -> Self {
+        self.extensible = Some(extensible);
+        self
+    }
+
+    /// Builds the header.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if any field is out of range, if a derived field overflows, or if the
+    /// options given require [`WAV_FORMAT_EXTENSIBLE`] while it was turned off.
+    pub fn build(self) -> Result<Header, HeaderBuildError> {
+        let container = self.sample_format.bits_per_sample();
+
+        if self.channel_count == 0 {
+            return Err(HeaderBuildError::ZeroChannels);
+        }
+        if self.sampling_rate == 0 {
+            return Err(HeaderBuildError::ZeroSamplingRate);
         }
+
+        let valid_bits = self.valid_bits_per_sample.unwrap_or(container);
+        if valid_bits == 0 || valid_bits > container {
+            return Err(HeaderBuildError::InvalidValidBits {
+                valid: valid_bits,
+                container,
+            });
+        }
+
+        if let Some(mask) = self.channel_mask {
+            if mask.count_ones() > u32::from(self.channel_count) {
+                return Err(HeaderBuildError::ChannelMaskMismatch {
+                    channel_mask: mask,
+                    channel_count: self.channel_count,
+                });
+            }
+        }
+
+        let block_align = (container >> 3)
+            .checked_mul(self.channel_count)
+            .ok_or(HeaderBuildError::BlockAlignOverflow)?;
+        let bytes_per_second = u32::from(block_align)
+            .checked_mul(self.sampling_rate)
+            .ok_or(HeaderBuildError::BytesPerSecondOverflow)?;
+
+        let needs_extension = valid_bits != container || self.channel_mask.is_some();
+        let extensible = match self.extensible {
+            Some(false) if needs_extension => return Err(HeaderBuildError::ExtensibleRequired),
+            Some(extensible) => extensible,
+            None => needs_extension,
+        };
+
+        let format_tag = self.sample_format.format_tag();
+        Ok(Header {
+            audio_format: if extensible {
+                WAV_FORMAT_EXTENSIBLE
+            } else {
+                format_tag
+            },
+            channel_count: self.channel_count,
+            sampling_rate: self.sampling_rate,
+            bytes_per_second,
+            bytes_per_sample: block_align,
+            bits_per_sample: container,
+            extension: if extensible {
+                Some(FormatExtension::new(
+                    valid_bits,
+                    self.channel_mask
+                        .unwrap_or_else(|| default_channel_mask(self.channel_count)),
+                    format_tag,
+                ))
+            } else {
+                None
+            },
+        })
     }
 }
+
+/// Returns the conventional speaker positions for the given channel count, or `0` (no speaker
+/// positions) if there isn't one.
+pub(crate) fn default_channel_mask(channel_count: u16) -> u32 {
+    match channel_count {
+        1 => 0x4,   // Front center
+        2 => 0x3,   // Front left, front right
+        3 => 0x7,   // Front left, front right, front center
+        4 => 0x33,  // Quad
+        5 => 0x37,  // 5.0
+        6 => 0x3F,  // 5.1
+        7 => 0x70F, // 6.1
+        8 => 0x63F, // 7.1
+        _ => 0,
+    }
+}
+
+/// Error returned by [`HeaderBuilder::build`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum HeaderBuildError {
+    /// The channel count is zero.
+    ZeroChannels,
+    /// The sampling rate is zero.
+    ZeroSamplingRate,
+    /// The valid bits per sample are zero or exceed the size of the sample container.
+    InvalidValidBits {
+        /// Valid bits per sample requested.
+        valid: u16,
+        /// Bits per sample of the sample format.
+        container: u16,
+    },
+    /// The channel mask has more speaker positions than there are channels.
+    ChannelMaskMismatch {
+        /// Channel mask requested.
+        channel_mask: u32,
+        /// Channel count requested.
+        channel_count: u16,
+    },
+    /// The block align doesn't fit in a `u16`.
+    BlockAlignOverflow,
+    /// The byte rate doesn't fit in a `u32`.
+    BytesPerSecondOverflow,
+    /// [`WAV_FORMAT_EXTENSIBLE`] was turned off, but is needed to store the valid bits per sample
+    /// or the channel mask.
+    ExtensibleRequired,
+}
+
+impl fmt::Display for HeaderBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroChannels => write!(f, "Channel count is zero"),
+            Self::ZeroSamplingRate => write!(f, "Sampling rate is zero"),
+            Self::InvalidValidBits { valid, container } => write!(
+                f,
+                "Valid bits per sample {valid} must be between 1 and bits per sample {container}"
+            ),
+            Self::ChannelMaskMismatch {
+                channel_mask,
+                channel_count,
+            } => write!(
+                f,
+                "Channel mask {channel_mask:#x} has more positions than {channel_count} channels"
+            ),
+            Self::BlockAlignOverflow => write!(f, "Block align overflows a u16"),
+            Self::BytesPerSecondOverflow => write!(f, "Bytes per second overflows a u32"),
+            Self::ExtensibleRequired => write!(f, "Options require the extensible format"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderBuildError {}
@@ -1,8 +1,10 @@
 //! This is a crate for reading in and writing out wave files. It supports uncompressed PCM bit
 //! depths of 8, 16, 24 bits, and 32bit IEEE Float formats, both with any number of channels.
-//! Unfortunately other types of data format (e.g. compressed WAVE files) are not supported. There
-//! is also no support for any metadata chunks or any chunks other than the `"fmt "` and `"data"`
-//! chunks.
+//! Unfortunately other types of data format (e.g. compressed WAVE files) are not supported. Chunks
+//! other than the `"fmt "` and `"data"` chunks can be read and written as raw [`Chunk`]s.
+//!
+//! With the default `std` feature disabled the crate is `no_std`, and only needs `alloc` to
+//! convert headers and samples from and to raw bytes with [`Header`] and [`BitDepth`].
 //!
 //! ## Example
 //!
@@ -20,229 +22,486 @@
 //! # }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
 
+extern crate alloc;
+
+#[cfg(feature = "std")]
 use std::{
     convert::TryFrom,
     io::{self, Read, Write},
 };
 
 pub mod header;
-pub use header::{Header, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM};
+pub use header::{
+    Header, HeaderBuilder, WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM,
+};
 
 pub mod bit_depth;
-pub use bit_depth::BitDepth;
+pub use bit_depth::{BitDepth, SampleFormat};
+
+pub mod sample;
+pub use sample::{Sample, I24};
+
+pub mod typed;
+pub use typed::Wav;
+
+pub mod channels;
+
+#[cfg(feature = "async")]
+pub mod async_io;
+
+#[cfg(feature = "mmap")]
+pub mod mmap;
+
+#[cfg(feature = "std")]
+pub mod options;
+#[cfg(feature = "std")]
+pub use options::{ReadOptions, Validation, Warning};
+
+#[cfg(feature = "std")]
+pub mod reader;
+#[cfg(feature = "std")]
+pub use reader::{read_range, WavReader};
+
+#[cfg(feature = "std")]
+pub mod loudness;
+
+#[cfg(feature = "std")]
+pub mod metadata;
+
+#[cfg(feature = "std")]
+pub mod resample;
+
+#[cfg(feature = "std")]
+pub mod writer;
+#[cfg(feature = "std")]
+pub use writer::WavWriter;
+
+#[cfg(feature = "std")]
+pub mod waveform;
+
+#[cfg(feature = "std")]
+pub mod chunk;
+#[cfg(feature = "std")]
+pub use chunk::{Chunk, ChunkHeader};
+
+#[cfg(feature = "serde")]
+mod serde_support;
 
 mod tuple_iterator;
-use tuple_iterator::{PairIter, QuadrupletIter, TripletIter};
 
 /// Reads in the given `reader` and attempts to extract the audio data and header from it.
 ///
+/// Files storing their audio as a `"LIST"` chunk of type `"wavl"`, alternating `"data"` chunks
+/// with `"slnt"` chunks of silence, instead of a single `"data"` chunk, are read as if the silence
+/// had been written out. So are RF64 and BW64 files, whose sizes beyond 4 GiB are held by a
+/// `"ds64"` chunk.
+///
 /// ## Errors
 ///
 /// This function fails under the following circumstances:
 ///
 /// * Any error occurring from the `reader` parameter during reading.
-/// * The data isn't RIFF data.
+/// * The data isn't RIFF, RF64 or BW64 data.
 /// * The wave header specifies a compressed data format.
 /// * The wave header specifies an unsupported bit-depth.
 /// * The wave data is malformed, or otherwise couldn't be parsed into samples.
+#[cfg(feature = "std")]
 #[allow(clippy::similar_names)]
 pub fn read<R>(reader: &mut R) -> io::Result<(Header, BitDepth)>
 where
     R: Read + io::Seek,
 {
-    let header = read_header(reader)?;
-    Ok((header, read_data(reader, &header)?))
+    let (header, data, _) = read_with_options(reader, ReadOptions::default())?;
+    Ok((header, data))
+}
+
+/// Reads in the given `reader` like [`read`], applying the given `options`.
+///
+/// Along with the header and audio data, a list of the [`Warning`]s describing how problems with
+/// the file were worked around is returned.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`read`], and additionally if the header
+/// doesn't pass the validation selected by `options`, in which case the error wraps a
+/// [`header::InvalidHeader`].
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::fs::File;
+/// use wav::{ReadOptions, Validation};
+///
+/// let options = ReadOptions {
+///     validation: Validation::Strict,
+///     ..ReadOptions::default()
+/// };
+/// let (header, data, warnings) = wav::read_with_options(&mut File::open("data/sine.wav")?, options)?;
+/// assert!(warnings.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn read_with_options<R>(
+    reader: &mut R,
+    options: ReadOptions,
+) -> io::Result<(Header, BitDepth, Vec<Warning>)>
+where
+    R: Read + io::Seek,
+{
+    let mut warnings = Vec::new();
+    let (header, chunks) = parse_fmt(reader, options, &mut warnings)?;
+    let bytes = if chunks.iter().any(|c| c.id == *b"data") {
+        find_data_chunk(&chunks, &header, options, &mut warnings)?.read_contents(reader)?
+    } else {
+        read_wave_list(reader, &chunks, &header, options, &mut warnings)?
+    };
+    let data = decode_data(&header, bytes)?;
+
+    Ok((header, data, warnings))
+}
+
+/// Reads in the given `reader` like [`read`], returning the samples as `T`, converted from the
+/// format of the file as described in [`sample`].
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`read`].
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::fs::File;
+///
+/// let (header, samples) = wav::read_as::<f32, _>(&mut File::open("data/sine.wav")?)?;
+/// assert!(samples.iter().all(|s| (-1.0..1.0).contains(s)));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn read_as<T, R>(reader: &mut R) -> io::Result<(Header, Vec<T>)>
+where
+    T: Sample,
+    R: Read + io::Seek,
+{
+    let (header, data) = read(reader)?;
+    Ok((header, T::from_bit_depth(&data).into_owned()))
 }
 
 /// Writes the given wav data to the given `writer`.
 ///
 /// ## Notes
 ///
-/// Although `track` is a borrowed value, its contents will be formatted into an owned `Vec<u8>` so
-/// that it can be written to the `writer` through [`riff::ChunkContents::write`].
+/// Although `track` is a borrowed value, its contents will be formatted into an owned `Vec<u8>`
+/// before being written to the `writer`.
+///
+/// Only the `"fmt "` and `"data"` chunks are written. To keep metadata such as tags and album art
+/// when re-exporting a file, read its chunks with [`read_chunks`] and pass them to
+/// [`write_with_chunks`].
 ///
 /// ## Errors
 ///
 /// This function fails under the following circumstances:
 ///
 /// * Any error occurring from the `writer` parameter during writing.
+/// * The given `header` doesn't pass [`Header::validate`], in which case the error wraps a
+///   [`header::InvalidHeader`].
 /// * The given [`BitDepth`] is [`BitDepth::Empty`].
+/// * The wave data is too large to be described by the 32-bit RIFF sizes.
+#[cfg(feature = "std")]
 pub fn write<W>(header: Header, track: &BitDepth, writer: &mut W) -> std::io::Result<()>
 where
     W: Write + io::Seek,
 {
-    const WAVE_ID: riff::ChunkId = riff::ChunkId {
-        value: [b'W', b'A', b'V', b'E'],
-    };
-    const HEADER_ID: riff::ChunkId = riff::ChunkId {
-        value: [b'f', b'm', b't', b' '],
-    };
-    const DATA_ID: riff::ChunkId = riff::ChunkId {
-        value: [b'd', b'a', b't', b'a'],
-    };
+    write_with_chunks(header, track, &[], writer)
+}
 
-    let h_vec: [u8; 16] = header.into();
-    let h_dat = riff::ChunkContents::Data(HEADER_ID, Vec::from(h_vec));
+/// Writes the given wav data to the given `writer` like [`write()`], along with the extra `chunks`.
+///
+/// The extra chunks are written in the given order between the `"fmt "` and `"data"` chunks, each
+/// followed by a pad byte if its length is odd.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`write()`], and additionally if any of the
+/// extra chunks is a `"fmt "` or `"data"` chunk.
+#[cfg(feature = "std")]
+pub fn write_with_chunks<W>(
+    header: Header,
+    track: &BitDepth,
+    chunks: &[Chunk],
+    writer: &mut W,
+) -> std::io::Result<()>
+where
+    W: Write + io::Seek,
+{
+    let d_vec = prepare_write(header, track, chunks)?;
+    let h_vec = Vec::from(header);
 
-    let d_vec = match track {
-        BitDepth::Eight(v) => v.clone(),
-        BitDepth::Sixteen(v) => v
-            .iter()
-            .flat_map(|s| {
-                let v = s.to_le_bytes();
-                PairIter::new((v[0], v[1]))
-            })
-            .collect::<Vec<_>>(),
-        BitDepth::TwentyFour(v) => v
-            .iter()
-            .flat_map(|s| {
-                let v = s.to_le_bytes().split_at(1).1.to_owned();
-                TripletIter::new((v[0], v[1], v[2]))
-            })
-            .collect::<Vec<_>>(),
-        BitDepth::ThirtyTwoFloat(v) => v
-            .iter()
-            .flat_map(|s| {
-                let v = s.to_le_bytes().to_owned();
-                QuadrupletIter::new((v[0], v[1], v[2], v[3]))
-            })
-            .collect::<Vec<_>>(),
-        _ => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Empty audio data given",
-            ))
-        }
-    };
-    let d_dat = riff::ChunkContents::Data(DATA_ID, d_vec);
+    chunk::write_riff(writer, &layout(&h_vec, chunks, &d_vec))
+}
+
+/// Writes the interleaved `samples` to the given `writer` like [`write()`], converting them to the
+/// format given by `header` as described in [`sample`].
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`write()`], and additionally if `header`
+/// doesn't describe a format supported by [`BitDepth`].
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::io::Cursor;
+/// use wav::{Header, WAV_FORMAT_PCM};
+///
+/// let header = Header::new(WAV_FORMAT_PCM, 1, 48_000, 16);
+/// let mut out = Cursor::new(Vec::new());
+/// wav::write_from(header, &[0.0f32, 0.5, -0.5], &mut out)?;
+///
+/// let (_, samples) = wav::read_as::<i16, _>(&mut Cursor::new(out.into_inner()))?;
+/// assert_eq!(samples, [0, 16_384, -16_384]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn write_from<T, W>(header: Header, samples: &[T], writer: &mut W) -> io::Result<()>
+where
+    T: Sample,
+    W: Write + io::Seek,
+{
+    let format = header.sample_format().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Unsupported sample format in header",
+        )
+    })?;
+    write(header, &T::to_bit_depth(samples).convert(format), writer)
+}
 
-    let r = riff::ChunkContents::Children(riff::RIFF_ID.clone(), WAVE_ID, vec![h_dat, d_dat]);
+/// Checks the arguments of [`write_with_chunks`], returning the encoded samples.
+#[cfg(feature = "std")]
+fn prepare_write(header: Header, track: &BitDepth, chunks: &[Chunk]) -> io::Result<Vec<u8>> {
+    check_write(header, chunks)?;
+    encode_data(track)
+}
 
-    r.write(writer)?;
+/// Checks that the header is valid and that none of the extra chunks replaces the `"fmt "` or
+/// `"data"` chunks.
+#[cfg(feature = "std")]
+fn check_write(header: Header, chunks: &[Chunk]) -> io::Result<()> {
+    header.validate().map_err(|issues| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            header::InvalidHeader { issues },
+        )
+    })?;
+    if chunks.iter().any(|c| c.id == *b"fmt " || c.id == *b"data") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Extra chunks can't replace the \"fmt \" or \"data\" chunks",
+        ));
+    }
 
     Ok(())
 }
 
-#[allow(clippy::similar_names)]
-fn read_header<R>(reader: &mut R) -> io::Result<Header>
+/// Orders the chunks of a wave file as they are written.
+#[cfg(feature = "std")]
+fn layout<'a>(h_vec: &'a [u8], chunks: &'a [Chunk], d_vec: &'a [u8]) -> Vec<([u8; 4], &'a [u8])> {
+    let mut contents = Vec::with_capacity(chunks.len() + 2);
+    contents.push((*b"fmt ", h_vec));
+    contents.extend(chunks.iter().map(|c| (c.id, c.data.as_slice())));
+    contents.push((*b"data", d_vec));
+
+    contents
+}
+
+/// Reads in the header of the wave file from the given `reader`, without reading the audio data.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`read`], except for those concerning the
+/// audio data itself.
+#[cfg(feature = "std")]
+pub fn read_header<R>(reader: &mut R) -> io::Result<Header>
 where
     R: Read + io::Seek,
 {
-    let wav = verify_wav_file(reader)?;
-
-    for c in wav.iter(reader) {
-        if c.id().as_str() == "fmt " {
-            // Read header contents
-            let header_bytes = c.read_contents(reader)?;
-            let header = Header::try_from(header_bytes.as_slice())
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-
-            // Return error if not using PCM
-            match header.audio_format {
-                WAV_FORMAT_PCM | WAV_FORMAT_IEEE_FLOAT => return Ok(header),
-                _ => {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "Unsupported data format, data is not in uncompressed PCM format, aborting",
-                    ))
-                }
-            };
-        }
-    }
+    parse_fmt(reader, ReadOptions::default(), &mut Vec::new()).map(|(header, _)| header)
+}
 
-    Err(io::Error::new(
-        io::ErrorKind::InvalidData,
-        "RIFF data is missing the \"fmt \" chunk, aborting",
-    ))
+/// Reads in the given `reader` and returns the location of every top-level chunk, including the
+/// `"fmt "` and `"data"` chunks, in the order they appear. No chunk contents are read.
+///
+/// ## Errors
+///
+/// This function fails if any error occurs from the `reader` during reading, or if the data isn't
+/// RIFF `"WAVE"` data.
+#[cfg(feature = "std")]
+pub fn list_chunks<R>(reader: &mut R) -> io::Result<Vec<ChunkHeader>>
+where
+    R: Read + io::Seek,
+{
+    chunk::walk(reader, false, &mut Vec::new())
 }
 
-#[allow(clippy::similar_names)]
-fn read_data<R>(reader: &mut R, header: &Header) -> io::Result<BitDepth>
+/// Reads in the given `reader` and returns every top-level chunk other than the `"fmt "` and
+/// `"data"` chunks, and the `"ds64"` chunk of RF64 and BW64 files, in the order they appear.
+///
+/// ## Errors
+///
+/// This function fails if any error occurs from the `reader` during reading, or if the data isn't
+/// RIFF `"WAVE"` data.
+#[cfg(feature = "std")]
+pub fn read_chunks<R>(reader: &mut R) -> io::Result<Vec<Chunk>>
 where
     R: Read + io::Seek,
 {
-    let wav = verify_wav_file(reader)?;
-
-    for c in wav.iter(reader) {
-        if c.id().as_str() == "data" {
-            // Read data contents
-            let data_bytes = c.read_contents(reader)?;
-
-            let wav_data = match header.audio_format {
-                WAV_FORMAT_PCM => match header.bits_per_sample {
-                    8 => Ok(BitDepth::Eight(data_bytes)),
-                    16 => Ok(BitDepth::Sixteen({
-                        let mut tmpv = Vec::with_capacity(data_bytes.len() / 2);
-                        tmpv.extend(
-                            data_bytes
-                                .chunks_exact(2)
-                                .map(|i| i16::from_le_bytes([i[0], i[1]])),
-                        );
-                        tmpv
-                    })),
-                    24 => Ok(BitDepth::TwentyFour({
-                        let mut tmpv = Vec::with_capacity(data_bytes.len() / 3);
-                        tmpv.extend(
-                            data_bytes
-                                .chunks_exact(3)
-                                .map(|i| i32::from_le_bytes([0, i[0], i[1], i[2]])),
-                        );
-                        tmpv
-                    })),
-                    _ => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "Unsupported PCM bit depth",
-                    )),
-                },
-                WAV_FORMAT_IEEE_FLOAT => match header.bits_per_sample {
-                    32 => Ok(BitDepth::ThirtyTwoFloat({
-                        let mut tmpv = Vec::with_capacity(data_bytes.len() / 4);
-                        tmpv.extend(
-                            data_bytes
-                                .chunks_exact(4)
-                                .map(|f| f32::from_le_bytes([f[0], f[1], f[2], f[3]])),
-                        );
-                        tmpv
-                    })),
-                    _ => Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "Unsupported IEEE Float bit depth",
-                    )),
-                },
-                _ => Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    "Unsupported WAV format",
-                )),
-            };
-
-            return wav_data;
-        }
-    }
+    chunk::walk(reader, false, &mut Vec::new())?
+        .into_iter()
+        .filter(|c| c.id != *b"fmt " && c.id != *b"data" && c.id != *b"ds64")
+        .map(|c| {
+            Ok(Chunk {
+                id: c.id,
+                data: c.read_contents(reader)?,
+            })
+        })
+        .collect()
+}
 
-    Err(io::Error::new(
-        io::ErrorKind::Other,
-        "Could not parse audio data",
-    ))
+/// Converts the samples into the little-endian bytes of the `"data"` chunk.
+#[cfg(feature = "std")]
+fn encode_data(track: &BitDepth) -> io::Result<Vec<u8>> {
+    track.encode().map_err(io::Error::other)
 }
 
-fn verify_wav_file<R>(reader: &mut R) -> io::Result<riff::Chunk>
+/// Walks the chunks of the wave file, returning its header and the location of its `"data"`
+/// chunk.
+#[cfg(feature = "std")]
+fn parse<R>(
+    reader: &mut R,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<(Header, ChunkHeader)>
 where
     R: Read + io::Seek,
 {
-    let wav = riff::Chunk::read(reader, 0)?;
+    let (header, chunks) = parse_fmt(reader, options, warnings)?;
+    let data = find_data_chunk(&chunks, &header, options, warnings)?;
 
-    let form_type = wav.read_type(reader)?;
+    Ok((header, data))
+}
 
-    if form_type.as_str() == "WAVE" {
-        Ok(wav)
-    } else {
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            "RIFF file type not \"WAVE\"",
-        ))
+/// Walks the chunks of the wave file, returning its header and the location of every chunk.
+#[cfg(feature = "std")]
+fn parse_fmt<R>(
+    reader: &mut R,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<(Header, Vec<ChunkHeader>)>
+where
+    R: Read + io::Seek,
+{
+    let chunks = chunk::walk(reader, options.recover, warnings)?;
+    let header_bytes = find_fmt_chunk(&chunks)?.read_contents(reader)?;
+    let header = parse_header(&header_bytes, options, warnings)?;
+
+    Ok((header, chunks))
+}
+
+/// Reads the audio data of a file without a `"data"` chunk from its `"LIST"` chunk of type
+/// `"wavl"`, expanding its silence.
+#[cfg(feature = "std")]
+fn read_wave_list<R>(
+    reader: &mut R,
+    chunks: &[ChunkHeader],
+    header: &Header,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<Vec<u8>>
+where
+    R: Read + io::Seek,
+{
+    for list in chunks.iter().filter(|c| c.id == *b"LIST") {
+        let contents = list.read_contents(reader)?;
+        if contents.starts_with(b"wavl") {
+            return chunk::expand_wave_list(&contents, header, options.recover, warnings);
+        }
     }
+
+    Err(io::Error::other("Could not parse audio data"))
+}
+
+#[cfg(feature = "std")]
+fn find_fmt_chunk(chunks: &[ChunkHeader]) -> io::Result<&ChunkHeader> {
+    chunks.iter().find(|c| c.id == *b"fmt ").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "RIFF data is missing the \"fmt \" chunk, aborting",
+        )
+    })
+}
+
+/// Parses the contents of the `"fmt "` chunk, checking that the format is supported and applying
+/// the validation selected by `options`.
+#[cfg(feature = "std")]
+fn parse_header(
+    header_bytes: &[u8],
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<Header> {
+    let mut header = Header::try_from(header_bytes).map_err(io::Error::other)?;
+
+    // Return error if not using PCM
+    match header.effective_format() {
+        WAV_FORMAT_PCM | WAV_FORMAT_IEEE_FLOAT => {}
+        _ => {
+            return Err(io::Error::other(
+                "Unsupported data format, data is not in uncompressed PCM format, aborting",
+            ))
+        }
+    }
+    options.validation.apply(&mut header, warnings)?;
+
+    Ok(header)
+}
+
+/// Locates the `"data"` chunk, dropping any trailing partial frame when recovering.
+#[cfg(feature = "std")]
+fn find_data_chunk(
+    chunks: &[ChunkHeader],
+    header: &Header,
+    options: ReadOptions,
+    warnings: &mut Vec<Warning>,
+) -> io::Result<ChunkHeader> {
+    let mut data = *chunks
+        .iter()
+        .find(|c| c.id == *b"data")
+        .ok_or_else(|| io::Error::other("Could not parse audio data"))?;
+
+    let block_align = u64::from(header.bytes_per_sample);
+    if options.recover && block_align != 0 && data.len % block_align != 0 {
+        warnings.push(Warning::PartialFrameDropped {
+            bytes: data.len % block_align,
+        });
+        data.len -= data.len % block_align;
+    }
+
+    Ok(data)
+}
+
+/// Converts the raw little-endian bytes of the `"data"` chunk into samples as described by
+/// `header`.
+#[cfg(feature = "std")]
+fn decode_data(header: &Header, data_bytes: Vec<u8>) -> io::Result<BitDepth> {
+    BitDepth::decode_owned(header, data_bytes).map_err(io::Error::other)
 }
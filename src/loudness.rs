@@ -0,0 +1,490 @@
+//! Contains items for measuring the loudness and levels of audio data following ITU-R BS.1770 and
+//! EBU R128.
+//!
+//! Audio is fed to an [`Analyzer`] in blocks of frames, so files of any size can be measured
+//! without decoding them at once; [`analyze`] and [`analyze_reader`] do so for a whole
+//! [`BitDepth`] or wave file. The resulting [`Loudness`] can be written into the loudness fields
+//! of a [`Bext`] chunk.
+//!
+//! ## Example
+//!
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use std::fs::File;
+//!
+//! let loudness = wav::loudness::analyze_reader(File::open("data/sine.wav")?)?;
+//! println!("{:.1} LUFS, {:.1} dBTP", loudness.integrated, loudness.true_peak);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    convert::TryFrom,
+    f64::consts::PI,
+    io::{self, Read, Seek},
+};
+
+use crate::{
+    channels::{ChannelLayout, Speaker},
+    metadata::Bext,
+    resample::{bessel_i0, sinc},
+    BitDepth, Header, Sample, WavReader,
+};
+
+/// Number of gating steps of 100ms in a momentary loudness block of 400ms.
+const MOMENTARY_STEPS: usize = 4;
+
+/// Number of gating steps of 100ms in a short-term loudness block of 3s.
+const SHORT_TERM_STEPS: usize = 30;
+
+/// Blocks quieter than this are ignored by the integrated loudness and loudness range, in LUFS.
+const ABSOLUTE_GATE: f64 = -70.0;
+
+/// Number of input samples weighted by the interpolator finding true peaks.
+const TRUE_PEAK_TAPS: usize = 12;
+
+/// Number of frames analyzed at once by [`analyze_reader`].
+const BLOCK_FRAMES: u64 = 65_536;
+
+/// Loudness and levels measured by an [`Analyzer`].
+///
+/// Loudness values are in LUFS, loudness ranges in LU and levels in dB relative to full scale.
+/// Loudness values measured on silence, or on audio too short to fill a single measurement block,
+/// are negative infinity, and so are the levels of silent channels. The loudness range is then
+/// zero.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Loudness {
+    /// Gated loudness of the whole audio.
+    pub integrated: f64,
+    /// Spread of the short-term loudness, following EBU Tech 3342.
+    pub loudness_range: f64,
+    /// Highest loudness over 400ms.
+    pub max_momentary: f64,
+    /// Highest loudness over 3s.
+    pub max_short_term: f64,
+    /// Highest true peak of any channel, in dBTP.
+    pub true_peak: f64,
+    /// Levels of every channel.
+    pub channels: Vec<ChannelLevels>,
+}
+
+/// Levels of a single channel.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelLevels {
+    /// Highest absolute sample value, in dBFS.
+    pub sample_peak: f64,
+    /// Highest absolute value of the signal reconstructed between samples, in dBTP. It's never
+    /// lower than the sample peak.
+    pub true_peak: f64,
+    /// Root mean square of the samples, in dBFS.
+    pub rms: f64,
+}
+
+impl Loudness {
+    /// Writes the measurements into the loudness fields of `bext`, raising its version to 2 if
+    /// needed. Measurements that are negative infinity are written as unset.
+    pub fn write_to(&self, bext: &mut Bext) {
+        bext.version = bext.version.max(2);
+        bext.loudness_value = hundredths(self.integrated);
+        bext.loudness_range = hundredths(self.loudness_range);
+        bext.max_true_peak_level = hundredths(self.true_peak);
+        bext.max_momentary_loudness = hundredths(self.max_momentary);
+        bext.max_short_term_loudness = hundredths(self.max_short_term);
+    }
+}
+
+/// Converts `value` to the hundredths stored in a [`Bext`] chunk.
+#[allow(clippy::cast_possible_truncation)]
+fn hundredths(value: f64) -> i16 {
+    if value.is_finite() {
+        // Clamped to the range of `i16`, the cast is exact
+        (value * 100.0)
+            .round()
+            .clamp(f64::from(i16::MIN), f64::from(i16::MAX - 1)) as i16
+    } else {
+        Bext::UNSET_LOUDNESS
+    }
+}
+
+/// Second-order IIR filter section in transposed direct form II.
+#[derive(Debug, Copy, Clone, Default)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// Returns the two stages of the K-weighting filter, a high shelf modelling the head followed by
+/// a high pass, designed for the given sampling rate so that they match the coefficients given by
+/// BS.1770 at 48kHz.
+fn k_weighting(sampling_rate: u32) -> [Biquad; 2] {
+    let rate = f64::from(sampling_rate);
+
+    let (f0, gain, q) = (
+        1_681.974_450_955_533,
+        3.999_843_853_973_347,
+        0.707_175_236_955_419_6,
+    );
+    let k = (PI * f0 / rate).tan();
+    let vh = 10f64.powf(gain / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    let (f0, q) = (38.135_470_876_024_44, 0.500_327_037_323_877_3);
+    let k = (PI * f0 / rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+/// Interpolator finding the peaks of a channel between its samples, by oversampling it with a
+/// polyphase filter of 12 taps per phase as outlined by BS.1770.
+#[derive(Debug, Clone)]
+struct TruePeak {
+    phases: Vec<[f64; TRUE_PEAK_TAPS]>,
+    history: [f64; TRUE_PEAK_TAPS],
+    peak: f64,
+}
+
+impl TruePeak {
+    /// Creates an interpolator computing `factor - 1` values between every pair of samples.
+    #[allow(clippy::cast_precision_loss)]
+    fn new(factor: u32) -> Self {
+        let half = (TRUE_PEAK_TAPS / 2) as f64;
+        let phases = (1..factor)
+            .map(|phase| {
+                let mut coefficients = [0.0; TRUE_PEAK_TAPS];
+                for (j, c) in coefficients.iter_mut().enumerate() {
+                    // Distance from the tap to the interpolated value, in samples
+                    let x = j as f64 - half + f64::from(phase) / f64::from(factor);
+                    let u = x / half;
+                    *c = sinc(x) * bessel_i0(8.0 * (1.0 - u * u).sqrt()) / bessel_i0(8.0);
+                }
+                let sum: f64 = coefficients.iter().sum();
+                coefficients.map(|c| c / sum)
+            })
+            .collect();
+
+        TruePeak {
+            phases,
+            history: [0.0; TRUE_PEAK_TAPS],
+            peak: 0.0,
+        }
+    }
+
+    fn process(&mut self, s: f64) {
+        // The newest sample is kept first, and the values are interpolated half the filter behind
+        self.history.copy_within(..TRUE_PEAK_TAPS - 1, 1);
+        self.history[0] = s;
+        for coefficients in &self.phases {
+            let value: f64 = coefficients
+                .iter()
+                .zip(&self.history)
+                .map(|(c, s)| c * s)
+                .sum();
+            self.peak = self.peak.max(value.abs());
+        }
+    }
+
+    /// Returns the highest peak, once the values following the last sample have been interpolated.
+    fn finish(mut self) -> f64 {
+        for _ in 0..TRUE_PEAK_TAPS / 2 {
+            self.process(0.0);
+        }
+        self.peak
+    }
+}
+
+/// Streaming analyzer measuring the [`Loudness`] of interleaved frames.
+///
+/// Loudness is measured in steps of 100ms, so [`Analyzer::momentary`] and
+/// [`Analyzer::short_term`] can also drive a live meter.
+#[derive(Debug, Clone)]
+pub struct Analyzer {
+    channels: usize,
+    weights: Vec<f64>,
+    filters: Vec<[Biquad; 2]>,
+    step_len: usize,
+    step_fill: usize,
+    step_sum: f64,
+    steps: VecDeque<f64>,
+    momentary: Vec<f64>,
+    short_term: Vec<f64>,
+    peaks: Vec<f64>,
+    squares: Vec<f64>,
+    frames: u64,
+    true_peaks: Vec<TruePeak>,
+}
+
+impl Analyzer {
+    /// Creates an analyzer for audio described by `header`.
+    ///
+    /// Channels are weighted by their position, following BS.1770: surround channels count 1.5dB
+    /// more than front channels, and the LFE channel isn't counted. Without a known channel layout,
+    /// every channel counts the same.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the sampling rate or the channel count of `header` is zero.
+    pub fn new(header: &Header) -> Result<Self, &'static str> {
+        if header.sampling_rate == 0 {
+            return Err("Sampling rate must not be zero");
+        }
+        if header.channel_count == 0 {
+            return Err("Channel count must not be zero");
+        }
+        let channels = usize::from(header.channel_count);
+
+        let weights = match ChannelLayout::from_header(header) {
+            Some(layout) => layout
+                .speakers()
+                .map(|speaker| match speaker {
+                    Speaker::LowFrequency => 0.0,
+                    Speaker::BackLeft
+                    | Speaker::BackRight
+                    | Speaker::SideLeft
+                    | Speaker::SideRight => 1.41,
+                    _ => 1.0,
+                })
+                .collect(),
+            None => vec![1.0; channels],
+        };
+
+        // Peaks between samples are found by oversampling to at least 192kHz
+        let factor = match header.sampling_rate {
+            0..=95_999 => 4,
+            96_000..=191_999 => 2,
+            _ => 1,
+        };
+
+        let step_len = usize::try_from(header.sampling_rate.div_ceil(10)).unwrap_or(usize::MAX);
+
+        Ok(Analyzer {
+            channels,
+            weights,
+            filters: vec![k_weighting(header.sampling_rate); channels],
+            step_len,
+            step_fill: 0,
+            step_sum: 0.0,
+            steps: VecDeque::with_capacity(SHORT_TERM_STEPS),
+            momentary: Vec::new(),
+            short_term: Vec::new(),
+            peaks: vec![0.0; channels],
+            squares: vec![0.0; channels],
+            frames: 0,
+            true_peaks: vec![TruePeak::new(factor); channels],
+        })
+    }
+
+    /// Feeds interleaved frames to the analyzer. A trailing partial frame is ignored.
+    pub fn process(&mut self, frames: &BitDepth) {
+        let samples = f32::from_bit_depth(frames);
+
+        for frame in samples.chunks_exact(self.channels) {
+            for (c, &s) in frame.iter().enumerate() {
+                let s = f64::from(s);
+                self.peaks[c] = self.peaks[c].max(s.abs());
+                self.squares[c] += s * s;
+                self.true_peaks[c].process(s);
+
+                let [head, high_pass] = &mut self.filters[c];
+                let y = high_pass.process(head.process(s));
+                self.step_sum += self.weights[c] * y * y;
+            }
+            self.frames += 1;
+
+            self.step_fill += 1;
+            if self.step_fill == self.step_len {
+                self.end_step();
+            }
+        }
+    }
+
+    /// Returns the loudness of the last 400ms, in LUFS, or negative infinity until 400ms have been
+    /// fed.
+    #[must_use]
+    pub fn momentary(&self) -> f64 {
+        self.momentary
+            .last()
+            .copied()
+            .map_or(f64::NEG_INFINITY, lufs)
+    }
+
+    /// Returns the loudness of the last 3s, in LUFS, or negative infinity until 3s have been fed.
+    #[must_use]
+    pub fn short_term(&self) -> f64 {
+        self.short_term
+            .last()
+            .copied()
+            .map_or(f64::NEG_INFINITY, lufs)
+    }
+
+    /// Returns the measurements of all the frames fed to the analyzer.
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn finish(self) -> Loudness {
+        let max = |powers: &[f64]| powers.iter().copied().fold(0.0, f64::max);
+        let frames = self.frames as f64;
+        let channels = self
+            .true_peaks
+            .into_iter()
+            .zip(self.peaks.iter().zip(&self.squares))
+            .map(|(true_peak, (&peak, &squares))| ChannelLevels {
+                sample_peak: 20.0 * peak.log10(),
+                true_peak: 20.0 * true_peak.finish().max(peak).log10(),
+                rms: if frames == 0.0 {
+                    f64::NEG_INFINITY
+                } else {
+                    10.0 * (squares / frames).log10()
+                },
+            })
+            .collect::<Vec<_>>();
+
+        Loudness {
+            integrated: integrated(&self.momentary),
+            loudness_range: loudness_range(&self.short_term),
+            max_momentary: lufs(max(&self.momentary)),
+            max_short_term: lufs(max(&self.short_term)),
+            true_peak: channels
+                .iter()
+                .map(|levels| levels.true_peak)
+                .fold(f64::NEG_INFINITY, f64::max),
+            channels,
+        }
+    }
+
+    /// Records the mean power of the gating step just completed, and of the blocks ending with it.
+    #[allow(clippy::cast_precision_loss)]
+    fn end_step(&mut self) {
+        if self.steps.len() == SHORT_TERM_STEPS {
+            self.steps.pop_front();
+        }
+        self.steps.push_back(self.step_sum);
+        self.step_sum = 0.0;
+        self.step_fill = 0;
+
+        let (steps, step_len) = (&self.steps, self.step_len);
+        let block = |count: usize| -> f64 {
+            let sum: f64 = steps.iter().rev().take(count).sum();
+            sum / (count * step_len) as f64
+        };
+        if steps.len() >= MOMENTARY_STEPS {
+            self.momentary.push(block(MOMENTARY_STEPS));
+        }
+        if steps.len() == SHORT_TERM_STEPS {
+            self.short_term.push(block(SHORT_TERM_STEPS));
+        }
+    }
+}
+
+/// Converts a mean weighted power to LUFS.
+fn lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+/// Returns the mean power of the blocks louder than `gate` LUFS, if any.
+#[allow(clippy::cast_precision_loss)]
+fn gated_mean(powers: &[f64], gate: f64) -> Option<f64> {
+    let (sum, count) = powers
+        .iter()
+        .filter(|&&power| lufs(power) > gate)
+        .fold((0.0, 0usize), |(sum, count), power| {
+            (sum + power, count + 1)
+        });
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Returns the integrated loudness given the powers of the momentary blocks, gated at -70 LUFS and
+/// then 10 LU below the loudness of the blocks passing that gate.
+fn integrated(powers: &[f64]) -> f64 {
+    gated_mean(powers, ABSOLUTE_GATE)
+        .and_then(|power| gated_mean(powers, ABSOLUTE_GATE.max(lufs(power) - 10.0)))
+        .map_or(f64::NEG_INFINITY, lufs)
+}
+
+/// Returns the loudness range given the powers of the short-term blocks: the spread between the
+/// 10th and 95th percentiles of the blocks passing the gate at -70 LUFS, and then 20 LU below the
+/// loudness of those blocks.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn loudness_range(powers: &[f64]) -> f64 {
+    let Some(power) = gated_mean(powers, ABSOLUTE_GATE) else {
+        return 0.0;
+    };
+    let gate = ABSOLUTE_GATE.max(lufs(power) - 20.0);
+
+    let mut loudness: Vec<f64> = powers
+        .iter()
+        .map(|&power| lufs(power))
+        .filter(|&l| l > gate)
+        .collect();
+    if loudness.is_empty() {
+        return 0.0;
+    }
+    loudness.sort_by(f64::total_cmp);
+
+    // Indices rounded from a non-negative fraction of the length are within bounds
+    let percentile = |p: f64| loudness[((loudness.len() - 1) as f64 * p).round() as usize];
+    percentile(0.95) - percentile(0.10)
+}
+
+/// Measures the loudness of the interleaved frames in `data`, described by `header`.
+///
+/// ## Errors
+///
+/// This function fails if the sampling rate or the channel count of `header` is zero.
+pub fn analyze(header: &Header, data: &BitDepth) -> Result<Loudness, &'static str> {
+    let mut analyzer = Analyzer::new(header)?;
+    analyzer.process(data);
+    Ok(analyzer.finish())
+}
+
+/// Measures the loudness of the wave file in `reader`, decoding it in blocks.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`WavReader::new`], if the sampling rate or
+/// the channel count of the file is zero, or if any error occurs from the `reader` during reading.
+pub fn analyze_reader<R>(reader: R) -> io::Result<Loudness>
+where
+    R: Read + Seek,
+{
+    let mut reader = WavReader::new(reader)?;
+    let mut analyzer = Analyzer::new(reader.header())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    while reader.position() < reader.frame_count() {
+        analyzer.process(&reader.read_frames(BLOCK_FRAMES)?);
+    }
+
+    Ok(analyzer.finish())
+}
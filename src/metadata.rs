@@ -0,0 +1,88 @@
+//! Contains types for the metadata chunks defined by broadcast and production standards.
+//!
+//! Every type converts from the [`Chunk`] holding it with [`TryFrom`], checking the chunk ID, and
+//! back into a [`Chunk`] ready to be passed to [`crate::write_with_chunks`].
+//!
+//! ## Example
+//!
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use std::{convert::TryFrom, fs::File};
+//! use wav::metadata::Bext;
+//!
+//! let chunks = wav::read_chunks(&mut File::open("data/sine.wav")?)?;
+//! let bext = chunks
+//!     .iter()
+//!     .find(|c| c.id == Bext::ID)
+//!     .map(Bext::try_from)
+//!     .transpose()
+//!     .map_err(std::io::Error::other)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod acid;
+pub use acid::Acid;
+
+mod adm;
+pub use adm::{Adm, AudioContent, AudioId, AudioObject, AudioProgramme, Axml, Chna};
+
+mod bext;
+pub use bext::Bext;
+
+mod cart;
+pub use cart::{Cart, PostTimer};
+
+mod cue;
+pub use cue::{Cue, CuePoint};
+
+mod id3;
+pub use id3::{Comment, Id3, Id3Frame, Picture};
+
+mod ixml;
+pub use ixml::{Ixml, Rate, Speed, Track};
+
+mod levl;
+pub use levl::{Levl, Peak, PeakFormat};
+
+mod plst;
+pub use plst::{Plst, PlstFrames, Segment};
+
+mod xml;
+
+use crate::Chunk;
+
+/// Returns the text stored in a fixed-width field, which ends at the first NUL byte if it doesn't
+/// fill the field.
+fn read_text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Appends `text` to `out` as a fixed-width field of `len` bytes, padded with NUL bytes.
+fn write_text(out: &mut Vec<u8>, text: &str, len: usize) -> Result<(), &'static str> {
+    if text.len() > len {
+        return Err("Text too long for its field");
+    }
+    out.extend_from_slice(text.as_bytes());
+    out.resize(out.len() + len - text.len(), 0);
+
+    Ok(())
+}
+
+/// Like [`write_text`], for fields that may only hold ASCII.
+fn write_ascii(out: &mut Vec<u8>, text: &str, len: usize) -> Result<(), &'static str> {
+    if !text.is_ascii() {
+        return Err("Text must be ASCII");
+    }
+    write_text(out, text, len)
+}
+
+/// Checks that `chunk` has the ID `id`.
+fn check_id(chunk: &Chunk, id: [u8; 4]) -> Result<(), &'static str> {
+    if chunk.id == id {
+        Ok(())
+    } else {
+        Err("Chunk has the wrong ID")
+    }
+}
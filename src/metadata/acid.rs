@@ -0,0 +1,146 @@
+use std::convert::TryFrom;
+
+use super::check_id;
+use crate::Chunk;
+
+/// Size of the chunk.
+const LEN: usize = 24;
+
+const ONE_SHOT: u32 = 0x01;
+const ROOT_NOTE: u32 = 0x02;
+const STRETCH: u32 = 0x04;
+const DISK_BASED: u32 = 0x08;
+
+/// Loop information, the `"acid"` chunk written by ACID and read by most DAWs to stretch loops
+/// to the tempo of a project.
+///
+/// ## Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use wav::{metadata::Acid, Chunk};
+///
+/// let acid = Acid {
+///     root_note: Some(57),
+///     beats: 8,
+///     tempo: 120.0,
+///     ..Acid::default()
+/// };
+/// let chunk = Chunk::from(&acid);
+/// assert_eq!(Acid::try_from(&chunk), Ok(acid));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Acid {
+    /// Whether the file is a one-shot played once, rather than a loop.
+    pub one_shot: bool,
+    /// MIDI note number of the root note, 60 being middle C, for files that can be transposed to
+    /// the key of a project.
+    pub root_note: Option<u16>,
+    /// Whether the file should be stretched to the tempo of a project.
+    pub stretch: bool,
+    /// Whether the file should be streamed from disk rather than loaded in memory.
+    pub disk_based: bool,
+    /// Number of beats in the file.
+    pub beats: u32,
+    /// Number of beats in a bar, the numerator of the meter.
+    pub meter_numerator: u16,
+    /// Note value of a beat, the denominator of the meter.
+    pub meter_denominator: u16,
+    /// Tempo in beats per minute.
+    pub tempo: f32,
+}
+
+impl Acid {
+    /// The chunk ID of the loop information.
+    pub const ID: [u8; 4] = *b"acid";
+}
+
+impl Default for Acid {
+    /// A stretched loop in 4/4 at 120 beats per minute, with no beats or root note.
+    fn default() -> Self {
+        Acid {
+            one_shot: false,
+            root_note: None,
+            stretch: true,
+            disk_based: false,
+            beats: 0,
+            meter_numerator: 4,
+            meter_denominator: 4,
+            tempo: 120.0,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Acid {
+    type Error = &'static str;
+
+    /// Parses the contents of an `"acid"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the fields of the chunk.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        if v.len() < LEN {
+            return Err("Acid chunk too short");
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([v[i], v[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes([v[i], v[i + 1], v[i + 2], v[i + 3]]);
+
+        let flags = u32_at(0);
+        Ok(Acid {
+            one_shot: flags & ONE_SHOT != 0,
+            root_note: Some(u16_at(4)).filter(|_| flags & ROOT_NOTE != 0),
+            stretch: flags & STRETCH != 0,
+            disk_based: flags & DISK_BASED != 0,
+            beats: u32_at(12),
+            meter_denominator: u16_at(16),
+            meter_numerator: u16_at(18),
+            tempo: f32::from_bits(u32_at(20)),
+        })
+    }
+}
+
+impl TryFrom<&Chunk> for Acid {
+    type Error = &'static str;
+
+    /// Parses an `"acid"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't an `"acid"` chunk, or if it's too short.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Acid::ID)?;
+        Acid::try_from(chunk.data.as_slice())
+    }
+}
+
+impl From<&Acid> for Chunk {
+    /// Writes `acid` as an `"acid"` chunk.
+    fn from(acid: &Acid) -> Self {
+        let mut flags = 0;
+        for (set, flag) in [
+            (acid.one_shot, ONE_SHOT),
+            (acid.root_note.is_some(), ROOT_NOTE),
+            (acid.stretch, STRETCH),
+            (acid.disk_based, DISK_BASED),
+        ] {
+            if set {
+                flags |= flag;
+            }
+        }
+
+        let mut v = Vec::with_capacity(LEN);
+        v.extend_from_slice(&flags.to_le_bytes());
+        v.extend_from_slice(&acid.root_note.unwrap_or(60).to_le_bytes());
+        // Fields of unknown meaning, set to the values ACID writes
+        v.extend_from_slice(&0x8000u16.to_le_bytes());
+        v.extend_from_slice(&0f32.to_le_bytes());
+        v.extend_from_slice(&acid.beats.to_le_bytes());
+        v.extend_from_slice(&acid.meter_denominator.to_le_bytes());
+        v.extend_from_slice(&acid.meter_numerator.to_le_bytes());
+        v.extend_from_slice(&acid.tempo.to_le_bytes());
+
+        Chunk::new(Acid::ID, v)
+    }
+}
@@ -0,0 +1,328 @@
+use std::convert::TryFrom;
+
+use super::{
+    check_id, read_text, write_ascii,
+    xml::{self, local_name, Element},
+};
+use crate::Chunk;
+
+/// Size of the track and ID counts preceding the audio IDs.
+const HEADER_LEN: usize = 4;
+
+/// Size of an audio ID.
+const AUDIO_ID_LEN: usize = 40;
+
+/// Entry of a [`Chna`] chunk, linking a track of the file to the Audio Definition Model.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioId {
+    /// Index of the channel of the file, from 1.
+    pub track_index: u16,
+    /// ID of the `audioTrackUID` element, such as `"ATU_00000001"`, 12 bytes.
+    pub uid: String,
+    /// ID of the `audioTrackFormat` element, such as `"AT_00010001_01"`, 14 bytes.
+    pub track_format_ref: String,
+    /// ID of the `audioPackFormat` element, such as `"AP_00010002"`, 11 bytes, or empty.
+    pub pack_format_ref: String,
+}
+
+/// Channel allocation, the `"chna"` chunk specified by ITU-R BS.2088 mapping the tracks of a file
+/// to the Audio Definition Model of its [`Axml`] chunk.
+///
+/// ## Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use wav::{
+///     metadata::{AudioId, Chna},
+///     Chunk,
+/// };
+///
+/// let chna = Chna {
+///     ids: vec![AudioId {
+///         track_index: 1,
+///         uid: "ATU_00000001".to_string(),
+///         track_format_ref: "AT_00010003_01".to_string(),
+///         pack_format_ref: "AP_00010001".to_string(),
+///     }],
+/// };
+/// let chunk = Chunk::try_from(&chna).unwrap();
+/// assert_eq!(Chna::try_from(&chunk), Ok(chna));
+/// ```
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chna {
+    /// The audio IDs, several of which may share a track.
+    pub ids: Vec<AudioId>,
+}
+
+impl Chna {
+    /// The chunk ID of the channel allocation.
+    pub const ID: [u8; 4] = *b"chna";
+
+    /// Returns the number of tracks referred to by the audio IDs.
+    #[must_use]
+    pub fn track_count(&self) -> usize {
+        let mut tracks: Vec<_> = self.ids.iter().map(|id| id.track_index).collect();
+        tracks.sort_unstable();
+        tracks.dedup();
+        tracks.len()
+    }
+}
+
+impl TryFrom<&[u8]> for Chna {
+    type Error = &'static str;
+
+    /// Parses the contents of a `"chna"` chunk. Unused audio IDs, whose track index is 0, are
+    /// skipped.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the counts of the chunk.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        if v.len() < HEADER_LEN {
+            return Err("Channel allocation chunk too short");
+        }
+
+        let ids = v[HEADER_LEN..]
+            .chunks_exact(AUDIO_ID_LEN)
+            .map(|id| AudioId {
+                track_index: u16::from_le_bytes([id[0], id[1]]),
+                uid: read_text(&id[2..14]),
+                track_format_ref: read_text(&id[14..28]),
+                pack_format_ref: read_text(&id[28..39]),
+            })
+            .filter(|id| id.track_index != 0)
+            .collect();
+
+        Ok(Chna { ids })
+    }
+}
+
+impl TryFrom<&Chunk> for Chna {
+    type Error = &'static str;
+
+    /// Parses a `"chna"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't a `"chna"` chunk, or if it's too short.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Chna::ID)?;
+        Chna::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Chna> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `chna` as a `"chna"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if there are more than `u16::MAX` audio IDs, if a track index is 0,
+    /// or if an ID isn't ASCII or is longer than its width.
+    fn try_from(chna: &Chna) -> Result<Self, Self::Error> {
+        let id_count = u16::try_from(chna.ids.len()).map_err(|_| "Too many audio IDs")?;
+        // Never more tracks than IDs
+        let track_count = u16::try_from(chna.track_count()).unwrap_or(id_count);
+
+        let mut v = Vec::with_capacity(HEADER_LEN + AUDIO_ID_LEN * chna.ids.len());
+        v.extend_from_slice(&track_count.to_le_bytes());
+        v.extend_from_slice(&id_count.to_le_bytes());
+        for id in &chna.ids {
+            if id.track_index == 0 {
+                return Err("Audio ID track index must be at least 1");
+            }
+            v.extend_from_slice(&id.track_index.to_le_bytes());
+            write_ascii(&mut v, &id.uid, 12)?;
+            write_ascii(&mut v, &id.track_format_ref, 14)?;
+            write_ascii(&mut v, &id.pack_format_ref, 11)?;
+            v.push(0);
+        }
+
+        Ok(Chunk::new(Chna::ID, v))
+    }
+}
+
+/// `audioProgramme` element of the Audio Definition Model, a complete mix such as a language
+/// version.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioProgramme {
+    /// `audioProgrammeID`, such as `"APR_1001"`.
+    pub id: String,
+    /// `audioProgrammeName`.
+    pub name: String,
+    /// IDs of the contents of the programme.
+    pub content_refs: Vec<String>,
+}
+
+/// `audioContent` element of the Audio Definition Model, a part of a programme such as the
+/// dialogue or the music.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioContent {
+    /// `audioContentID`, such as `"ACO_1001"`.
+    pub id: String,
+    /// `audioContentName`.
+    pub name: String,
+    /// IDs of the objects of the content.
+    pub object_refs: Vec<String>,
+}
+
+/// `audioObject` element of the Audio Definition Model, grouping the tracks of a bed or of a
+/// moving object.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioObject {
+    /// `audioObjectID`, such as `"AO_1001"`.
+    pub id: String,
+    /// `audioObjectName`.
+    pub name: String,
+    /// IDs of the pack formats of the object, such as `"AP_00010002"` for stereo.
+    pub pack_format_refs: Vec<String>,
+    /// IDs of the track UIDs of the object, listed in the [`Chna`] chunk.
+    pub track_uid_refs: Vec<String>,
+}
+
+/// The main elements of an Audio Definition Model document, read with [`Axml::adm`].
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Adm {
+    /// The programmes, in document order.
+    pub programmes: Vec<AudioProgramme>,
+    /// The contents, in document order.
+    pub contents: Vec<AudioContent>,
+    /// The objects, in document order.
+    pub objects: Vec<AudioObject>,
+}
+
+/// Audio Definition Model, the `"axml"` chunk specified by ITU-R BS.2076 and BS.2088 describing
+/// the programmes, objects and channels of object-based and immersive audio.
+///
+/// The XML is kept as stored. The [`Chna`] chunk maps the tracks of the file to the document.
+///
+/// ## Example
+///
+/// ```
+/// use wav::metadata::Axml;
+///
+/// let axml = Axml::new(
+///     r#"<ebuCoreMain><coreMetadata><format><audioFormatExtended>
+///         <audioProgramme audioProgrammeID="APR_1001" audioProgrammeName="Main">
+///             <audioContentIDRef>ACO_1001</audioContentIDRef>
+///         </audioProgramme>
+///     </audioFormatExtended></format></coreMetadata></ebuCoreMain>"#,
+/// );
+/// let adm = axml.adm().unwrap();
+/// assert_eq!(adm.programmes[0].name, "Main");
+/// assert_eq!(adm.programmes[0].content_refs, ["ACO_1001"]);
+/// ```
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Axml {
+    /// The XML document.
+    pub xml: String,
+}
+
+impl Axml {
+    /// The chunk ID of the Audio Definition Model.
+    pub const ID: [u8; 4] = *b"axml";
+
+    /// Creates the chunk holding the document `xml`.
+    pub fn new(xml: impl Into<String>) -> Self {
+        Axml { xml: xml.into() }
+    }
+
+    /// Reads the programmes, contents and objects of the `audioFormatExtended` element of the
+    /// document. Namespace prefixes are ignored.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the document isn't well-formed XML, or if it has no
+    /// `audioFormatExtended` element.
+    pub fn adm(&self) -> Result<Adm, &'static str> {
+        let root = xml::parse(&self.xml)?;
+        let format = root
+            .descendant("audioFormatExtended")
+            .ok_or("ADM document has no audioFormatExtended element")?;
+
+        let named = |name: &'static str| {
+            format
+                .elements()
+                .filter(move |element| local_name(&element.name) == name)
+        };
+        let attribute =
+            |element: &Element, name: &str| element.attribute(name).unwrap_or_default().to_string();
+        let refs = |element: &Element, name: &str| {
+            element
+                .elements()
+                .filter(|child| local_name(&child.name) == name)
+                .map(|child| child.text().trim().to_string())
+                .collect()
+        };
+
+        Ok(Adm {
+            programmes: named("audioProgramme")
+                .map(|p| AudioProgramme {
+                    id: attribute(p, "audioProgrammeID"),
+                    name: attribute(p, "audioProgrammeName"),
+                    content_refs: refs(p, "audioContentIDRef"),
+                })
+                .collect(),
+            contents: named("audioContent")
+                .map(|c| AudioContent {
+                    id: attribute(c, "audioContentID"),
+                    name: attribute(c, "audioContentName"),
+                    object_refs: refs(c, "audioObjectIDRef"),
+                })
+                .collect(),
+            objects: named("audioObject")
+                .map(|o| AudioObject {
+                    id: attribute(o, "audioObjectID"),
+                    name: attribute(o, "audioObjectName"),
+                    pack_format_refs: refs(o, "audioPackFormatIDRef"),
+                    track_uid_refs: refs(o, "audioTrackUIDRef"),
+                })
+                .collect(),
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for Axml {
+    type Error = &'static str;
+
+    /// Reads the contents of an `"axml"` chunk, ignoring trailing NUL bytes. The XML isn't
+    /// checked until [`Axml::adm`] is called.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` isn't UTF-8.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        let end = v.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        let text = std::str::from_utf8(&v[..end]).map_err(|_| "axml chunk isn't UTF-8")?;
+        Ok(Axml::new(text))
+    }
+}
+
+impl TryFrom<&Chunk> for Axml {
+    type Error = &'static str;
+
+    /// Reads an `"axml"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't an `"axml"` chunk, or if it isn't UTF-8.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Axml::ID)?;
+        Axml::try_from(chunk.data.as_slice())
+    }
+}
+
+impl From<&Axml> for Chunk {
+    /// Writes `axml` as an `"axml"` chunk.
+    fn from(axml: &Axml) -> Self {
+        Chunk::new(Axml::ID, axml.xml.as_bytes().to_vec())
+    }
+}
@@ -0,0 +1,163 @@
+use std::convert::TryFrom;
+
+use super::{check_id, read_text, write_text};
+use crate::Chunk;
+
+/// Size of the fields preceding the coding history.
+const FIXED_LEN: usize = 602;
+
+/// Broadcast audio extension, the `"bext"` chunk of a Broadcast Wave Format file as specified by
+/// EBU Tech 3285.
+///
+/// Text fields are limited to the width given for each of them, and are written as ASCII by most
+/// software. The loudness fields are only meaningful from version 2 onwards, and hold hundredths
+/// of their unit, or [`Bext::UNSET_LOUDNESS`] when they haven't been measured.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bext {
+    /// Free description of the sound sequence, up to 256 bytes.
+    pub description: String,
+    /// Name of the originator, up to 32 bytes.
+    pub originator: String,
+    /// Unambiguous reference allocated by the originating organisation, up to 32 bytes.
+    pub originator_reference: String,
+    /// Date of creation, formatted as `yyyy-mm-dd`.
+    pub origination_date: String,
+    /// Time of creation, formatted as `hh:mm:ss`.
+    pub origination_time: String,
+    /// Number of samples since midnight at the first sample of the file.
+    pub time_reference: u64,
+    /// Version of the chunk's format.
+    pub version: u16,
+    /// SMPTE 330M unique material identifier, all zero when unused.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::byte_array"))]
+    pub umid: [u8; 64],
+    /// Integrated loudness, in hundredths of LUFS.
+    pub loudness_value: i16,
+    /// Loudness range, in hundredths of LU.
+    pub loudness_range: i16,
+    /// Maximum true peak level, in hundredths of dBTP.
+    pub max_true_peak_level: i16,
+    /// Highest momentary loudness, in hundredths of LUFS.
+    pub max_momentary_loudness: i16,
+    /// Highest short-term loudness, in hundredths of LUFS.
+    pub max_short_term_loudness: i16,
+    /// Lines describing the coding processes applied to the audio, each ended by `"\r\n"`.
+    pub coding_history: String,
+}
+
+impl Bext {
+    /// The chunk ID of the broadcast audio extension.
+    pub const ID: [u8; 4] = *b"bext";
+
+    /// Value of the loudness fields that haven't been measured.
+    pub const UNSET_LOUDNESS: i16 = 0x7FFF;
+}
+
+impl Default for Bext {
+    fn default() -> Self {
+        Bext {
+            description: String::new(),
+            originator: String::new(),
+            originator_reference: String::new(),
+            origination_date: String::new(),
+            origination_time: String::new(),
+            time_reference: 0,
+            version: 2,
+            umid: [0; 64],
+            loudness_value: Bext::UNSET_LOUDNESS,
+            loudness_range: Bext::UNSET_LOUDNESS,
+            max_true_peak_level: Bext::UNSET_LOUDNESS,
+            max_momentary_loudness: Bext::UNSET_LOUDNESS,
+            max_short_term_loudness: Bext::UNSET_LOUDNESS,
+            coding_history: String::new(),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Bext {
+    type Error = &'static str;
+
+    /// Parses the contents of a `"bext"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the fixed fields of the chunk.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        if v.len() < FIXED_LEN {
+            return Err("Broadcast extension chunk too short");
+        }
+
+        let u16_at = |i: usize| u16::from_le_bytes([v[i], v[i + 1]]);
+        let i16_at = |i: usize| i16::from_le_bytes([v[i], v[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes([v[i], v[i + 1], v[i + 2], v[i + 3]]);
+
+        let mut umid = [0; 64];
+        umid.copy_from_slice(&v[348..412]);
+
+        Ok(Bext {
+            description: read_text(&v[..256]),
+            originator: read_text(&v[256..288]),
+            originator_reference: read_text(&v[288..320]),
+            origination_date: read_text(&v[320..330]),
+            origination_time: read_text(&v[330..338]),
+            time_reference: u64::from(u32_at(338)) | u64::from(u32_at(342)) << 32,
+            version: u16_at(346),
+            umid,
+            loudness_value: i16_at(412),
+            loudness_range: i16_at(414),
+            max_true_peak_level: i16_at(416),
+            max_momentary_loudness: i16_at(418),
+            max_short_term_loudness: i16_at(420),
+            coding_history: read_text(&v[FIXED_LEN..]),
+        })
+    }
+}
+
+impl TryFrom<&Chunk> for Bext {
+    type Error = &'static str;
+
+    /// Parses a `"bext"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't a `"bext"` chunk, or if it's too short.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Bext::ID)?;
+        Bext::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Bext> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `bext` as a `"bext"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if any text field is longer than its width.
+    fn try_from(bext: &Bext) -> Result<Self, Self::Error> {
+        let mut v = Vec::with_capacity(FIXED_LEN + bext.coding_history.len());
+        write_text(&mut v, &bext.description, 256)?;
+        write_text(&mut v, &bext.originator, 32)?;
+        write_text(&mut v, &bext.originator_reference, 32)?;
+        write_text(&mut v, &bext.origination_date, 10)?;
+        write_text(&mut v, &bext.origination_time, 8)?;
+        v.extend_from_slice(&bext.time_reference.to_le_bytes());
+        v.extend_from_slice(&bext.version.to_le_bytes());
+        v.extend_from_slice(&bext.umid);
+        for loudness in [
+            bext.loudness_value,
+            bext.loudness_range,
+            bext.max_true_peak_level,
+            bext.max_momentary_loudness,
+            bext.max_short_term_loudness,
+        ] {
+            v.extend_from_slice(&loudness.to_le_bytes());
+        }
+        v.resize(FIXED_LEN, 0);
+        v.extend_from_slice(bext.coding_history.as_bytes());
+
+        Ok(Chunk::new(Bext::ID, v))
+    }
+}
@@ -0,0 +1,221 @@
+use std::convert::TryFrom;
+
+use super::{check_id, read_text, write_ascii};
+use crate::Chunk;
+
+/// Size of the fields preceding the tag text.
+const FIXED_LEN: usize = 2048;
+
+/// Number of post timer slots in the chunk.
+const POST_TIMERS: usize = 8;
+
+/// Offset of the level reference, which follows the text fields.
+const LEVEL_REFERENCE: usize = 680;
+
+/// Offset of the URL, which follows the post timers and reserved bytes.
+const URL: usize = 1024;
+
+/// Marker of a position in the audio, such as the end of an intro or the start of a segue.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PostTimer {
+    /// What the timer marks, like `"INT1"` for the end of an intro or `"SEG1"` for the start of a
+    /// segue.
+    pub usage: [u8; 4],
+    /// Position of the marker, in frames from the start of the audio.
+    pub value: u32,
+}
+
+/// Cart chunk, the `"cart"` chunk specified by AES46 for exchanging the metadata of radio
+/// automation systems.
+///
+/// Text fields may only hold ASCII and are limited to the width given for each of them.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cart {
+    /// Version of the chunk's format, as 4 digits such as `"0101"` for version 1.01.
+    pub version: String,
+    /// Title of the cut, up to 64 bytes.
+    pub title: String,
+    /// Artist of the cut, up to 64 bytes.
+    pub artist: String,
+    /// Identifier of the cut in the automation system, up to 64 bytes.
+    pub cut_id: String,
+    /// Identifier of the client, up to 64 bytes.
+    pub client_id: String,
+    /// Category of the cut, such as `"NEWS"` or `"SPOT"`, up to 64 bytes.
+    pub category: String,
+    /// Classification of the cut, up to 64 bytes.
+    pub classification: String,
+    /// Text describing the end of the cut, up to 64 bytes.
+    pub out_cue: String,
+    /// First day the cut may be aired, formatted as `yyyy/mm/dd`.
+    pub start_date: String,
+    /// Time of day from which the cut may be aired, formatted as `hh:mm:ss`.
+    pub start_time: String,
+    /// Last day the cut may be aired, formatted as `yyyy/mm/dd`.
+    pub end_date: String,
+    /// Time of day until which the cut may be aired, formatted as `hh:mm:ss`.
+    pub end_time: String,
+    /// Name of the software that created the chunk, up to 64 bytes.
+    pub producer_app_id: String,
+    /// Version of the software that created the chunk, up to 64 bytes.
+    pub producer_app_version: String,
+    /// Text defined by the user, up to 64 bytes.
+    pub user_def: String,
+    /// Sample value of 0dB reference level, 0 if unknown.
+    pub level_reference: i32,
+    /// Markers of positions in the audio, up to 8.
+    pub post_timers: Vec<PostTimer>,
+    /// Address of a web page about the cut, up to 1024 bytes.
+    pub url: String,
+    /// Free text, usually lines ended by `"\r\n"`.
+    pub tag_text: String,
+}
+
+impl Cart {
+    /// The chunk ID of the cart chunk.
+    pub const ID: [u8; 4] = *b"cart";
+}
+
+impl Default for Cart {
+    fn default() -> Self {
+        Cart {
+            version: "0101".to_string(),
+            title: String::new(),
+            artist: String::new(),
+            cut_id: String::new(),
+            client_id: String::new(),
+            category: String::new(),
+            classification: String::new(),
+            out_cue: String::new(),
+            start_date: String::new(),
+            start_time: String::new(),
+            end_date: String::new(),
+            end_time: String::new(),
+            producer_app_id: String::new(),
+            producer_app_version: String::new(),
+            user_def: String::new(),
+            level_reference: 0,
+            post_timers: Vec::new(),
+            url: String::new(),
+            tag_text: String::new(),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Cart {
+    type Error = &'static str;
+
+    /// Parses the contents of a `"cart"` chunk. Unused post timers, whose usage is all zero, are
+    /// skipped.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the fixed fields of the chunk.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        if v.len() < FIXED_LEN {
+            return Err("Cart chunk too short");
+        }
+
+        let mut pos = 0;
+        let mut text = |len: usize| {
+            pos += len;
+            read_text(&v[pos - len..pos])
+        };
+        let mut cart = Cart {
+            version: text(4),
+            title: text(64),
+            artist: text(64),
+            cut_id: text(64),
+            client_id: text(64),
+            category: text(64),
+            classification: text(64),
+            out_cue: text(64),
+            start_date: text(10),
+            start_time: text(8),
+            end_date: text(10),
+            end_time: text(8),
+            producer_app_id: text(64),
+            producer_app_version: text(64),
+            user_def: text(64),
+            url: read_text(&v[URL..FIXED_LEN]),
+            tag_text: read_text(&v[FIXED_LEN..]),
+            ..Cart::default()
+        };
+
+        let i = LEVEL_REFERENCE;
+        cart.level_reference = i32::from_le_bytes([v[i], v[i + 1], v[i + 2], v[i + 3]]);
+        cart.post_timers = v[i + 4..i + 4 + 8 * POST_TIMERS]
+            .chunks_exact(8)
+            .map(|t| PostTimer {
+                usage: [t[0], t[1], t[2], t[3]],
+                value: u32::from_le_bytes([t[4], t[5], t[6], t[7]]),
+            })
+            .filter(|timer| timer.usage != [0; 4])
+            .collect();
+
+        Ok(cart)
+    }
+}
+
+impl TryFrom<&Chunk> for Cart {
+    type Error = &'static str;
+
+    /// Parses a `"cart"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't a `"cart"` chunk, or if it's too short.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Cart::ID)?;
+        Cart::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Cart> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `cart` as a `"cart"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if any text field isn't ASCII or is longer than its width, or if there
+    /// are more than 8 post timers.
+    fn try_from(cart: &Cart) -> Result<Self, Self::Error> {
+        if cart.post_timers.len() > POST_TIMERS {
+            return Err("Cart chunk holds at most 8 post timers");
+        }
+
+        let mut v = Vec::with_capacity(FIXED_LEN + cart.tag_text.len());
+        for (text, len) in [
+            (&cart.version, 4),
+            (&cart.title, 64),
+            (&cart.artist, 64),
+            (&cart.cut_id, 64),
+            (&cart.client_id, 64),
+            (&cart.category, 64),
+            (&cart.classification, 64),
+            (&cart.out_cue, 64),
+            (&cart.start_date, 10),
+            (&cart.start_time, 8),
+            (&cart.end_date, 10),
+            (&cart.end_time, 8),
+            (&cart.producer_app_id, 64),
+            (&cart.producer_app_version, 64),
+            (&cart.user_def, 64),
+        ] {
+            write_ascii(&mut v, text, len)?;
+        }
+        v.extend_from_slice(&cart.level_reference.to_le_bytes());
+        for timer in &cart.post_timers {
+            v.extend_from_slice(&timer.usage);
+            v.extend_from_slice(&timer.value.to_le_bytes());
+        }
+        v.resize(URL, 0);
+        write_ascii(&mut v, &cart.url, FIXED_LEN - URL)?;
+        write_ascii(&mut v, &cart.tag_text, cart.tag_text.len())?;
+
+        Ok(Chunk::new(Cart::ID, v))
+    }
+}
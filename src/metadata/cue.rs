@@ -0,0 +1,162 @@
+use std::convert::TryFrom;
+
+use super::check_id;
+use crate::Chunk;
+
+/// Size of a cue point.
+const CUE_POINT_LEN: usize = 24;
+
+/// Marker of a position in the audio, an entry of a [`Cue`] chunk.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CuePoint {
+    /// Identifier of the cue point, unique within the chunk, which labels and playlists refer to.
+    pub id: u32,
+    /// Position of the cue point when the file is played through a playlist, usually the same as
+    /// [`CuePoint::sample_offset`].
+    pub position: u32,
+    /// ID of the chunk holding the cue point, `*b"data"` unless the audio is stored as a wave
+    /// list.
+    pub chunk_id: [u8; 4],
+    /// Offset of the chunk holding the cue point within a wave list, 0 for a `"data"` chunk.
+    pub chunk_start: u32,
+    /// Offset of the block holding the cue point within compressed audio, 0 for uncompressed
+    /// audio.
+    pub block_start: u32,
+    /// Frame of the cue point, counted from the start of its chunk.
+    pub sample_offset: u32,
+}
+
+impl CuePoint {
+    /// Creates the cue point `id` at `frame` of the `"data"` chunk.
+    #[must_use]
+    pub fn new(id: u32, frame: u32) -> Self {
+        CuePoint {
+            id,
+            position: frame,
+            chunk_id: *b"data",
+            chunk_start: 0,
+            block_start: 0,
+            sample_offset: frame,
+        }
+    }
+}
+
+/// Cue points, the `"cue "` chunk marking positions in the audio for labels, loops and
+/// [`crate::metadata::Plst`] playlists.
+///
+/// ## Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use wav::{
+///     metadata::{Cue, CuePoint},
+///     Chunk,
+/// };
+///
+/// let cue = Cue {
+///     points: vec![CuePoint::new(1, 0), CuePoint::new(2, 48_000)],
+/// };
+/// let chunk = Chunk::try_from(&cue).unwrap();
+/// assert_eq!(Cue::try_from(&chunk), Ok(cue));
+/// ```
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cue {
+    /// The cue points, in the order they're stored.
+    pub points: Vec<CuePoint>,
+}
+
+impl Cue {
+    /// The chunk ID of the cue points.
+    pub const ID: [u8; 4] = *b"cue ";
+
+    /// Returns the cue point with the identifier `id`.
+    #[must_use]
+    pub fn point(&self, id: u32) -> Option<&CuePoint> {
+        self.points.iter().find(|point| point.id == id)
+    }
+}
+
+impl TryFrom<&[u8]> for Cue {
+    type Error = &'static str;
+
+    /// Parses the contents of a `"cue "` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the cue points it counts.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        let too_short = "Cue chunk too short";
+        let count = v.get(..4).ok_or(too_short)?;
+        let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]);
+        let points = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(CUE_POINT_LEN))
+            .and_then(|len| v.get(4..len.checked_add(4)?))
+            .ok_or(too_short)?;
+
+        let points = points
+            .chunks_exact(CUE_POINT_LEN)
+            .map(|p| {
+                let u32_at = |i: usize| u32::from_le_bytes([p[i], p[i + 1], p[i + 2], p[i + 3]]);
+                CuePoint {
+                    id: u32_at(0),
+                    position: u32_at(4),
+                    chunk_id: [p[8], p[9], p[10], p[11]],
+                    chunk_start: u32_at(12),
+                    block_start: u32_at(16),
+                    sample_offset: u32_at(20),
+                }
+            })
+            .collect();
+
+        Ok(Cue { points })
+    }
+}
+
+impl TryFrom<&Chunk> for Cue {
+    type Error = &'static str;
+
+    /// Parses a `"cue "` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't a `"cue "` chunk, or if it's too short.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Cue::ID)?;
+        Cue::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Cue> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `cue` as a `"cue "` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if there are more than `u32::MAX` cue points, or if two of them have
+    /// the same identifier.
+    fn try_from(cue: &Cue) -> Result<Self, Self::Error> {
+        let count = u32::try_from(cue.points.len()).map_err(|_| "Too many cue points")?;
+        let mut ids: Vec<_> = cue.points.iter().map(|point| point.id).collect();
+        ids.sort_unstable();
+        if ids.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err("Cue point identifiers must be unique");
+        }
+
+        let mut v = Vec::with_capacity(4 + CUE_POINT_LEN * cue.points.len());
+        v.extend_from_slice(&count.to_le_bytes());
+        for point in &cue.points {
+            v.extend_from_slice(&point.id.to_le_bytes());
+            v.extend_from_slice(&point.position.to_le_bytes());
+            v.extend_from_slice(&point.chunk_id);
+            v.extend_from_slice(&point.chunk_start.to_le_bytes());
+            v.extend_from_slice(&point.block_start.to_le_bytes());
+            v.extend_from_slice(&point.sample_offset.to_le_bytes());
+        }
+
+        Ok(Chunk::new(Cue::ID, v))
+    }
+}
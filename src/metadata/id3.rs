@@ -0,0 +1,546 @@
+use std::convert::TryFrom;
+
+use crate::Chunk;
+
+/// Size of the tag header and of every frame header.
+const HEADER_LEN: usize = 10;
+
+/// Largest size that can be stored as a synchsafe integer.
+const MAX_SYNCHSAFE: usize = (1 << 28) - 1;
+
+// Text encodings of ID3v2 strings
+const LATIN_1: u8 = 0;
+const UTF_16: u8 = 1;
+const UTF_16_BE: u8 = 2;
+const UTF_8: u8 = 3;
+
+/// Comment frame, `"COMM"`.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment {
+    /// ISO 639-2 code of the language of the comment, such as `*b"eng"`.
+    pub language: [u8; 3],
+    /// Short description telling comments apart, often empty.
+    pub description: String,
+    /// The comment.
+    pub text: String,
+}
+
+/// Attached picture frame, `"APIC"`, such as the album cover.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Picture {
+    /// MIME type of the image, such as `"image/jpeg"`.
+    pub mime_type: String,
+    /// What the picture shows, 3 being the front cover.
+    pub picture_type: u8,
+    /// Description of the picture.
+    pub description: String,
+    /// The image file.
+    pub data: Vec<u8>,
+}
+
+/// A frame of an [`Id3`] tag.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Id3Frame {
+    /// Text information frame, whose ID starts with `T`, other than `"TXXX"`. Frames of ID3v2.4
+    /// holding several values have them separated by NUL characters.
+    Text {
+        /// The frame ID, such as `*b"TIT2"` for the title.
+        id: [u8; 4],
+        /// The text of the frame.
+        text: String,
+    },
+    /// Comment frame.
+    Comment(Comment),
+    /// Attached picture frame.
+    Picture(Picture),
+    /// Any other frame, or a frame with any flag set, such as a compressed frame or one to
+    /// discard when the tag is altered, with its contents as stored so the flags still apply.
+    Other {
+        /// The frame ID.
+        id: [u8; 4],
+        /// The frame flags, whose meaning depends on the version of the tag.
+        flags: [u8; 2],
+        /// The contents of the frame.
+        data: Vec<u8>,
+    },
+}
+
+/// `ID3v2` tag, stored by music software in an `"id3 "` chunk.
+///
+/// Versions 2.3 and 2.4 are supported. Text, comment and picture frames without flags are decoded,
+/// and every other frame is kept as stored, so tags are written back without loss.
+///
+/// ## Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use wav::{metadata::Id3, Chunk};
+///
+/// let mut tag = Id3::default();
+/// tag.set_text(*b"TIT2", "Intro");
+/// tag.set_text(*b"TRCK", "1/12");
+/// let chunk = Chunk::try_from(&tag).unwrap();
+///
+/// let tag = Id3::try_from(&chunk).unwrap();
+/// assert_eq!(tag.title(), Some("Intro"));
+/// assert_eq!(tag.track(), Some(1));
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Id3 {
+    /// Major version of the tag, 3 or 4.
+    pub version: u8,
+    /// The frames of the tag, in order.
+    pub frames: Vec<Id3Frame>,
+}
+
+impl Default for Id3 {
+    /// An empty ID3v2.3 tag, the version most widely supported by players.
+    fn default() -> Self {
+        Id3 {
+            version: 3,
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl Id3 {
+    /// The chunk ID of the tag, also found in upper case as `"ID3 "`.
+    pub const ID: [u8; 4] = *b"id3 ";
+
+    /// Returns the text of the first text frame with the ID `id`.
+    #[must_use]
+    pub fn text(&self, id: [u8; 4]) -> Option<&str> {
+        self.frames.iter().find_map(|frame| match frame {
+            Id3Frame::Text { id: i, text } if *i == id => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Sets the text of the text frame with the ID `id`, replacing any existing ones.
+    pub fn set_text(&mut self, id: [u8; 4], text: impl Into<String>) {
+        let frame = Id3Frame::Text {
+            id,
+            text: text.into(),
+        };
+        let existing = self
+            .frames
+            .iter()
+            .position(|f| matches!(f, Id3Frame::Text { id: i, .. } if *i == id));
+        match existing {
+            Some(index) => {
+                self.frames[index] = frame;
+                let mut seen = false;
+                self.frames.retain(|f| match f {
+                    Id3Frame::Text { id: other, .. } if *other == id => {
+                        !std::mem::replace(&mut seen, true)
+                    }
+                    _ => true,
+                });
+            }
+            None => self.frames.push(frame),
+        }
+    }
+
+    /// Returns the title, from the `"TIT2"` frame.
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.text(*b"TIT2")
+    }
+
+    /// Returns the lead artist, from the `"TPE1"` frame.
+    #[must_use]
+    pub fn artist(&self) -> Option<&str> {
+        self.text(*b"TPE1")
+    }
+
+    /// Returns the album, from the `"TALB"` frame.
+    #[must_use]
+    pub fn album(&self) -> Option<&str> {
+        self.text(*b"TALB")
+    }
+
+    /// Returns the track number, from the `"TRCK"` frame, which may also hold the number of tracks
+    /// as in `"3/12"`.
+    #[must_use]
+    pub fn track(&self) -> Option<u32> {
+        let track = self.text(*b"TRCK")?;
+        track.split('/').next()?.trim().parse().ok()
+    }
+
+    /// Returns the year of recording, from the `"TDRC"` frame of ID3v2.4 or the `"TYER"` frame of
+    /// ID3v2.3.
+    #[must_use]
+    pub fn year(&self) -> Option<i32> {
+        let date = self.text(*b"TDRC").or_else(|| self.text(*b"TYER"))?;
+        date.get(..4)?.parse().ok()
+    }
+
+    /// Returns the comments.
+    pub fn comments(&self) -> impl Iterator<Item = &Comment> {
+        self.frames.iter().filter_map(|frame| match frame {
+            Id3Frame::Comment(comment) => Some(comment),
+            _ => None,
+        })
+    }
+
+    /// Returns the attached pictures.
+    pub fn pictures(&self) -> impl Iterator<Item = &Picture> {
+        self.frames.iter().filter_map(|frame| match frame {
+            Id3Frame::Picture(picture) => Some(picture),
+            _ => None,
+        })
+    }
+}
+
+impl TryFrom<&[u8]> for Id3 {
+    type Error = &'static str;
+
+    /// Parses an `ID3v2` tag, ignoring any bytes following it.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` doesn't start with an ID3v2.3 or ID3v2.4 tag, if it's shorter
+    /// than the tag, or if a frame extends past the end of the tag or is malformed.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        if v.len() < HEADER_LEN || &v[..3] != b"ID3" {
+            return Err("Not an ID3v2 tag");
+        }
+        let version = v[3];
+        if version != 3 && version != 4 {
+            return Err("Unsupported ID3v2 version");
+        }
+        let flags = v[5];
+        let size = synchsafe(&v[6..10]);
+        let tag = v
+            .get(HEADER_LEN..HEADER_LEN + size)
+            .ok_or("ID3v2 tag extends past the end of the chunk")?;
+
+        // Version 2.3 applies unsynchronisation to the whole tag, version 2.4 to single frames
+        let tag = if version == 3 && flags & 0x80 != 0 {
+            resynchronise(tag)
+        } else {
+            tag.to_vec()
+        };
+
+        let mut pos = 0;
+        if flags & 0x40 != 0 {
+            let extended = tag.get(..4).ok_or("ID3v2 extended header too short")?;
+            pos = match version {
+                3 => {
+                    4 + u32::from_be_bytes([extended[0], extended[1], extended[2], extended[3]])
+                        as usize
+                }
+                _ => synchsafe(extended),
+            };
+        }
+
+        let mut frames = Vec::new();
+        while pos + HEADER_LEN <= tag.len() {
+            let header = &tag[pos..pos + HEADER_LEN];
+            let id = [header[0], header[1], header[2], header[3]];
+            if !id
+                .iter()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            {
+                // Padding, or garbage that can't be a frame
+                break;
+            }
+            let size = match version {
+                3 => u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize,
+                _ => synchsafe(&header[4..8]),
+            };
+            let flags = [header[8], header[9]];
+            pos += HEADER_LEN;
+            let data = tag
+                .get(pos..pos.saturating_add(size))
+                .ok_or("ID3v2 frame extends past the end of the tag")?;
+            pos += size;
+
+            // Only `Id3Frame::Other` keeps the flags, which can also mark transformed contents
+            frames.push(if flags == [0; 2] {
+                parse_frame(id, data)?
+            } else {
+                Id3Frame::Other {
+                    id,
+                    flags,
+                    data: data.to_vec(),
+                }
+            });
+        }
+
+        Ok(Id3 { version, frames })
+    }
+}
+
+impl TryFrom<&Chunk> for Id3 {
+    type Error = &'static str;
+
+    /// Parses an `"id3 "` or `"ID3 "` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't an `"id3 "` or `"ID3 "` chunk, or if its contents
+    /// are invalid.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        if chunk.id != Id3::ID && chunk.id != *b"ID3 " {
+            return Err("Chunk has the wrong ID");
+        }
+        Id3::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Id3> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `tag` as an `"id3 "` chunk. Text is written as ISO-8859-1 where possible and as
+    /// UTF-16 otherwise in ID3v2.3, and as UTF-8 in ID3v2.4.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the version isn't 3 or 4, if a MIME type isn't ASCII, or if a
+    /// frame or the whole tag is larger than `ID3v2` allows.
+    fn try_from(tag: &Id3) -> Result<Self, Self::Error> {
+        if tag.version != 3 && tag.version != 4 {
+            return Err("Unsupported ID3v2 version");
+        }
+
+        let mut body = Vec::new();
+        for frame in &tag.frames {
+            let (id, flags, data) = encode_frame(frame, tag.version)?;
+            let size = match tag.version {
+                3 => u32::try_from(data.len())
+                    .map_err(|_| "ID3v2 frame too large")?
+                    .to_be_bytes(),
+                _ => to_synchsafe(data.len()).ok_or("ID3v2 frame too large")?,
+            };
+            body.extend_from_slice(&id);
+            body.extend_from_slice(&size);
+            body.extend_from_slice(&flags);
+            body.extend_from_slice(&data);
+        }
+
+        let mut v = Vec::with_capacity(HEADER_LEN + body.len());
+        v.extend_from_slice(b"ID3");
+        v.extend_from_slice(&[tag.version, 0, 0]);
+        v.extend_from_slice(&to_synchsafe(body.len()).ok_or("ID3v2 tag too large")?);
+        v.extend_from_slice(&body);
+
+        Ok(Chunk::new(Id3::ID, v))
+    }
+}
+
+/// Reads a 28-bit integer stored in the low 7 bits of 4 bytes.
+fn synchsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take(4)
+        .fold(0, |n, &b| (n << 7) | usize::from(b & 0x7F))
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_synchsafe(n: usize) -> Option<[u8; 4]> {
+    if n > MAX_SYNCHSAFE {
+        return None;
+    }
+    // Each value is masked to 7 bits, so the casts are exact
+    Some([
+        (n >> 21 & 0x7F) as u8,
+        (n >> 14 & 0x7F) as u8,
+        (n >> 7 & 0x7F) as u8,
+        (n & 0x7F) as u8,
+    ])
+}
+
+/// Removes the zero bytes inserted after every `0xFF` byte by unsynchronisation.
+fn resynchronise(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut after_ff = false;
+    for &b in bytes {
+        if !(after_ff && b == 0) {
+            out.push(b);
+        }
+        after_ff = b == 0xFF;
+    }
+    out
+}
+
+fn parse_frame(id: [u8; 4], data: &[u8]) -> Result<Id3Frame, &'static str> {
+    let too_short = "ID3v2 frame too short";
+    // Empty frames have no encoding, so only unknown frames are allowed to be empty
+    let Some((&encoding, rest)) = data.split_first() else {
+        return Ok(Id3Frame::Other {
+            id,
+            flags: [0; 2],
+            data: Vec::new(),
+        });
+    };
+
+    Ok(match &id {
+        b"TXXX" => Id3Frame::Other {
+            id,
+            flags: [0; 2],
+            data: data.to_vec(),
+        },
+        [b'T', ..] => Id3Frame::Text {
+            id,
+            text: decode_values(encoding, rest)?,
+        },
+        b"COMM" => {
+            let language = rest.get(..3).ok_or(too_short)?;
+            let (description, text) = split_terminated(encoding, &rest[3..]);
+            Id3Frame::Comment(Comment {
+                language: [language[0], language[1], language[2]],
+                description: decode(encoding, description)?,
+                text: decode_values(encoding, text)?,
+            })
+        }
+        b"APIC" => {
+            let (mime_type, rest) = split_terminated(LATIN_1, rest);
+            let (&picture_type, rest) = rest.split_first().ok_or(too_short)?;
+            let (description, data) = split_terminated(encoding, rest);
+            Id3Frame::Picture(Picture {
+                mime_type: decode(LATIN_1, mime_type)?,
+                picture_type,
+                description: decode(encoding, description)?,
+                data: data.to_vec(),
+            })
+        }
+        _ => Id3Frame::Other {
+            id,
+            flags: [0; 2],
+            data: data.to_vec(),
+        },
+    })
+}
+
+/// ID, flags and contents of a frame.
+type RawFrame = ([u8; 4], [u8; 2], Vec<u8>);
+
+/// Returns the ID, flags and contents of `frame` for a tag of the given version.
+fn encode_frame(frame: &Id3Frame, version: u8) -> Result<RawFrame, &'static str> {
+    let mut data = Vec::new();
+    let id = match frame {
+        Id3Frame::Text { id, text } => {
+            let encoding = encoding_for(version, &[text]);
+            data.push(encoding);
+            for (i, value) in text.split('\0').enumerate() {
+                if i > 0 {
+                    data.extend_from_slice(terminator(encoding));
+                }
+                data.extend_from_slice(&encode(encoding, value));
+            }
+            *id
+        }
+        Id3Frame::Comment(comment) => {
+            let encoding = encoding_for(version, &[&comment.description, &comment.text]);
+            data.push(encoding);
+            data.extend_from_slice(&comment.language);
+            data.extend_from_slice(&encode(encoding, &comment.description));
+            data.extend_from_slice(terminator(encoding));
+            data.extend_from_slice(&encode(encoding, &comment.text));
+            *b"COMM"
+        }
+        Id3Frame::Picture(picture) => {
+            if !picture.mime_type.is_ascii() {
+                return Err("MIME type must be ASCII");
+            }
+            let encoding = encoding_for(version, &[&picture.description]);
+            data.push(encoding);
+            data.extend_from_slice(picture.mime_type.as_bytes());
+            data.push(0);
+            data.push(picture.picture_type);
+            data.extend_from_slice(&encode(encoding, &picture.description));
+            data.extend_from_slice(terminator(encoding));
+            data.extend_from_slice(&picture.data);
+            *b"APIC"
+        }
+        Id3Frame::Other { id, flags, data } => return Ok((*id, *flags, data.clone())),
+    };
+
+    Ok((id, [0; 2], data))
+}
+
+/// Returns the encoding used to write the given strings in a tag of the given version.
+fn encoding_for(version: u8, texts: &[&String]) -> u8 {
+    if version == 4 {
+        UTF_8
+    } else if texts.iter().all(|text| text.chars().all(|c| c <= '\u{FF}')) {
+        LATIN_1
+    } else {
+        UTF_16
+    }
+}
+
+fn terminator(encoding: u8) -> &'static [u8] {
+    match encoding {
+        UTF_16 | UTF_16_BE => &[0, 0],
+        _ => &[0],
+    }
+}
+
+/// Splits `bytes` after the first string terminated in the given encoding, returning the string
+/// without its terminator and the bytes following it.
+fn split_terminated(encoding: u8, bytes: &[u8]) -> (&[u8], &[u8]) {
+    let end = match encoding {
+        UTF_16 | UTF_16_BE => bytes
+            .chunks_exact(2)
+            .position(|unit| unit == [0, 0])
+            .map(|i| i * 2),
+        _ => bytes.iter().position(|&b| b == 0),
+    };
+    match end {
+        Some(end) => (&bytes[..end], &bytes[end + terminator(encoding).len()..]),
+        None => (bytes, &[]),
+    }
+}
+
+/// Decodes terminated strings until the end of `bytes`, separating them with NUL characters.
+fn decode_values(encoding: u8, mut bytes: &[u8]) -> Result<String, &'static str> {
+    let mut values = Vec::new();
+    while !bytes.is_empty() {
+        let (value, rest) = split_terminated(encoding, bytes);
+        values.push(decode(encoding, value)?);
+        bytes = rest;
+    }
+    Ok(values.join("\0"))
+}
+
+fn decode(encoding: u8, bytes: &[u8]) -> Result<String, &'static str> {
+    let utf_16 = |bytes: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|unit| from_bytes([unit[0], unit[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+
+    Ok(match encoding {
+        LATIN_1 => bytes.iter().map(|&b| char::from(b)).collect(),
+        UTF_16 => match bytes {
+            [0xFF, 0xFE, rest @ ..] => utf_16(rest, u16::from_le_bytes),
+            [0xFE, 0xFF, rest @ ..] => utf_16(rest, u16::from_be_bytes),
+            _ => utf_16(bytes, u16::from_be_bytes),
+        },
+        UTF_16_BE => utf_16(bytes, u16::from_be_bytes),
+        UTF_8 => String::from_utf8_lossy(bytes).into_owned(),
+        _ => return Err("Unknown ID3v2 text encoding"),
+    })
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn encode(encoding: u8, text: &str) -> Vec<u8> {
+    match encoding {
+        // Only used when every character fits in a byte
+        LATIN_1 => text.chars().map(|c| u32::from(c) as u8).collect(),
+        UTF_16 => {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        }
+        _ => text.as_bytes().to_vec(),
+    }
+}
@@ -0,0 +1,400 @@
+use std::{convert::TryFrom, fmt};
+
+use super::{
+    check_id,
+    xml::{self, Element, Node},
+};
+use crate::Chunk;
+
+/// Name of the root element of iXML documents.
+const ROOT: &str = "BWFXML";
+
+/// Exact rate or speed, such as `30000/1001` for the 29.97 fps of NTSC timecode.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rate {
+    /// Numerator of the rate.
+    pub numerator: u32,
+    /// Denominator of the rate, never zero.
+    pub denominator: u32,
+}
+
+impl Rate {
+    /// Creates the rate `numerator / denominator`.
+    #[must_use]
+    pub fn new(numerator: u32, denominator: u32) -> Self {
+        Rate {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Returns the rate as a number.
+    #[must_use]
+    pub fn as_f64(self) -> f64 {
+        f64::from(self.numerator) / f64::from(self.denominator)
+    }
+
+    /// Parses a rate written as `"numerator/denominator"`, or as a whole number.
+    fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(2, '/');
+        let numerator = parts.next()?.trim().parse().ok()?;
+        let denominator = match parts.next() {
+            Some(denominator) => denominator.trim().parse().ok()?,
+            None => 1,
+        };
+        (denominator != 0).then_some(Rate::new(numerator, denominator))
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Speed and timecode of a recording, the `SPEED` element of an [`Ixml`] document.
+///
+/// Fields missing from the document are `None`.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Speed {
+    /// Frame rate of the picture the audio belongs to, `MASTER_SPEED`.
+    pub master_speed: Option<Rate>,
+    /// Frame rate the audio was recorded at, `CURRENT_SPEED`, which differs from the master speed
+    /// for pulled up or down recordings.
+    pub current_speed: Option<Rate>,
+    /// Frame rate of the timecode, `TIMECODE_RATE`.
+    pub timecode_rate: Option<Rate>,
+    /// Whether the timecode is drop-frame, `TIMECODE_FLAG`.
+    pub drop_frame: Option<bool>,
+    /// Sampling rate of the file, `FILE_SAMPLE_RATE`.
+    pub file_sample_rate: Option<u32>,
+    /// Bits per sample of the audio, `AUDIO_BIT_DEPTH`.
+    pub audio_bit_depth: Option<u16>,
+    /// Sampling rate of the recorder's converters, `DIGITIZER_SAMPLE_RATE`.
+    pub digitizer_sample_rate: Option<u32>,
+    /// Position of the first sample, in samples since midnight, from
+    /// `TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_HI` and `TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_LO`.
+    pub timestamp: Option<u64>,
+    /// Sampling rate the timestamp is counted in, `TIMESTAMP_SAMPLE_RATE`.
+    pub timestamp_sample_rate: Option<u32>,
+}
+
+/// Track of a multitrack recording, a `TRACK` element of an [`Ixml`] document.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Track {
+    /// Index of the track on the recorder, from 1.
+    pub channel_index: u16,
+    /// Index of the channel of the file holding the track, from 1.
+    pub interleave_index: u16,
+    /// Name of the track, such as the name of the actor wearing its microphone.
+    pub name: String,
+    /// Role of the track, such as `"LEFT"` or `"M-MID_SIDE"`, often empty.
+    pub function: String,
+}
+
+/// Production sound metadata, the `"iXML"` chunk written by location recorders with the scene,
+/// take, timecode and track names of a recording.
+///
+/// The whole document is kept, so elements without an accessor can be read and written with
+/// [`Ixml::text`] and [`Ixml::set_text`], and are written back as they were.
+///
+/// ## Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use wav::{
+///     metadata::{Ixml, Track},
+///     Chunk,
+/// };
+///
+/// let mut ixml = Ixml::default();
+/// ixml.set_text("SCENE", "12A");
+/// ixml.set_text("TAKE", "3");
+/// ixml.set_tracks(&[Track {
+///     channel_index: 1,
+///     interleave_index: 1,
+///     name: "Boom".to_string(),
+///     ..Track::default()
+/// }]);
+/// let chunk = Chunk::from(&ixml);
+///
+/// let ixml = Ixml::try_from(&chunk).unwrap();
+/// assert_eq!(ixml.scene(), Some("12A"));
+/// assert_eq!(ixml.tracks()[0].name, "Boom");
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ixml {
+    root: Element,
+}
+
+impl Default for Ixml {
+    /// A document holding only the iXML version.
+    fn default() -> Self {
+        let mut root = Element::new(ROOT);
+        root.children
+            .push(Node::Element(Element::with_text("IXML_VERSION", "2.10")));
+        Ixml { root }
+    }
+}
+
+impl Ixml {
+    /// The chunk ID of the iXML document.
+    pub const ID: [u8; 4] = *b"iXML";
+
+    /// Parses an iXML document.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `text` isn't well-formed XML or its root element isn't `BWFXML`.
+    pub fn from_xml(text: &str) -> Result<Self, &'static str> {
+        let root = xml::parse(text)?;
+        if root.name != ROOT {
+            return Err("iXML root element must be BWFXML");
+        }
+        Ok(Ixml { root })
+    }
+
+    /// Returns the document as XML, with an XML declaration and indented with tabs.
+    #[must_use]
+    pub fn to_xml(&self) -> String {
+        self.root.to_document()
+    }
+
+    /// Returns the text of the element at `path`, made of the names of nested elements separated
+    /// by `/`, such as `"SPEED/TIMECODE_RATE"`. Only the first element with each name is looked
+    /// at.
+    #[must_use]
+    pub fn text(&self, path: &str) -> Option<&str> {
+        self.root.find(path).map(Element::text)
+    }
+
+    /// Sets the text of the element at `path`, as described in [`Ixml::text`], replacing its
+    /// children. Missing elements along `path` are appended.
+    pub fn set_text(&mut self, path: &str, text: &str) {
+        self.root.find_or_insert(path).set_text(text);
+    }
+
+    /// Removes the elements at `path`, as described in [`Ixml::text`], with all their children.
+    pub fn remove(&mut self, path: &str) {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (self.root.find_mut(parent), name),
+            None => (Some(&mut self.root), path),
+        };
+        if let Some(parent) = parent {
+            parent.remove(name);
+        }
+    }
+
+    /// Returns the name of the project, `PROJECT`.
+    #[must_use]
+    pub fn project(&self) -> Option<&str> {
+        self.text("PROJECT")
+    }
+
+    /// Returns the scene, `SCENE`.
+    #[must_use]
+    pub fn scene(&self) -> Option<&str> {
+        self.text("SCENE")
+    }
+
+    /// Returns the take, `TAKE`.
+    #[must_use]
+    pub fn take(&self) -> Option<&str> {
+        self.text("TAKE")
+    }
+
+    /// Returns the name of the tape or roll, `TAPE`.
+    #[must_use]
+    pub fn tape(&self) -> Option<&str> {
+        self.text("TAPE")
+    }
+
+    /// Returns the note of the sound recordist, `NOTE`.
+    #[must_use]
+    pub fn note(&self) -> Option<&str> {
+        self.text("NOTE")
+    }
+
+    /// Returns whether the take is circled as a good one, `CIRCLED`.
+    #[must_use]
+    pub fn circled(&self) -> Option<bool> {
+        parse_bool(self.text("CIRCLED")?)
+    }
+
+    /// Returns the speed and timecode of the recording. Values that can't be parsed are `None`.
+    #[must_use]
+    pub fn speed(&self) -> Speed {
+        let speed = self.root.child("SPEED");
+        let text = |name: &str| Some(speed?.child(name)?.text().trim());
+        let number = |name: &str| text(name).and_then(|t| t.parse().ok());
+        let rate = |name: &str| text(name).and_then(Rate::parse);
+
+        let timestamp = match (
+            number("TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_HI"),
+            number("TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_LO"),
+        ) {
+            (Some(hi), Some(lo)) => Some(u64::from(hi) << 32 | u64::from(lo)),
+            _ => None,
+        };
+
+        Speed {
+            master_speed: rate("MASTER_SPEED"),
+            current_speed: rate("CURRENT_SPEED"),
+            timecode_rate: rate("TIMECODE_RATE"),
+            drop_frame: text("TIMECODE_FLAG").and_then(|flag| match flag {
+                "DF" => Some(true),
+                "NDF" => Some(false),
+                _ => None,
+            }),
+            file_sample_rate: number("FILE_SAMPLE_RATE"),
+            audio_bit_depth: text("AUDIO_BIT_DEPTH").and_then(|t| t.parse().ok()),
+            digitizer_sample_rate: number("DIGITIZER_SAMPLE_RATE"),
+            timestamp,
+            timestamp_sample_rate: number("TIMESTAMP_SAMPLE_RATE"),
+        }
+    }
+
+    /// Sets the speed and timecode of the recording. Fields that are `None` are removed from the
+    /// `SPEED` element, and its other children are kept.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn set_speed(&mut self, speed: &Speed) {
+        let speed_element = self.root.child_or_insert("SPEED");
+        let mut set = |name: &str, value: Option<String>| match value {
+            Some(value) => speed_element.child_or_insert(name).set_text(&value),
+            None => speed_element.remove(name),
+        };
+
+        set("MASTER_SPEED", speed.master_speed.map(|r| r.to_string()));
+        set("CURRENT_SPEED", speed.current_speed.map(|r| r.to_string()));
+        set("TIMECODE_RATE", speed.timecode_rate.map(|r| r.to_string()));
+        set(
+            "TIMECODE_FLAG",
+            speed
+                .drop_frame
+                .map(|df| if df { "DF" } else { "NDF" }.to_string()),
+        );
+        set(
+            "FILE_SAMPLE_RATE",
+            speed.file_sample_rate.map(|r| r.to_string()),
+        );
+        set(
+            "AUDIO_BIT_DEPTH",
+            speed.audio_bit_depth.map(|b| b.to_string()),
+        );
+        set(
+            "DIGITIZER_SAMPLE_RATE",
+            speed.digitizer_sample_rate.map(|r| r.to_string()),
+        );
+        // The halves are masked to 32 bits, so the casts are exact
+        set(
+            "TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_HI",
+            speed.timestamp.map(|t| ((t >> 32) as u32).to_string()),
+        );
+        set(
+            "TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_LO",
+            speed.timestamp.map(|t| (t as u32).to_string()),
+        );
+        set(
+            "TIMESTAMP_SAMPLE_RATE",
+            speed.timestamp_sample_rate.map(|r| r.to_string()),
+        );
+    }
+
+    /// Returns the tracks of the recording, from `TRACK_LIST`. Tracks missing an index get their
+    /// position in the list, from 1.
+    #[must_use]
+    pub fn tracks(&self) -> Vec<Track> {
+        let Some(list) = self.root.child("TRACK_LIST") else {
+            return Vec::new();
+        };
+
+        list.children("TRACK")
+            .zip(1u16..)
+            .map(|(track, position)| {
+                let text = |name: &str| track.child(name).map_or("", Element::text);
+                let index = |name: &str| text(name).trim().parse().unwrap_or(position);
+                Track {
+                    channel_index: index("CHANNEL_INDEX"),
+                    interleave_index: index("INTERLEAVE_INDEX"),
+                    name: text("NAME").to_string(),
+                    function: text("FUNCTION").to_string(),
+                }
+            })
+            .collect()
+    }
+
+    /// Replaces the tracks of the recording, and their count, in `TRACK_LIST`.
+    pub fn set_tracks(&mut self, tracks: &[Track]) {
+        let list = self.root.child_or_insert("TRACK_LIST");
+        list.children.clear();
+        list.children.push(Node::Element(Element::with_text(
+            "TRACK_COUNT",
+            &tracks.len().to_string(),
+        )));
+
+        for track in tracks {
+            let mut element = Element::new("TRACK");
+            for (name, text) in [
+                ("CHANNEL_INDEX", track.channel_index.to_string()),
+                ("INTERLEAVE_INDEX", track.interleave_index.to_string()),
+                ("NAME", track.name.clone()),
+                ("FUNCTION", track.function.clone()),
+            ] {
+                element
+                    .children
+                    .push(Node::Element(Element::with_text(name, &text)));
+            }
+            list.children.push(Node::Element(element));
+        }
+    }
+}
+
+/// Parses the `TRUE` and `FALSE` of iXML flags.
+fn parse_bool(text: &str) -> Option<bool> {
+    match text.trim() {
+        t if t.eq_ignore_ascii_case("TRUE") => Some(true),
+        t if t.eq_ignore_ascii_case("FALSE") => Some(false),
+        _ => None,
+    }
+}
+
+impl TryFrom<&[u8]> for Ixml {
+    type Error = &'static str;
+
+    /// Parses the contents of an `"iXML"` chunk, ignoring trailing NUL bytes.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` isn't UTF-8, or if it isn't an iXML document as described in
+    /// [`Ixml::from_xml`].
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        let end = v.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        let text = std::str::from_utf8(&v[..end]).map_err(|_| "iXML chunk isn't UTF-8")?;
+        Ixml::from_xml(text.trim_start_matches('\u{FEFF}'))
+    }
+}
+
+impl TryFrom<&Chunk> for Ixml {
+    type Error = &'static str;
+
+    /// Parses an `"iXML"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't an `"iXML"` chunk, or if its contents are invalid.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Ixml::ID)?;
+        Ixml::try_from(chunk.data.as_slice())
+    }
+}
+
+impl From<&Ixml> for Chunk {
+    /// Writes `ixml` as an `"iXML"` chunk.
+    fn from(ixml: &Ixml) -> Self {
+        Chunk::new(Ixml::ID, ixml.to_xml().into_bytes())
+    }
+}
@@ -0,0 +1,327 @@
+use std::convert::TryFrom;
+
+use super::{check_id, read_text, write_text};
+use crate::{
+    waveform::{Level, WaveformGenerator, WaveformOptions},
+    BitDepth, Chunk, Header, Sample, SampleFormat,
+};
+
+/// Size of the header of the chunk, including the chunk ID and size, which the offset to the peaks
+/// is counted from.
+const HEADER_LEN: u32 = 128;
+
+/// Size of the fields of the header, which is the header without the chunk ID and size.
+const FIELDS_LEN: usize = 120;
+
+/// Type of the values of a [`Levl`] peak envelope.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PeakFormat {
+    /// Unsigned 8-bit values, where 128 is full scale.
+    Eight,
+    /// Unsigned 16-bit values, where 32768 is full scale.
+    Sixteen,
+}
+
+impl PeakFormat {
+    fn full_scale(self) -> f64 {
+        match self {
+            PeakFormat::Eight => 128.0,
+            PeakFormat::Sixteen => 32_768.0,
+        }
+    }
+
+    fn max(self) -> u16 {
+        match self {
+            PeakFormat::Eight => u16::from(u8::MAX),
+            PeakFormat::Sixteen => u16::MAX,
+        }
+    }
+
+    /// Returns the value of the absolute sample level `level`, between 0 and 1.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn value(self, level: f32) -> u16 {
+        // Clamped to the range of the format, the cast is exact
+        (f64::from(level.abs()) * self.full_scale())
+            .round()
+            .min(f64::from(self.max())) as u16
+    }
+}
+
+/// Highest levels of the samples of one channel over a block of frames, in either direction.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Peak {
+    /// Level of the highest positive sample.
+    pub positive: u16,
+    /// Level of the lowest negative sample, as an absolute value.
+    pub negative: u16,
+}
+
+/// Peak envelope, the `"levl"` chunk specified by EBU Tech 3285 Supplement 3, letting editors draw
+/// the waveform of a file without reading its audio.
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::{convert::TryFrom, fs::File, io::Cursor};
+/// use wav::{metadata::Levl, Chunk};
+///
+/// let (header, data) = wav::read(&mut File::open("data/sine.wav")?)?;
+/// let levl = Levl::generate(&header, &data, 256).map_err(std::io::Error::other)?;
+/// let chunk = Chunk::try_from(&levl).map_err(std::io::Error::other)?;
+///
+/// let mut out = Cursor::new(Vec::new());
+/// wav::write_with_chunks(header, &data, &[chunk], &mut out)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Levl {
+    /// Version of the chunk's format.
+    pub version: u32,
+    /// Type of the peak values.
+    pub format: PeakFormat,
+    /// Number of values per peak: 2 if both directions are stored, or 1 if only the positive
+    /// one is, in which case [`Peak::negative`] is always 0.
+    pub points_per_value: u32,
+    /// Number of frames summarized by each peak.
+    pub block_frames: u32,
+    /// Number of channels summarized.
+    pub channel_count: u32,
+    /// Frame holding the highest absolute sample of the file, if known.
+    pub peak_of_peaks: Option<u32>,
+    /// Time of creation of the envelope, formatted as `yyyy:mm:dd:hh:mm:ss:uuu`, or empty.
+    pub timestamp: String,
+    /// Peaks of every channel for each block in turn, interleaved like samples.
+    pub peaks: Vec<Peak>,
+}
+
+impl Levl {
+    /// The chunk ID of the peak envelope.
+    pub const ID: [u8; 4] = *b"levl";
+
+    /// Creates a peak envelope from a level of a [`crate::waveform::Waveform`] of the audio
+    /// described by `header`. Audio of 8 bits per sample gets 8-bit peaks, and any other audio
+    /// 16-bit peaks. The timestamp is left empty, and the peak of peaks unknown.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the blocks of `level` span more than `u32::MAX` frames.
+    pub fn from_level(header: &Header, level: &Level) -> Result<Self, &'static str> {
+        let block_frames =
+            u32::try_from(level.block_frames).map_err(|_| "Peak envelope blocks too long")?;
+        let format = if header.sample_format() == Some(SampleFormat::Eight) {
+            PeakFormat::Eight
+        } else {
+            PeakFormat::Sixteen
+        };
+
+        Ok(Levl {
+            version: 1,
+            format,
+            points_per_value: 2,
+            block_frames,
+            channel_count: u32::from(header.channel_count),
+            peak_of_peaks: None,
+            timestamp: String::new(),
+            peaks: level
+                .summaries
+                .iter()
+                .map(|summary| Peak {
+                    positive: format.value(summary.max.max(0.0)),
+                    negative: format.value(summary.min.min(0.0)),
+                })
+                .collect(),
+        })
+    }
+
+    /// Creates the peak envelope of the interleaved frames in `data`, described by `header`, with
+    /// a peak every `block_frames` frames. The peak of peaks is located, but the timestamp is left
+    /// empty.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `block_frames` or the channel count of `header` is zero.
+    pub fn generate(
+        header: &Header,
+        data: &BitDepth,
+        block_frames: u32,
+    ) -> Result<Self, &'static str> {
+        let options = WaveformOptions {
+            block_frames: u64::from(block_frames),
+            ..WaveformOptions::default()
+        };
+        // Converted once for both the envelope and the peak of peaks
+        let samples = f32::from_bit_depth(data);
+        let mut generator = WaveformGenerator::new(header, options)?;
+        generator.process_samples(&samples);
+        let mut levl = Levl::from_level(header, &generator.finish().levels[0])?;
+
+        let loudest = samples
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()));
+        levl.peak_of_peaks =
+            loudest.and_then(|(i, _)| u32::try_from(i / usize::from(header.channel_count)).ok());
+
+        Ok(levl)
+    }
+}
+
+impl TryFrom<&[u8]> for Levl {
+    type Error = &'static str;
+
+    /// Parses the contents of a `"levl"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the fields of the chunk or than the peaks they
+    /// describe, or if the format, the number of points per value or the channel count is invalid.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        if v.len() < FIELDS_LEN {
+            return Err("Peak envelope chunk too short");
+        }
+        let u32_at = |i: usize| u32::from_le_bytes([v[i], v[i + 1], v[i + 2], v[i + 3]]);
+
+        let format = match u32_at(4) {
+            1 => PeakFormat::Eight,
+            2 => PeakFormat::Sixteen,
+            _ => return Err("Unknown peak envelope format"),
+        };
+        let points_per_value = u32_at(8);
+        if points_per_value != 1 && points_per_value != 2 {
+            return Err("Peak envelope must have 1 or 2 points per value");
+        }
+        let channel_count = u32_at(16);
+        if channel_count == 0 {
+            return Err("Peak envelope must have at least one channel");
+        }
+
+        let offset = u32_at(28);
+        if offset < HEADER_LEN {
+            return Err("Peak envelope data overlaps its header");
+        }
+        // The offset counts the chunk ID and size, which aren't part of `v`
+        let start = usize::try_from(offset - 8).map_err(|_| "Peak envelope chunk too short")?;
+
+        let width = match format {
+            PeakFormat::Eight => 1,
+            PeakFormat::Sixteen => 2,
+        };
+        let value_len = width * if points_per_value == 2 { 2 } else { 1 };
+        let end = usize::try_from(u64::from(u32_at(20)) * u64::from(channel_count))
+            .ok()
+            .and_then(|count| count.checked_mul(value_len))
+            .and_then(|len| start.checked_add(len))
+            .filter(|&end| end <= v.len())
+            .ok_or("Peak envelope chunk too short")?;
+
+        let value = |bytes: &[u8]| match format {
+            PeakFormat::Eight => u16::from(bytes[0]),
+            PeakFormat::Sixteen => u16::from_le_bytes([bytes[0], bytes[1]]),
+        };
+        let peaks = v[start..end]
+            .chunks_exact(value_len)
+            .map(|point| Peak {
+                positive: value(point),
+                negative: if points_per_value == 2 {
+                    value(&point[width..])
+                } else {
+                    0
+                },
+            })
+            .collect();
+
+        Ok(Levl {
+            version: u32_at(0),
+            format,
+            points_per_value,
+            block_frames: u32_at(12),
+            channel_count,
+            peak_of_peaks: Some(u32_at(24)).filter(|&pos| pos != u32::MAX),
+            timestamp: read_text(&v[32..60]),
+            peaks,
+        })
+    }
+}
+
+impl TryFrom<&Chunk> for Levl {
+    type Error = &'static str;
+
+    /// Parses a `"levl"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't a `"levl"` chunk, or if its contents are invalid.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Levl::ID)?;
+        Levl::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Levl> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `levl` as a `"levl"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the number of points per value isn't 1 or 2, if the peaks don't make
+    /// up whole frames of `channel_count` channels or don't fit the format, or if the timestamp is
+    /// longer than 28 bytes.
+    fn try_from(levl: &Levl) -> Result<Self, Self::Error> {
+        if levl.points_per_value != 1 && levl.points_per_value != 2 {
+            return Err("Peak envelope must have 1 or 2 points per value");
+        }
+        let channels = usize::try_from(levl.channel_count).unwrap_or(0);
+        if channels == 0 || levl.peaks.len() % channels != 0 {
+            return Err("Peaks don't make up whole frames");
+        }
+        let frames = u32::try_from(levl.peaks.len() / channels).map_err(|_| "Too many peaks")?;
+        let max = levl.format.max();
+        if levl
+            .peaks
+            .iter()
+            .any(|p| p.positive > max || p.negative > max)
+        {
+            return Err("Peak too high for the peak format");
+        }
+
+        let format: u32 = match levl.format {
+            PeakFormat::Eight => 1,
+            PeakFormat::Sixteen => 2,
+        };
+        let mut v = Vec::new();
+        for field in [
+            levl.version,
+            format,
+            levl.points_per_value,
+            levl.block_frames,
+            levl.channel_count,
+            frames,
+            levl.peak_of_peaks.unwrap_or(u32::MAX),
+            HEADER_LEN,
+        ] {
+            v.extend_from_slice(&field.to_le_bytes());
+        }
+        write_text(&mut v, &levl.timestamp, 28)?;
+        v.resize(FIELDS_LEN, 0);
+
+        for peak in &levl.peaks {
+            let mut push = |value: u16| match levl.format {
+                // Checked against the highest 8-bit value above
+                PeakFormat::Eight => v.push(value.to_le_bytes()[0]),
+                PeakFormat::Sixteen => v.extend_from_slice(&value.to_le_bytes()),
+            };
+            push(peak.positive);
+            if levl.points_per_value == 2 {
+                push(peak.negative);
+            }
+        }
+
+        Ok(Chunk::new(Levl::ID, v))
+    }
+}
@@ -0,0 +1,329 @@
+use std::{convert::TryFrom, ops::Range};
+
+use super::{check_id, Cue};
+use crate::{BitDepth, Chunk, Header};
+
+/// Size of a segment.
+const SEGMENT_LEN: usize = 12;
+
+/// Entry of a [`Plst`] playlist, playing the frames following a cue point.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    /// Identifier of the [`crate::metadata::CuePoint`] the segment starts at.
+    pub cue_id: u32,
+    /// Number of frames in the segment.
+    pub length: u32,
+    /// Number of times the segment is played in a row.
+    pub loops: u32,
+}
+
+/// Playlist, the `"plst"` chunk giving the order in which segments of the audio starting at the
+/// cue points of a [`Cue`] chunk are played.
+///
+/// ## Example
+///
+/// ```
+/// use wav::{
+///     metadata::{Cue, CuePoint, Plst, Segment},
+///     BitDepth, Header, WAV_FORMAT_PCM,
+/// };
+///
+/// let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 16);
+/// let data = BitDepth::Sixteen(vec![1, 2, 3, 4]);
+/// let cue = Cue {
+///     points: vec![CuePoint::new(1, 0), CuePoint::new(2, 2)],
+/// };
+/// let plst = Plst {
+///     segments: vec![
+///         Segment { cue_id: 2, length: 2, loops: 2 },
+///         Segment { cue_id: 1, length: 1, loops: 1 },
+///     ],
+/// };
+/// let rendered = plst.render(&cue, &header, &data).unwrap();
+/// assert_eq!(rendered, BitDepth::Sixteen(vec![3, 4, 3, 4, 1]));
+/// ```
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plst {
+    /// The segments, in the order they're played.
+    pub segments: Vec<Segment>,
+}
+
+impl Plst {
+    /// The chunk ID of the playlist.
+    pub const ID: [u8; 4] = *b"plst";
+
+    /// Returns an iterator over the frames of the interleaved samples in `data`, described by
+    /// `header`, in the order the playlist plays them, borrowing every frame from `data`. The
+    /// segments start at the [`crate::metadata::CuePoint::sample_offset`] of their cue point in
+    /// `cue`.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the channel count of `header` is zero, if a segment refers to a cue
+    /// point missing from `cue` or placed in a wave list chunk other than the first, or if a
+    /// segment extends past the end of `data`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use wav::{
+    ///     metadata::{Cue, CuePoint, Plst, Segment},
+    ///     Header, WAV_FORMAT_PCM,
+    /// };
+    ///
+    /// let header = Header::new(WAV_FORMAT_PCM, 2, 8_000, 16);
+    /// let data: &[i16] = &[1, -1, 2, -2, 3, -3];
+    /// let cue = Cue {
+    ///     points: vec![CuePoint::new(1, 1)],
+    /// };
+    /// let plst = Plst {
+    ///     segments: vec![Segment { cue_id: 1, length: 2, loops: 1 }],
+    /// };
+    /// let frames: Vec<&[i16]> = plst.frames(&cue, &header, data).unwrap().collect();
+    /// assert_eq!(frames, [[2, -2], [3, -3]]);
+    /// ```
+    pub fn frames<'a, T>(
+        &self,
+        cue: &Cue,
+        header: &Header,
+        data: &'a [T],
+    ) -> Result<PlstFrames<'a, T>, &'static str> {
+        let channels = usize::from(header.channel_count);
+        let segments = self.segments(cue, channels, data.len())?;
+
+        Ok(PlstFrames {
+            data,
+            channels,
+            segments,
+            segment: 0,
+            loops: 0,
+            pos: 0,
+        })
+    }
+
+    /// Renders the playlist into the interleaved audio of the frames it plays, as described in
+    /// [`Plst::frames`].
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`Plst::frames`].
+    pub fn render(
+        &self,
+        cue: &Cue,
+        header: &Header,
+        data: &BitDepth,
+    ) -> Result<BitDepth, &'static str> {
+        let channels = usize::from(header.channel_count);
+        let segments = self.segments(cue, channels, sample_count(data))?;
+
+        let mut out = slice(data, 0..0);
+        for (range, loops) in segments.iter().filter(|(range, _)| !range.is_empty()) {
+            for _ in 0..*loops {
+                extend(&mut out, data, range.clone());
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the range of samples and number of loops of each segment, in audio of `samples`
+    /// interleaved samples of `channels` channels.
+    fn segments(
+        &self,
+        cue: &Cue,
+        channels: usize,
+        samples: usize,
+    ) -> Result<Vec<(Range<usize>, u32)>, &'static str> {
+        if channels == 0 {
+            return Err("Channel count must be at least 1");
+        }
+        let frame_count = samples / channels;
+
+        self.segments
+            .iter()
+            .map(|segment| {
+                let point = cue
+                    .point(segment.cue_id)
+                    .ok_or("Playlist refers to a missing cue point")?;
+                // Only the first chunk of a wave list starts with the audio
+                if point.chunk_start != 0 {
+                    return Err("Playlist cue point is within a later wave list chunk");
+                }
+                let start = usize::try_from(point.sample_offset).unwrap_or(usize::MAX);
+                let end = usize::try_from(segment.length)
+                    .ok()
+                    .and_then(|length| start.checked_add(length))
+                    .filter(|&end| end <= frame_count)
+                    .ok_or("Playlist segment extends past the end of the audio")?;
+                Ok((start * channels..end * channels, segment.loops))
+            })
+            .collect()
+    }
+}
+
+/// Iterator over the frames played by a [`Plst`], returned by [`Plst::frames`]. Every frame is a
+/// slice of the audio holding one sample per channel.
+#[derive(Debug, Clone)]
+pub struct PlstFrames<'a, T> {
+    data: &'a [T],
+    channels: usize,
+    /// Range of samples and number of loops of each segment.
+    segments: Vec<(Range<usize>, u32)>,
+    segment: usize,
+    loops: u32,
+    pos: usize,
+}
+
+impl<'a, T> Iterator for PlstFrames<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (range, loops) = self.segments.get(self.segment)?;
+            if self.loops < *loops && range.start + self.pos < range.end {
+                let start = range.start + self.pos;
+                self.pos += self.channels;
+                return Some(&self.data[start..start + self.channels]);
+            }
+
+            self.pos = 0;
+            self.loops += 1;
+            if self.loops >= *loops || range.is_empty() {
+                self.loops = 0;
+                self.segment += 1;
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self
+            .segments
+            .iter()
+            .skip(self.segment)
+            .enumerate()
+            .try_fold(0usize, |total, (i, (range, loops))| {
+                let mut loops = usize::try_from(*loops).ok()?;
+                let mut played = 0;
+                if i == 0 {
+                    // The current segment is partly played
+                    loops = loops.saturating_sub(usize::try_from(self.loops).ok()?);
+                    played = self.pos / self.channels;
+                }
+                let frames = (range.len() / self.channels).checked_mul(loops)?;
+                total.checked_add(frames.saturating_sub(played))
+            });
+
+        match remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+/// Returns the number of samples in `data`.
+fn sample_count(data: &BitDepth) -> usize {
+    match data {
+        BitDepth::Eight(v) => v.len(),
+        BitDepth::Sixteen(v) => v.len(),
+        BitDepth::TwentyFour(v) => v.len(),
+        BitDepth::ThirtyTwoFloat(v) => v.len(),
+        BitDepth::Empty => 0,
+    }
+}
+
+/// Returns the samples of `data` in `range`.
+fn slice(data: &BitDepth, range: Range<usize>) -> BitDepth {
+    match data {
+        BitDepth::Eight(v) => BitDepth::Eight(v[range].to_vec()),
+        BitDepth::Sixteen(v) => BitDepth::Sixteen(v[range].to_vec()),
+        BitDepth::TwentyFour(v) => BitDepth::TwentyFour(v[range].to_vec()),
+        BitDepth::ThirtyTwoFloat(v) => BitDepth::ThirtyTwoFloat(v[range].to_vec()),
+        BitDepth::Empty => BitDepth::Empty,
+    }
+}
+
+/// Appends the samples of `data` in `range` to `out`, which holds samples of the same format.
+fn extend(out: &mut BitDepth, data: &BitDepth, range: Range<usize>) {
+    match (out, data) {
+        (BitDepth::Eight(out), BitDepth::Eight(v)) => out.extend_from_slice(&v[range]),
+        (BitDepth::Sixteen(out), BitDepth::Sixteen(v)) => out.extend_from_slice(&v[range]),
+        (BitDepth::TwentyFour(out), BitDepth::TwentyFour(v)) => out.extend_from_slice(&v[range]),
+        (BitDepth::ThirtyTwoFloat(out), BitDepth::ThirtyTwoFloat(v)) => {
+            out.extend_from_slice(&v[range]);
+        }
+        _ => {}
+    }
+}
+
+impl TryFrom<&[u8]> for Plst {
+    type Error = &'static str;
+
+    /// Parses the contents of a `"plst"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `v` is shorter than the segments it counts.
+    fn try_from(v: &[u8]) -> Result<Self, Self::Error> {
+        let too_short = "Playlist chunk too short";
+        let count = v.get(..4).ok_or(too_short)?;
+        let count = u32::from_le_bytes([count[0], count[1], count[2], count[3]]);
+        let segments = usize::try_from(count)
+            .ok()
+            .and_then(|count| count.checked_mul(SEGMENT_LEN))
+            .and_then(|len| v.get(4..len.checked_add(4)?))
+            .ok_or(too_short)?;
+
+        let segments = segments
+            .chunks_exact(SEGMENT_LEN)
+            .map(|s| {
+                let u32_at = |i: usize| u32::from_le_bytes([s[i], s[i + 1], s[i + 2], s[i + 3]]);
+                Segment {
+                    cue_id: u32_at(0),
+                    length: u32_at(4),
+                    loops: u32_at(8),
+                }
+            })
+            .collect();
+
+        Ok(Plst { segments })
+    }
+}
+
+impl TryFrom<&Chunk> for Plst {
+    type Error = &'static str;
+
+    /// Parses a `"plst"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `chunk` isn't a `"plst"` chunk, or if it's too short.
+    fn try_from(chunk: &Chunk) -> Result<Self, Self::Error> {
+        check_id(chunk, Plst::ID)?;
+        Plst::try_from(chunk.data.as_slice())
+    }
+}
+
+impl TryFrom<&Plst> for Chunk {
+    type Error = &'static str;
+
+    /// Writes `plst` as a `"plst"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if there are more than `u32::MAX` segments.
+    fn try_from(plst: &Plst) -> Result<Self, Self::Error> {
+        let count = u32::try_from(plst.segments.len()).map_err(|_| "Too many playlist segments")?;
+
+        let mut v = Vec::with_capacity(4 + SEGMENT_LEN * plst.segments.len());
+        v.extend_from_slice(&count.to_le_bytes());
+        for segment in &plst.segments {
+            v.extend_from_slice(&segment.cue_id.to_le_bytes());
+            v.extend_from_slice(&segment.length.to_le_bytes());
+            v.extend_from_slice(&segment.loops.to_le_bytes());
+        }
+
+        Ok(Chunk::new(Plst::ID, v))
+    }
+}
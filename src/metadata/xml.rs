@@ -0,0 +1,425 @@
+//! Minimal XML parser and writer for the XML metadata chunks.
+//!
+//! Only what metadata chunks need is supported: elements, attributes, text, CDATA sections and the
+//! predefined and numeric character references. The prolog, comments, processing instructions and
+//! document type declarations are skipped, as is text made only of whitespace.
+
+/// Node of an XML document.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// XML element, with its attributes and children in document order.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Element {
+    pub(super) name: String,
+    pub(super) attributes: Vec<(String, String)>,
+    pub(super) children: Vec<Node>,
+}
+
+impl Element {
+    /// Creates an element without attributes or children.
+    pub(super) fn new(name: &str) -> Self {
+        Element {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Creates an element holding only `text`.
+    pub(super) fn with_text(name: &str, text: &str) -> Self {
+        let mut element = Element::new(name);
+        element.set_text(text);
+        element
+    }
+
+    /// Returns the value of the attribute `name`, ignoring namespace prefixes.
+    pub(super) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| local_name(n) == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the first element named `name`, ignoring namespace prefixes, among the element and
+    /// its descendants in document order.
+    pub(super) fn descendant(&self, name: &str) -> Option<&Element> {
+        if local_name(&self.name) == name {
+            return Some(self);
+        }
+        self.elements().find_map(|element| element.descendant(name))
+    }
+
+    /// Returns the child elements.
+    pub(super) fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Returns the child elements named `name`.
+    pub(super) fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    /// Returns the first child element named `name`.
+    pub(super) fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Returns the first child element named `name`, appending an empty one if there is none.
+    pub(super) fn child_or_insert(&mut self, name: &str) -> &mut Element {
+        let index = self
+            .children
+            .iter()
+            .position(|node| matches!(node, Node::Element(e) if e.name == name));
+        let index = index.unwrap_or_else(|| {
+            self.children.push(Node::Element(Element::new(name)));
+            self.children.len() - 1
+        });
+        match &mut self.children[index] {
+            Node::Element(element) => element,
+            Node::Text(_) => unreachable!(),
+        }
+    }
+
+    /// Removes the child elements named `name`.
+    pub(super) fn remove(&mut self, name: &str) {
+        self.children
+            .retain(|node| !matches!(node, Node::Element(e) if e.name == name));
+    }
+
+    /// Returns the element found by following the child names in `path`, separated by `/`.
+    pub(super) fn find(&self, path: &str) -> Option<&Element> {
+        path.split('/')
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// Like [`Element::find`], returning a mutable reference.
+    pub(super) fn find_mut(&mut self, path: &str) -> Option<&mut Element> {
+        path.split('/').try_fold(self, |element, name| {
+            element.children.iter_mut().find_map(|node| match node {
+                Node::Element(e) if e.name == name => Some(e),
+                _ => None,
+            })
+        })
+    }
+
+    /// Like [`Element::find`], appending the elements missing along `path`.
+    pub(super) fn find_or_insert(&mut self, path: &str) -> &mut Element {
+        path.split('/')
+            .fold(self, |element, name| element.child_or_insert(name))
+    }
+
+    /// Returns the text of the element, empty if it has none.
+    pub(super) fn text(&self) -> &str {
+        self.children
+            .iter()
+            .find_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                Node::Element(_) => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Replaces the children of the element with `text`.
+    pub(super) fn set_text(&mut self, text: &str) {
+        self.children.clear();
+        if !text.is_empty() {
+            self.children.push(Node::Text(text.to_string()));
+        }
+    }
+
+    /// Returns the element as a document, with an XML declaration and indented with tabs.
+    pub(super) fn to_document(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, depth: usize) {
+        out.extend(std::iter::repeat('\t').take(depth));
+        out.push('<');
+        out.push_str(&self.name);
+        for (name, value) in &self.attributes {
+            out.push(' ');
+            out.push_str(name);
+            out.push_str("=\"");
+            escape(out, value, true);
+            out.push('"');
+        }
+
+        if self.children.is_empty() {
+            out.push_str("/>\n");
+            return;
+        }
+        out.push('>');
+        if let [Node::Text(text)] = self.children.as_slice() {
+            escape(out, text, false);
+        } else {
+            out.push('\n');
+            for child in &self.children {
+                match child {
+                    Node::Element(element) => element.write(out, depth + 1),
+                    Node::Text(text) => {
+                        out.extend(std::iter::repeat('\t').take(depth + 1));
+                        escape(out, text, false);
+                        out.push('\n');
+                    }
+                }
+            }
+            out.extend(std::iter::repeat('\t').take(depth));
+        }
+        out.push_str("</");
+        out.push_str(&self.name);
+        out.push_str(">\n");
+    }
+}
+
+/// Returns `name` without its namespace prefix.
+pub(super) fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Appends `text` to `out`, escaping the characters that can't appear as is in text, or in
+/// attribute values if `attribute` is set.
+fn escape(out: &mut String, text: &str, attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Parses the root element of the XML document `text`.
+///
+/// ## Errors
+///
+/// This function fails if `text` isn't a well-formed document.
+pub(super) fn parse(text: &str) -> Result<Element, &'static str> {
+    let mut parser = Parser {
+        text,
+        pos: 0,
+        depth: 0,
+    };
+    parser.skip_misc()?;
+    if !parser.rest().starts_with('<') {
+        return Err("XML document has no root element");
+    }
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < text.len() {
+        return Err("Unexpected content after the XML root element");
+    }
+
+    Ok(root)
+}
+
+/// The deepest nesting of elements accepted by [`parse`], keeping the recursion of the parser off
+/// the end of the stack.
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    /// The number of elements enclosing the one being parsed.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips past the next occurrence of `end`.
+    fn skip_past(&mut self, end: &str) -> Result<&'a str, &'static str> {
+        let rest = self.rest();
+        let len = rest.find(end).ok_or("Unterminated XML markup")?;
+        self.pos += len + end.len();
+        Ok(&rest[..len])
+    }
+
+    /// Skips whitespace, comments, processing instructions and document type declarations.
+    fn skip_misc(&mut self) -> Result<(), &'static str> {
+        loop {
+            self.skip_whitespace();
+            if !self.skip_markup()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips a comment, processing instruction or document type declaration, if one comes next.
+    fn skip_markup(&mut self) -> Result<bool, &'static str> {
+        let rest = self.rest();
+        if rest.starts_with("<!--") {
+            self.skip_past("-->")?;
+        } else if rest.starts_with("<?") {
+            self.skip_past("?>")?;
+        } else if rest.starts_with("<!DOCTYPE") {
+            // The internal subset may hold `>` characters within brackets
+            let mut depth = 0usize;
+            let len = rest
+                .char_indices()
+                .find(|&(_, c)| match c {
+                    '[' => {
+                        depth += 1;
+                        false
+                    }
+                    ']' => {
+                        depth = depth.saturating_sub(1);
+                        false
+                    }
+                    '>' => depth == 0,
+                    _ => false,
+                })
+                .ok_or("Unterminated XML markup")?
+                .0;
+            self.pos += len + 1;
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn name(&mut self) -> Result<&'a str, &'static str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err("Missing XML name");
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Parses the element starting at the current `<`.
+    fn element(&mut self) -> Result<Element, &'static str> {
+        self.pos += 1;
+        let mut element = Element::new(self.name()?);
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            } else if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+
+            let name = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err("XML attribute has no value");
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let Some(quote @ ('"' | '\'')) = self.rest().chars().next() else {
+                return Err("XML attribute value isn't quoted");
+            };
+            self.pos += 1;
+            let value = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+            element
+                .attributes
+                .push((name.to_string(), unescape(value)?));
+        }
+
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err("XML element isn't closed");
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                if self.name()? != element.name {
+                    return Err("Mismatched XML end tag");
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err("Unterminated XML markup");
+                }
+                self.pos += 1;
+                push_text(&mut element, &mut text);
+                return Ok(element);
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                text.push_str(self.skip_past("]]>")?);
+            } else if self.skip_markup()? {
+                // Comments and processing instructions within the element
+            } else if rest.starts_with('<') {
+                push_text(&mut element, &mut text);
+                if self.depth == MAX_DEPTH {
+                    return Err("XML nested too deeply");
+                }
+                self.depth += 1;
+                let child = self.element()?;
+                self.depth -= 1;
+                element.children.push(Node::Element(child));
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                text.push_str(&unescape(&rest[..len])?);
+                self.pos += len;
+            }
+        }
+    }
+}
+
+/// Moves the text read so far into a child of `element`, unless it's only whitespace.
+fn push_text(element: &mut Element, text: &mut String) {
+    if !text.trim().is_empty() {
+        element.children.push(Node::Text(std::mem::take(text)));
+    }
+    text.clear();
+}
+
+/// Replaces the character references in `text` with the characters they stand for.
+fn unescape(text: &str) -> Result<String, &'static str> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+        let end = rest.find(';').ok_or("Unterminated XML reference")?;
+        let c = match &rest[..end] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            reference => {
+                let code = if let Some(hex) = reference.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = reference.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or("Unknown XML reference")?
+            }
+        };
+        out.push(c);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
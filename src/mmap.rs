@@ -0,0 +1,185 @@
+//! Contains items for memory-mapped, zero-copy reading of wave files.
+//!
+//! Only available with the `mmap` feature enabled.
+
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, Cursor},
+    ops::Range,
+    path::Path,
+};
+
+use memmap2::Mmap;
+
+use crate::{decode_data, parse, BitDepth, Header, ReadOptions};
+
+/// A wave file mapped into memory, giving access to its samples without copying them where
+/// possible.
+///
+/// 8-bit samples are always borrowed straight from the mapping. 16-bit and 32-bit float samples
+/// are borrowed when the host is little-endian and the `"data"` chunk is correctly aligned for the
+/// sample type, which is the case for the vast majority of files. Everything else is decoded into
+/// an owned [`BitDepth`].
+#[derive(Debug)]
+pub struct MmapWav {
+    map: Mmap,
+    header: Header,
+    data: Range<usize>,
+}
+
+impl MmapWav {
+    /// Maps the file at `path` into memory and parses its header.
+    ///
+    /// ## Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other process, while the
+    /// returned `MmapWav` or any [`Samples`] borrowed from it are alive. See [`memmap2::Mmap::map`].
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the file can't be opened or mapped, under the same circumstances as
+    /// [`crate::read`] when parsing the header or locating the audio data, or if the `"data"`
+    /// chunk extends past the end of the file.
+    pub unsafe fn open<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_file(&File::open(path)?)
+    }
+
+    /// Maps the given `file` into memory and parses its header.
+    ///
+    /// ## Safety
+    ///
+    /// See [`Self::open`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`Self::open`].
+    pub unsafe fn from_file(file: &File) -> io::Result<Self> {
+        Self::from_mmap(Mmap::map(file)?)
+    }
+
+    fn from_mmap(map: Mmap) -> io::Result<Self> {
+        let (header, chunk) = parse(
+            &mut Cursor::new(&map[..]),
+            ReadOptions::default(),
+            &mut Vec::new(),
+        )?;
+
+        let start = chunk.data_offset();
+        let end = start + chunk.len;
+        match (usize::try_from(start), usize::try_from(end)) {
+            (Ok(start), Ok(end)) if end <= map.len() => Ok(Self {
+                map,
+                header,
+                data: start..end,
+            }),
+            _ => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "\"data\" chunk extends past the end of the file",
+            )),
+        }
+    }
+
+    /// Returns the header of the wave file.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the raw bytes of the `"data"` chunk.
+    #[must_use]
+    pub fn data_bytes(&self) -> &[u8] {
+        &self.map[self.data.clone()]
+    }
+
+    /// Returns the total number of complete frames in the `"data"` chunk.
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        match self.header.bytes_per_sample {
+            0 => 0,
+            align => self.data.len() / usize::from(align),
+        }
+    }
+
+    /// Returns all the samples of the `"data"` chunk.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the header specifies an unsupported data format or bit-depth.
+    pub fn samples(&self) -> io::Result<Samples<'_>> {
+        self.frames(0, self.frame_count())
+    }
+
+    /// Returns up to `count` frames starting at the frame index `start`. Fewer frames are returned
+    /// if the end of the audio data is reached.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `start` lies beyond the end of the audio data, or if the header
+    /// specifies an unsupported data format or bit-depth.
+    pub fn frames(&self, start: usize, count: usize) -> io::Result<Samples<'_>> {
+        if start > self.frame_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Start position lies beyond the end of the audio data",
+            ));
+        }
+
+        let count = count.min(self.frame_count() - start);
+        let align = usize::from(self.header.bytes_per_sample);
+        let bytes = &self.data_bytes()[start * align..(start + count) * align];
+
+        match Samples::borrow(&self.header, bytes) {
+            Some(samples) => Ok(samples),
+            None => decode_data(&self.header, bytes.to_vec()).map(Samples::Decoded),
+        }
+    }
+}
+
+/// Samples read from a [`MmapWav`], either borrowed from the mapping or decoded into an owned
+/// [`BitDepth`] when borrowing isn't possible.
+#[derive(Debug, PartialEq, Clone)]
+#[allow(missing_docs)]
+pub enum Samples<'a> {
+    Eight(&'a [u8]),
+    Sixteen(&'a [i16]),
+    ThirtyTwoFloat(&'a [f32]),
+    Decoded(BitDepth),
+}
+
+impl<'a> Samples<'a> {
+    fn borrow(header: &Header, bytes: &'a [u8]) -> Option<Self> {
+        match (header.effective_format(), header.bits_per_sample) {
+            (crate::WAV_FORMAT_PCM, 8) => Some(Samples::Eight(bytes)),
+            (crate::WAV_FORMAT_PCM, 16) if cfg!(target_endian = "little") => {
+                bytemuck::try_cast_slice(bytes).ok().map(Samples::Sixteen)
+            }
+            (crate::WAV_FORMAT_IEEE_FLOAT, 32) if cfg!(target_endian = "little") => {
+                bytemuck::try_cast_slice(bytes)
+                    .ok()
+                    .map(Samples::ThirtyTwoFloat)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the samples are borrowed from the mapping rather than decoded.
+    #[must_use]
+    pub fn is_borrowed(&self) -> bool {
+        !matches!(self, Samples::Decoded(..))
+    }
+
+    /// Converts the samples into an owned [`BitDepth`], copying them if they are borrowed.
+    #[must_use]
+    pub fn into_owned(self) -> BitDepth {
+        match self {
+            Samples::Eight(v) => BitDepth::Eight(v.to_vec()),
+            Samples::Sixteen(v) => BitDepth::Sixteen(v.to_vec()),
+            Samples::ThirtyTwoFloat(v) => BitDepth::ThirtyTwoFloat(v.to_vec()),
+            Samples::Decoded(v) => v,
+        }
+    }
+}
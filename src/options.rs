@@ -0,0 +1,129 @@
+//! Contains items for configuring how wave files are read, and for reporting what was done to
+//! make sense of them.
+
+use std::{fmt, io};
+
+use crate::header::{Header, HeaderIssue, InvalidHeader};
+
+/// How the header of a wave file is checked when reading it.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Validation {
+    /// The header is accepted as-is.
+    #[default]
+    Unchecked,
+    /// Reading fails if [`Header::validate`] reports any issue.
+    Strict,
+    /// Inconsistent derived fields are repaired with [`Header::repair`] and reported as
+    /// [`Warning::HeaderRepaired`]. Reading fails only if the header can't be repaired.
+    Lenient,
+}
+
+impl Validation {
+    pub(crate) fn apply(self, header: &mut Header, warnings: &mut Vec<Warning>) -> io::Result<()> {
+        let result = match self {
+            Validation::Unchecked => Ok(()),
+            Validation::Strict => header.validate(),
+            Validation::Lenient => header
+                .repair()
+                .map(|repaired| warnings.extend(repaired.into_iter().map(Warning::HeaderRepaired))),
+        };
+
+        result
+            .map_err(|issues| io::Error::new(io::ErrorKind::InvalidData, InvalidHeader { issues }))
+    }
+}
+
+/// Options for [`crate::read_with_options`] and [`crate::WavReader::with_options`].
+///
+/// ## Example
+///
+/// ```
+/// use wav::{ReadOptions, Validation};
+///
+/// let options = ReadOptions {
+///     validation: Validation::Lenient,
+///     ..ReadOptions::default()
+/// };
+/// ```
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReadOptions {
+    /// How the header is checked.
+    pub validation: Validation,
+    /// Whether to recover what audio can be recovered from malformed or truncated files instead
+    /// of failing.
+    ///
+    /// When set, the RIFF size and chunk sizes are clamped to the real length of the stream, a
+    /// `"data"` chunk size of 0 or `0xFFFFFFFF` (left by recorders that never finalized the file)
+    /// is taken to extend to the end of the stream, and a trailing partial frame is dropped. Each
+    /// fix is reported as a [`Warning`].
+    pub recover: bool,
+}
+
+/// Something that was wrong with a wave file and has been worked around while reading it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Warning {
+    /// A derived field of the header was inconsistent and has been repaired.
+    HeaderRepaired(HeaderIssue),
+    /// The size of the RIFF data doesn't match the length of the stream, which has been used
+    /// instead.
+    RiffSizeMismatch {
+        /// Size stored in the RIFF header, or in the `"ds64"` chunk of RF64 and BW64 files.
+        declared: u64,
+        /// Size according to the length of the stream.
+        actual: u64,
+    },
+    /// A chunk size was unknown or extended past the end of the stream, and has been clamped to
+    /// the end of the stream.
+    ChunkSizeClamped {
+        /// ID of the chunk.
+        id: [u8; 4],
+        /// Size stored in the chunk header.
+        declared: u32,
+        /// Size used instead.
+        recovered: u64,
+    },
+    /// A chunk of odd length wasn't followed by the pad byte required by RIFF. The following chunk
+    /// has been read from where it actually starts.
+    MissingPadByte {
+        /// ID of the chunk missing its pad byte.
+        id: [u8; 4],
+    },
+    /// The audio data ended with an incomplete frame, which has been dropped.
+    PartialFrameDropped {
+        /// Number of bytes dropped.
+        bytes: u64,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::HeaderRepaired(issue) => write!(f, "Repaired header: {issue}"),
+            Warning::RiffSizeMismatch { declared, actual } => write!(
+                f,
+                "RIFF size is {declared} bytes, but the stream holds {actual} bytes"
+            ),
+            Warning::ChunkSizeClamped {
+                id,
+                declared,
+                recovered,
+            } => write!(
+                f,
+                "Clamped size of chunk \"{}\" from {declared} to {recovered} bytes",
+                String::from_utf8_lossy(id)
+            ),
+            Warning::MissingPadByte { id } => write!(
+                f,
+                "Chunk \"{}\" is missing its pad byte",
+                String::from_utf8_lossy(id)
+            ),
+            Warning::PartialFrameDropped { bytes } => {
+                write!(f, "Dropped {bytes} bytes of a trailing partial frame")
+            }
+        }
+    }
+}
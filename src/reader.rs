@@ -0,0 +1,210 @@
+//! Contains items for random-access reading of the `"data"` chunk of wave files.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{chunk::ChunkHeader, decode_data, parse, BitDepth, Header, ReadOptions, Warning};
+
+/// Reader giving sample-accurate access to the frames of a wave file without decoding the whole
+/// `"data"` chunk.
+///
+/// A frame is one sample for every channel, so its size in bytes is the block align of the file,
+/// stored in [`Header::bytes_per_sample`].
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::fs::File;
+///
+/// let mut reader = wav::WavReader::new(File::open("data/sine.wav")?)?;
+/// reader.seek(1_000)?;
+/// let window = reader.read_frames(480)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WavReader<R> {
+    reader: R,
+    header: Header,
+    frames: FrameCursor,
+    warnings: Vec<Warning>,
+}
+
+impl<R> WavReader<R>
+where
+    R: Read + Seek,
+{
+    /// Parses the header of the wave file in `reader` and locates its `"data"` chunk, positioning
+    /// the reader at the first frame.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`crate::read`] when parsing the header
+    /// or locating the audio data, or if the header specifies a block align of zero.
+    pub fn new(reader: R) -> io::Result<Self> {
+        Self::with_options(reader, ReadOptions::default())
+    }
+
+    /// Parses the header of the wave file in `reader` like [`Self::new`], applying the given
+    /// `options`. The [`Warning`]s raised are available through [`Self::warnings`].
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`Self::new`], and additionally if the
+    /// header doesn't pass the validation selected by `options`.
+    pub fn with_options(mut reader: R, options: ReadOptions) -> io::Result<Self> {
+        let mut warnings = Vec::new();
+        let (header, data) = parse(&mut reader, options, &mut warnings)?;
+        let frames = FrameCursor::new(&header, &data)?;
+        reader.seek(SeekFrom::Start(frames.data_offset))?;
+
+        Ok(Self {
+            reader,
+            header,
+            frames,
+            warnings,
+        })
+    }
+
+    /// Returns the header of the wave file.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the warnings raised while parsing the file.
+    #[must_use]
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns the total number of complete frames in the `"data"` chunk.
+    #[must_use]
+    pub fn frame_count(&self) -> u64 {
+        self.frames.frame_count()
+    }
+
+    /// Returns the index of the frame that will be read next.
+    #[must_use]
+    pub fn position(&self) -> u64 {
+        self.frames.position
+    }
+
+    /// Moves the reader to the frame at index `frame`. Seeking to [`Self::frame_count`] is allowed
+    /// and leaves nothing left to read.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `frame` lies beyond the end of the audio data, or if seeking the
+    /// underlying reader fails.
+    pub fn seek(&mut self, frame: u64) -> io::Result<()> {
+        let offset = self.frames.seek_offset(frame)?;
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.frames.position = frame;
+
+        Ok(())
+    }
+
+    /// Reads and decodes up to `count` frames starting at the current position, advancing the
+    /// position past them. Fewer frames are returned if the end of the audio data is reached.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if reading from the underlying reader fails, or if the header specifies
+    /// an unsupported data format or bit-depth.
+    pub fn read_frames(&mut self, count: u64) -> io::Result<BitDepth> {
+        let (count, len) = self.frames.read_len(count)?;
+
+        let mut bytes = vec![0; len];
+        self.reader.read_exact(&mut bytes)?;
+        self.frames.position += count;
+
+        decode_data(&self.header, bytes)
+    }
+
+    /// Consumes the `WavReader`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// The position of a reader within the frames of the `"data"` chunk, shared by the blocking and
+/// async readers.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct FrameCursor {
+    pub(crate) data_offset: u64,
+    pub(crate) position: u64,
+    data_len: u64,
+    block_align: u64,
+}
+
+impl FrameCursor {
+    pub(crate) fn new(header: &Header, data: &ChunkHeader) -> io::Result<Self> {
+        if header.bytes_per_sample == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Header specifies a block align of zero",
+            ));
+        }
+
+        Ok(FrameCursor {
+            data_offset: data.data_offset(),
+            position: 0,
+            data_len: data.len,
+            block_align: u64::from(header.bytes_per_sample),
+        })
+    }
+
+    pub(crate) fn frame_count(&self) -> u64 {
+        self.data_len / self.block_align
+    }
+
+    /// Returns the stream offset of the frame at index `frame`.
+    pub(crate) fn seek_offset(&self, frame: u64) -> io::Result<u64> {
+        if frame > self.frame_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek position lies beyond the end of the audio data",
+            ));
+        }
+
+        Ok(self.data_offset + frame * self.block_align)
+    }
+
+    /// Returns how many of `count` frames can be read from the current position, and their length
+    /// in bytes.
+    pub(crate) fn read_len(&self, count: u64) -> io::Result<(u64, usize)> {
+        let count = count.min(self.frame_count() - self.position);
+        let len = usize::try_from(count * self.block_align).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Requested range does not fit in memory",
+            )
+        })?;
+
+        Ok((count, len))
+    }
+}
+
+/// Reads `count` frames starting at the frame index `start` from the given `reader`, decoding only
+/// that window of the `"data"` chunk.
+///
+/// Fewer than `count` frames are returned if the end of the audio data is reached first.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`WavReader::new`], [`WavReader::seek`]
+/// and [`WavReader::read_frames`].
+pub fn read_range<R>(reader: &mut R, start: u64, count: u64) -> io::Result<(Header, BitDepth)>
+where
+    R: Read + Seek,
+{
+    let mut reader = WavReader::new(reader)?;
+    reader.seek(start)?;
+    let data = reader.read_frames(count)?;
+
+    Ok((*reader.header(), data))
+}
@@ -0,0 +1,323 @@
+//! Contains items for changing the sampling rate of audio data.
+//!
+//! Resampling uses a band-limited interpolator: a Kaiser-windowed sinc filter evaluated at every
+//! phase of the rational ratio between the two rates. The output only depends on the input and
+//! the chosen [`Quality`], so it is identical across runs and whether the audio is processed at
+//! once with [`resample`] or in blocks with a [`Resampler`].
+//!
+//! ## Example
+//!
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use std::fs::File;
+//! use wav::resample::{self, Quality};
+//!
+//! let (header, data) = wav::read(&mut File::open("data/sine.wav")?)?;
+//! let (header, data) = resample::resample(&header, &data, 44_100, Quality::High)
+//!     .map_err(std::io::Error::other)?;
+//! assert_eq!(header.sampling_rate, 44_100);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{convert::TryFrom, f64::consts::PI};
+
+use crate::{BitDepth, Header, Sample};
+
+/// Trade-off between speed and accuracy of a [`Resampler`].
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Quality {
+    /// 16 taps per output sample, for previews.
+    Low,
+    /// 32 taps per output sample.
+    #[default]
+    Medium,
+    /// 64 taps per output sample, keeping aliasing below audibility for mastering.
+    High,
+}
+
+impl Quality {
+    /// Returns the half-width of the filter in taps, its Kaiser window parameter and its cutoff
+    /// relative to the lower of the two Nyquist frequencies.
+    fn parameters(self) -> (u64, f64, f64) {
+        match self {
+            Quality::Low => (8, 6.0, 0.85),
+            Quality::Medium => (16, 8.0, 0.9),
+            Quality::High => (32, 10.0, 0.945),
+        }
+    }
+}
+
+/// Filters with more phases than this compute their coefficients for every output sample instead
+/// of keeping a table of them.
+const MAX_TABLE_PHASES: u64 = 1024;
+
+/// Streaming resampler for interleaved `f32` frames.
+///
+/// Frames are fed with [`Resampler::process`], which returns every output frame that can be
+/// computed from the input seen so far, and [`Resampler::finish`] returns the remaining ones.
+/// Together they produce `ceil(input_frames * to / from)` frames.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    channels: usize,
+    up: u64,
+    down: u64,
+    half: u64,
+    beta: f64,
+    cutoff: f64,
+    table: Vec<f64>,
+    scratch: Vec<f64>,
+    buffer: Vec<f32>,
+    buffer_start: u64,
+    received: u64,
+    next_out: u64,
+}
+
+impl Resampler {
+    /// Creates a resampler from the sampling rate `from` to the sampling rate `to` for frames of
+    /// `channels` samples.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if either rate or the channel count is zero.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn new(from: u32, to: u32, channels: u16, quality: Quality) -> Result<Self, &'static str> {
+        if from == 0 || to == 0 {
+            return Err("Sampling rate must not be zero");
+        }
+        if channels == 0 {
+            return Err("Channel count must not be zero");
+        }
+
+        let divisor = gcd(from, to);
+        let (up, down) = (u64::from(to / divisor), u64::from(from / divisor));
+        let (taps, beta, rolloff) = quality.parameters();
+
+        // When downsampling, the filter is stretched to cut off below the new Nyquist frequency
+        let ratio = (up as f64 / down as f64).min(1.0);
+        let half = if up == down {
+            0
+        } else {
+            (taps as f64 / ratio).ceil() as u64
+        };
+
+        let mut resampler = Resampler {
+            channels: usize::from(channels),
+            up,
+            down,
+            half,
+            beta,
+            cutoff: ratio * rolloff,
+            table: Vec::new(),
+            scratch: vec![0.0; 2 * usize::try_from(half).unwrap_or(0)],
+            buffer: Vec::new(),
+            buffer_start: 0,
+            received: 0,
+            next_out: 0,
+        };
+        if up <= MAX_TABLE_PHASES {
+            let mut table = Vec::with_capacity(resampler.scratch.len() * up as usize);
+            for phase in 0..up {
+                resampler.compute(phase);
+                table.extend_from_slice(&resampler.scratch);
+            }
+            resampler.table = table;
+        }
+
+        Ok(resampler)
+    }
+
+    /// Feeds interleaved frames to the resampler, returning the interleaved output frames that
+    /// could be computed. A trailing partial frame in `input` is ignored.
+    #[must_use]
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let whole = input.len() - input.len() % self.channels;
+        self.buffer.extend_from_slice(&input[..whole]);
+        self.received += (whole / self.channels) as u64;
+
+        self.drain(None)
+    }
+
+    /// Returns the output frames left once the end of the input is reached, treating the input as
+    /// silent past its end.
+    #[must_use]
+    pub fn finish(mut self) -> Vec<f32> {
+        let total = (self.received * self.up).div_ceil(self.down);
+        self.drain(Some(total))
+    }
+
+    /// Computes output frames until one needs input that hasn't been received, or until `total`
+    /// frames have been output.
+    #[allow(clippy::cast_possible_truncation)]
+    fn drain(&mut self, total: Option<u64>) -> Vec<f32> {
+        let mut out = Vec::new();
+        let mut acc = vec![0.0f64; self.channels];
+        let taps = self.scratch.len();
+
+        loop {
+            let pos = self.next_out * self.down;
+            let (center, phase) = (pos / self.up, pos % self.up);
+            match total {
+                Some(total) if self.next_out >= total => break,
+                None if center + self.half >= self.received => break,
+                _ => {}
+            }
+
+            if self.half == 0 {
+                // Same rate, the filter is the identity
+                out.extend_from_slice(self.frame(center).unwrap_or(&[]));
+            } else {
+                if self.table.is_empty() {
+                    self.compute(phase);
+                }
+                let coefficients = if self.table.is_empty() {
+                    &self.scratch[..]
+                } else {
+                    let start = phase as usize * taps;
+                    &self.table[start..start + taps]
+                };
+
+                acc.fill(0.0);
+                for (j, c) in (0..).zip(coefficients) {
+                    // Tap `j` reads input frame `center + 1 + j - half`
+                    let index = (center + 1 + j).checked_sub(self.half);
+                    if let Some(frame) = index.and_then(|i| self.frame(i)) {
+                        for (a, &s) in acc.iter_mut().zip(frame) {
+                            *a += c * f64::from(s);
+                        }
+                    }
+                }
+                out.extend(acc.iter().map(|&a| a as f32));
+            }
+            self.next_out += 1;
+        }
+
+        // Drop the frames no later output frame reads
+        let first = (self.next_out * self.down / self.up + 1).saturating_sub(self.half.max(1));
+        let drop = first.clamp(self.buffer_start, self.received) - self.buffer_start;
+        self.buffer.drain(..drop as usize * self.channels);
+        self.buffer_start += drop;
+
+        out
+    }
+
+    /// Returns the input frame at index `index`, or `None` if it's outside of the buffered input.
+    #[allow(clippy::cast_possible_truncation)]
+    fn frame(&self, index: u64) -> Option<&[f32]> {
+        if index < self.buffer_start || index >= self.received {
+            return None;
+        }
+        let start = (index - self.buffer_start) as usize * self.channels;
+        self.buffer.get(start..start + self.channels)
+    }
+
+    /// Computes the normalized filter coefficients for the output sample `phase / up` input frames
+    /// past an input frame into `scratch`.
+    #[allow(clippy::cast_precision_loss)]
+    fn compute(&mut self, phase: u64) {
+        let half = self.half as f64;
+        let offset = phase as f64 / self.up as f64;
+        let i0_beta = bessel_i0(self.beta);
+
+        for (j, c) in self.scratch.iter_mut().enumerate() {
+            // Distance from the tap to the output sample, in input frames
+            let x = offset + half - 1.0 - j as f64;
+            let u = x / half;
+            let window = if u.abs() <= 1.0 {
+                bessel_i0(self.beta * (1.0 - u * u).sqrt()) / i0_beta
+            } else {
+                0.0
+            };
+            *c = self.cutoff * sinc(self.cutoff * x) * window;
+        }
+
+        let sum: f64 = self.scratch.iter().sum();
+        for c in &mut self.scratch {
+            *c /= sum;
+        }
+    }
+}
+
+/// Returns `header` changed to describe the same data at the given sampling rate.
+///
+/// ## Errors
+///
+/// This function fails if `sampling_rate` is zero, or if the byte rate overflows.
+pub fn resampled_header(header: &Header, sampling_rate: u32) -> Result<Header, &'static str> {
+    if sampling_rate == 0 {
+        return Err("Sampling rate must not be zero");
+    }
+
+    let mut header = *header;
+    header.sampling_rate = sampling_rate;
+    header.bytes_per_second = u32::from(header.bytes_per_sample)
+        .checked_mul(sampling_rate)
+        .ok_or("Byte rate overflows at the new sampling rate")?;
+
+    Ok(header)
+}
+
+/// Resamples all of `data` to the given sampling rate, returning the updated header and the
+/// samples in their original format.
+///
+/// ## Errors
+///
+/// This function fails if either rate or the channel count of `header` is zero, or if the byte
+/// rate overflows at the new sampling rate.
+pub fn resample(
+    header: &Header,
+    data: &BitDepth,
+    sampling_rate: u32,
+    quality: Quality,
+) -> Result<(Header, BitDepth), &'static str> {
+    let new_header = resampled_header(header, sampling_rate)?;
+    let mut resampler = Resampler::new(
+        header.sampling_rate,
+        sampling_rate,
+        header.channel_count,
+        quality,
+    )?;
+
+    let Some(format) = data.sample_format() else {
+        return Ok((new_header, BitDepth::Empty));
+    };
+    let samples = f32::from_bit_depth(data);
+
+    let mut out = resampler.process(&samples);
+    out.extend(resampler.finish());
+
+    Ok((new_header, BitDepth::ThirtyTwoFloat(out).convert(format)))
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub(crate) fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind, used by the Kaiser window.
+pub(crate) fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-16 {
+        term *= (x / (2.0 * k)) * (x / (2.0 * k));
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
@@ -0,0 +1,154 @@
+//! Contains the [`Sample`] trait, giving typed access to the samples of a [`BitDepth`].
+//!
+//! Samples are converted with [`BitDepth::convert`], so integer samples of every width are scaled
+//! to the full range of the requested type and float samples span `-1.0..1.0`.
+//!
+//! ## Example
+//!
+//! ```
+//! use wav::{BitDepth, Sample};
+//!
+//! let data = BitDepth::Sixteen(vec![0, 16_384, -32_768]);
+//! assert_eq!(*f32::from_bit_depth(&data), [0.0, 0.5, -1.0]);
+//! assert_eq!(*u8::from_bit_depth(&data), [128, 192, 0]);
+//! ```
+
+use alloc::{borrow::Cow, vec::Vec};
+
+use crate::{BitDepth, SampleFormat};
+
+/// Type of the samples of a wave file.
+///
+/// Implemented for `u8`, `i16`, [`I24`], `i32`, `f32` and `f64`, which read and write audio of the
+/// [`SampleFormat`] given by [`Sample::FORMAT`].
+pub trait Sample: Copy {
+    /// Format of the samples in a wave file, which they're read from without loss.
+    const FORMAT: SampleFormat;
+
+    /// Returns the samples of `data`, converted from any format, or borrowed if `data` already
+    /// holds samples of this type.
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]>;
+
+    /// Returns `samples` as samples of [`Sample::FORMAT`].
+    fn to_bit_depth(samples: &[Self]) -> BitDepth;
+}
+
+/// 24-bit sample stored in the low bits of an `i32`, between `-8_388_608` and `8_388_607`.
+///
+/// Unlike [`BitDepth::TwentyFour`], which holds 24-bit samples in the high bits of an `i32`, the
+/// value is that of the sample as stored in the file.
+#[derive(Debug, Default, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct I24(pub i32);
+
+impl I24 {
+    /// The smallest value of a 24-bit sample.
+    pub const MIN: I24 = I24(-(1 << 23));
+    /// The largest value of a 24-bit sample.
+    pub const MAX: I24 = I24((1 << 23) - 1);
+}
+
+impl From<I24> for i32 {
+    fn from(sample: I24) -> Self {
+        sample.0
+    }
+}
+
+/// Returns the samples of `data` if it is of the variant matched by `variant`, or else converted
+/// to `format`, which must be the format of the samples of that variant.
+macro_rules! borrow_or_convert {
+    ($data:expr, $format:expr, $variant:path) => {
+        match $data {
+            $variant(v) => Cow::Borrowed(v.as_slice()),
+            data => match data.convert($format) {
+                $variant(v) => Cow::Owned(v),
+                _ => Cow::Owned(Vec::new()),
+            },
+        }
+    };
+}
+
+impl Sample for u8 {
+    const FORMAT: SampleFormat = SampleFormat::Eight;
+
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]> {
+        borrow_or_convert!(data, Self::FORMAT, BitDepth::Eight)
+    }
+
+    fn to_bit_depth(samples: &[Self]) -> BitDepth {
+        BitDepth::Eight(samples.to_vec())
+    }
+}
+
+impl Sample for i16 {
+    const FORMAT: SampleFormat = SampleFormat::Sixteen;
+
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]> {
+        borrow_or_convert!(data, Self::FORMAT, BitDepth::Sixteen)
+    }
+
+    fn to_bit_depth(samples: &[Self]) -> BitDepth {
+        BitDepth::Sixteen(samples.to_vec())
+    }
+}
+
+impl Sample for I24 {
+    const FORMAT: SampleFormat = SampleFormat::TwentyFour;
+
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]> {
+        let samples = i32::from_bit_depth(data);
+        samples.iter().map(|s| I24(s >> 8)).collect()
+    }
+
+    /// Values out of the 24-bit range are clamped.
+    fn to_bit_depth(samples: &[Self]) -> BitDepth {
+        BitDepth::TwentyFour(
+            samples
+                .iter()
+                .map(|s| s.0.clamp(I24::MIN.0, I24::MAX.0) << 8)
+                .collect(),
+        )
+    }
+}
+
+/// Samples spanning the full range of `i32`, like those of [`BitDepth::TwentyFour`]. They're
+/// written as 24-bit audio, dropping the low 8 bits.
+impl Sample for i32 {
+    const FORMAT: SampleFormat = SampleFormat::TwentyFour;
+
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]> {
+        borrow_or_convert!(data, Self::FORMAT, BitDepth::TwentyFour)
+    }
+
+    fn to_bit_depth(samples: &[Self]) -> BitDepth {
+        BitDepth::TwentyFour(samples.iter().map(|s| s & !0xFF).collect())
+    }
+}
+
+impl Sample for f32 {
+    const FORMAT: SampleFormat = SampleFormat::ThirtyTwoFloat;
+
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]> {
+        borrow_or_convert!(data, Self::FORMAT, BitDepth::ThirtyTwoFloat)
+    }
+
+    fn to_bit_depth(samples: &[Self]) -> BitDepth {
+        BitDepth::ThirtyTwoFloat(samples.to_vec())
+    }
+}
+
+/// Samples written as 32-bit float audio, rounded to the precision of `f32`.
+impl Sample for f64 {
+    const FORMAT: SampleFormat = SampleFormat::ThirtyTwoFloat;
+
+    fn from_bit_depth(data: &BitDepth) -> Cow<'_, [Self]> {
+        let samples = f32::from_bit_depth(data);
+        samples.iter().copied().map(f64::from).collect()
+    }
+
+    // Rounding to the nearest `f32` is the documented behavior
+    #[allow(clippy::cast_possible_truncation)]
+    fn to_bit_depth(samples: &[Self]) -> BitDepth {
+        BitDepth::ThirtyTwoFloat(samples.iter().map(|&s| s as f32).collect())
+    }
+}
@@ -0,0 +1,233 @@
+//! Serialization helpers for fields whose raw representation isn't meaningful to a reader.
+//!
+//! Human-readable formats like JSON get names and text, while compact formats like bincode keep
+//! the raw values.
+
+use alloc::string::String;
+use core::fmt;
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serializer,
+};
+
+use crate::header::{WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM};
+
+/// Format tags, written as `"pcm"`, `"ieee_float"` or `"extensible"`, or as a number for any other
+/// tag.
+pub(crate) mod format_tag {
+    use core::convert::TryFrom;
+
+    use super::{de, fmt, Deserialize, Deserializer, Serializer, Visitor};
+    use super::{WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM};
+
+    const NAMES: [(u16, &str); 3] = [
+        (WAV_FORMAT_PCM, "pcm"),
+        (WAV_FORMAT_IEEE_FLOAT, "ieee_float"),
+        (WAV_FORMAT_EXTENSIBLE, "extensible"),
+    ];
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub(crate) fn serialize<S>(tag: &u16, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match NAMES.iter().find(|(t, _)| t == tag) {
+            Some((_, name)) if serializer.is_human_readable() => serializer.serialize_str(name),
+            _ => serializer.serialize_u16(*tag),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<u16, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(TagVisitor)
+        } else {
+            u16::deserialize(deserializer)
+        }
+    }
+
+    struct TagVisitor;
+
+    impl Visitor<'_> for TagVisitor {
+        type Value = u16;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a format name or a 16-bit format tag")
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<u16, E>
+        where
+            E: de::Error,
+        {
+            u16::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<u16, E>
+        where
+            E: de::Error,
+        {
+            NAMES
+                .iter()
+                .find(|(_, name)| *name == v)
+                .map(|(tag, _)| *tag)
+                .ok_or_else(|| E::unknown_variant(v, &["pcm", "ieee_float", "extensible"]))
+        }
+    }
+}
+
+/// GUIDs, written in their usual text form, e.g. `"00000001-0000-0010-8000-00aa00389b71"`.
+///
+/// The first three groups of a GUID are stored little-endian, and the rest as-is.
+pub(crate) mod guid {
+    use core::fmt::Write;
+
+    use serde::Serialize;
+
+    use super::{de, fmt, Deserialize, Deserializer, Serializer, String, Visitor};
+
+    pub(crate) fn serialize<S>(guid: &[u8; 16], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&to_string(guid))
+        } else {
+            guid.serialize(serializer)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<[u8; 16], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(GuidVisitor)
+        } else {
+            <[u8; 16]>::deserialize(deserializer)
+        }
+    }
+
+    /// Order in which the bytes of a GUID appear in its text form.
+    const TEXT_ORDER: [usize; 16] = [3, 2, 1, 0, 5, 4, 7, 6, 8, 9, 10, 11, 12, 13, 14, 15];
+
+    fn to_string(guid: &[u8; 16]) -> String {
+        let mut s = String::with_capacity(36);
+        for (i, &pos) in TEXT_ORDER.iter().enumerate() {
+            if let 4 | 6 | 8 | 10 = i {
+                s.push('-');
+            }
+            // Writing to a `String` can't fail
+            let _ = write!(s, "{:02x}", guid[pos]);
+        }
+
+        s
+    }
+
+    fn from_str(s: &str) -> Option<[u8; 16]> {
+        let groups: [usize; 5] = [8, 4, 4, 4, 12];
+        let mut digits = [0; 32];
+        let mut n = 0;
+        for (i, group) in s.split('-').enumerate() {
+            if groups.get(i) != Some(&group.len()) {
+                return None;
+            }
+            for b in group.bytes() {
+                digits[n] = char::from(b).to_digit(16)?;
+                n += 1;
+            }
+        }
+        if n != 32 {
+            return None;
+        }
+
+        let mut guid = [0; 16];
+        for (i, &pos) in TEXT_ORDER.iter().enumerate() {
+            // Both digits are below 16, so the byte can't overflow
+            #[allow(clippy::cast_possible_truncation)]
+            let byte = (digits[2 * i] * 16 + digits[2 * i + 1]) as u8;
+            guid[pos] = byte;
+        }
+
+        Some(guid)
+    }
+
+    struct GuidVisitor;
+
+    impl Visitor<'_> for GuidVisitor {
+        type Value = [u8; 16];
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a GUID such as \"00000001-0000-0010-8000-00aa00389b71\"")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<[u8; 16], E>
+        where
+            E: de::Error,
+        {
+            from_str(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+}
+
+/// Byte arrays of any length, which serde only supports up to 32 bytes, written like a byte slice.
+/// Only the metadata, which needs `std`, has such arrays.
+#[cfg(feature = "std")]
+pub(crate) mod byte_array {
+    use core::convert::TryFrom;
+
+    use super::{de, fmt, Deserializer, Serializer, Visitor};
+
+    pub(crate) fn serialize<S, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(ArrayVisitor::<N>)
+    }
+
+    struct ArrayVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for ArrayVisitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{N} bytes")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<[u8; N], E>
+        where
+            E: de::Error,
+        {
+            <[u8; N]>::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<[u8; N], A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut bytes = [0; N];
+            for (i, b) in bytes.iter_mut().enumerate() {
+                *b = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(de::Error::invalid_length(N + 1, &self));
+            }
+
+            Ok(bytes)
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Contains [`Wav`], holding audio whose sample type and channel count are known at compile time.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+#[cfg(feature = "std")]
+use std::io::{self, Read, Seek, Write};
+
+use crate::{header::HeaderBuildError, BitDepth, Header, HeaderBuilder, Sample};
+
+/// Audio of `CHANNELS` channels of samples of type `S`, stored as frames holding one sample per
+/// channel.
+///
+/// Frames of the wrong channel count can't be stored, so only files can fail to match the type,
+/// which is checked when converting from a [`Header`] and [`BitDepth`] or reading a file.
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::io::Cursor;
+/// use wav::Wav;
+///
+/// let mut stereo = Wav::<i16, 2>::new(48_000, Vec::new()).unwrap();
+/// stereo.frames.push([100, -100]);
+///
+/// let mut out = Cursor::new(Vec::new());
+/// stereo.write(&mut out)?;
+/// out.set_position(0);
+/// assert_eq!(Wav::<i16, 2>::read(&mut out)?, stereo);
+///
+/// // Reading it as mono or as float samples fails
+/// out.set_position(0);
+/// assert!(Wav::<i16, 1>::read(&mut out).is_err());
+/// out.set_position(0);
+/// assert!(Wav::<f32, 2>::read(&mut out).is_err());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Frames must match the channel count:
+///
+/// ```compile_fail
+/// let mut mono = wav::Wav::<i16, 1>::new(48_000, Vec::new()).unwrap();
+/// mono.frames.push([100, -100]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wav<S, const CHANNELS: usize> {
+    header: Header,
+    /// The frames of the audio.
+    pub frames: Vec<[S; CHANNELS]>,
+}
+
+impl<S, const CHANNELS: usize> Wav<S, CHANNELS>
+where
+    S: Sample,
+{
+    /// The channel count, failing to compile if `CHANNELS` doesn't fit a wave header.
+    #[allow(clippy::cast_possible_truncation)]
+    const CHANNEL_COUNT: u16 = {
+        assert!(
+            CHANNELS > 0 && CHANNELS <= u16::MAX as usize,
+            "A wave file must have between 1 and 65535 channels"
+        );
+        // Checked to fit just above, so the cast is exact
+        CHANNELS as u16
+    };
+
+    /// Creates the audio made of `frames` at `sampling_rate`, with the header built by
+    /// [`HeaderBuilder`] for the format of `S`.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `sampling_rate` is zero or too high for the byte rate to fit the
+    /// header.
+    pub fn new(sampling_rate: u32, frames: Vec<[S; CHANNELS]>) -> Result<Self, HeaderBuildError> {
+        let header = HeaderBuilder::new(S::FORMAT)
+            .channel_count(Self::CHANNEL_COUNT)
+            .sampling_rate(sampling_rate)
+            .build()?;
+
+        Ok(Wav { header, frames })
+    }
+
+    /// Creates the audio made of `frames`, described by `header`, which keeps any
+    /// [`crate::header::FormatExtension`] such as the speaker positions.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the channel count or sample format of `header` doesn't match the
+    /// type.
+    pub fn with_header(header: Header, frames: Vec<[S; CHANNELS]>) -> Result<Self, &'static str> {
+        if header.channel_count != Self::CHANNEL_COUNT {
+            return Err("Channel count doesn't match the Wav type");
+        }
+        if header.sample_format() != Some(S::FORMAT) {
+            return Err("Sample format doesn't match the Wav type");
+        }
+
+        Ok(Wav { header, frames })
+    }
+
+    /// Converts the interleaved samples in `data`, described by `header`, into typed audio.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the channel count or sample format of `header` or the format of
+    /// `data` doesn't match the type, or if `data` doesn't make up whole frames.
+    pub fn from_parts(header: Header, data: &BitDepth) -> Result<Self, &'static str> {
+        let mut wav = Self::with_header(header, Vec::new())?;
+        if !data.is_empty() && data.sample_format() != Some(S::FORMAT) {
+            return Err("Sample format doesn't match the Wav type");
+        }
+
+        let samples = S::from_bit_depth(data);
+        if samples.len() % CHANNELS != 0 {
+            return Err("Audio data doesn't make up whole frames");
+        }
+        wav.frames = samples
+            .chunks_exact(CHANNELS)
+            .filter_map(|frame| <[S; CHANNELS]>::try_from(frame).ok())
+            .collect();
+
+        Ok(wav)
+    }
+
+    /// Returns the header and the interleaved samples of the audio.
+    #[must_use]
+    pub fn to_parts(&self) -> (Header, BitDepth) {
+        (self.header, S::to_bit_depth(&self.frames.concat()))
+    }
+
+    /// Returns the header of the audio.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the sampling rate of the audio.
+    #[must_use]
+    pub fn sampling_rate(&self) -> u32 {
+        self.header.sampling_rate
+    }
+
+    /// Reads in the wave file in `reader` like [`crate::read`].
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`crate::read`] and
+    /// [`Self::from_parts`].
+    #[cfg(feature = "std")]
+    pub fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: Read + Seek,
+    {
+        let (header, data) = crate::read(reader)?;
+        Self::from_parts(header, &data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Writes the audio to `writer` like [`crate::write()`].
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`crate::write()`].
+    #[cfg(feature = "std")]
+    pub fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write + Seek,
+    {
+        let (header, data) = self.to_parts();
+        crate::write(header, &data, writer)
+    }
+}
+
+impl<S, const CHANNELS: usize> TryFrom<(Header, BitDepth)> for Wav<S, CHANNELS>
+where
+    S: Sample,
+{
+    type Error = &'static str;
+
+    /// Converts the header and interleaved samples of a wave file like [`Wav::from_parts`].
+    ///
+    /// ## Errors
+    ///
+    /// See [`Wav::from_parts`].
+    fn try_from((header, data): (Header, BitDepth)) -> Result<Self, Self::Error> {
+        Self::from_parts(header, &data)
+    }
+}
+
+impl<S, const CHANNELS: usize> From<&Wav<S, CHANNELS>> for (Header, BitDepth)
+where
+    S: Sample,
+{
+    fn from(wav: &Wav<S, CHANNELS>) -> Self {
+        wav.to_parts()
+    }
+}
@@ -0,0 +1,320 @@
+//! Contains items for summarizing audio data into waveform overviews, like the peak files drawn
+//! by audio editors.
+//!
+//! Every channel is summarized by its minimum, maximum and RMS over blocks of frames. A
+//! [`Waveform`] holds several levels of such summaries, each coarser than the previous one by a
+//! constant factor, so a viewer can draw any zoom level from a few values per pixel. All levels are
+//! computed in one pass over the audio by a [`WaveformGenerator`].
+//!
+//! ## Example
+//!
+//! ```
+//! # fn main() -> std::io::Result<()> {
+//! use std::fs::File;
+//! use wav::waveform::{self, WaveformOptions};
+//!
+//! let options = WaveformOptions {
+//!     block_frames: 64,
+//!     levels: 3,
+//!     factor: 8,
+//! };
+//! let overview = waveform::waveform_reader(File::open("data/sine.wav")?, options)?;
+//! let level = overview.level_for(1_000).unwrap();
+//! assert_eq!(level.block_frames, 512);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, Read, Seek};
+
+use crate::{BitDepth, Header, Sample, WavReader};
+
+/// Number of frames summarized at once by [`waveform_reader`].
+const BLOCK_FRAMES: u64 = 65_536;
+
+/// Options for the levels of a [`Waveform`].
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaveformOptions {
+    /// Number of frames summarized by each block of the finest level.
+    pub block_frames: u64,
+    /// Number of levels.
+    pub levels: usize,
+    /// Number of blocks of a level summarized by each block of the next one.
+    pub factor: u64,
+}
+
+impl Default for WaveformOptions {
+    /// A single level of blocks of 256 frames, with a factor of 4 for any added levels.
+    fn default() -> Self {
+        WaveformOptions {
+            block_frames: 256,
+            levels: 1,
+            factor: 4,
+        }
+    }
+}
+
+/// Summary of the samples of one channel over a block of frames, as floating point values
+/// between -1 and 1.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    /// Lowest sample.
+    pub min: f32,
+    /// Highest sample.
+    pub max: f32,
+    /// Root mean square of the samples.
+    pub rms: f32,
+}
+
+/// Summaries of every channel over consecutive blocks of the same number of frames. The last
+/// block may summarize fewer frames.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Level {
+    /// Number of frames summarized by each block.
+    pub block_frames: u64,
+    /// Summaries of every channel for each block in turn, interleaved like samples.
+    pub summaries: Vec<Summary>,
+}
+
+impl Level {
+    /// Returns the summaries of every channel for the block at `index`, given the channel count
+    /// of the audio.
+    #[must_use]
+    pub fn block(&self, index: usize, channel_count: u16) -> Option<&[Summary]> {
+        let channels = usize::from(channel_count);
+        let start = index.checked_mul(channels)?;
+        self.summaries.get(start..start.checked_add(channels)?)
+    }
+}
+
+/// Multi-resolution overview of audio data, created by a [`WaveformGenerator`].
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Waveform {
+    /// Sampling rate of the audio, to convert blocks to time.
+    pub sampling_rate: u32,
+    /// Number of channels of the audio.
+    pub channel_count: u16,
+    /// Number of frames of the audio.
+    pub frames: u64,
+    /// The levels, from the finest to the coarsest.
+    pub levels: Vec<Level>,
+}
+
+impl Waveform {
+    /// Returns the coarsest level whose blocks don't span more than `frames` frames, to draw the
+    /// waveform with one block per `frames` frames or fewer. If every level is coarser, the finest
+    /// one is returned.
+    #[must_use]
+    pub fn level_for(&self, frames: u64) -> Option<&Level> {
+        self.levels
+            .iter()
+            .rev()
+            .find(|level| level.block_frames <= frames)
+            .or_else(|| self.levels.first())
+    }
+}
+
+/// Running summary of the samples of a channel.
+#[derive(Debug, Copy, Clone)]
+struct Accumulator {
+    min: f32,
+    max: f32,
+    squares: f64,
+    frames: u64,
+}
+
+impl Accumulator {
+    const EMPTY: Accumulator = Accumulator {
+        min: f32::INFINITY,
+        max: f32::NEG_INFINITY,
+        squares: 0.0,
+        frames: 0,
+    };
+
+    fn add(&mut self, s: f32) {
+        self.min = self.min.min(s);
+        self.max = self.max.max(s);
+        self.squares += f64::from(s) * f64::from(s);
+        self.frames += 1;
+    }
+
+    fn merge(&mut self, other: &Accumulator) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.squares += other.squares;
+        self.frames += other.frames;
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn summary(&self) -> Summary {
+        Summary {
+            min: self.min,
+            max: self.max,
+            // Samples between -1 and 1 have a mean square between 0 and 1, which f32 can hold
+            rms: (self.squares / self.frames as f64).sqrt() as f32,
+        }
+    }
+}
+
+/// Streaming generator summarizing interleaved frames into every level of a [`Waveform`].
+#[derive(Debug, Clone)]
+pub struct WaveformGenerator {
+    waveform: Waveform,
+    factor: u64,
+    /// Running summaries of the current block of every level, interleaved like samples.
+    current: Vec<Accumulator>,
+    /// Number of frames, or blocks of the previous level, summarized by the current block of every
+    /// level.
+    fill: Vec<u64>,
+}
+
+impl WaveformGenerator {
+    /// Creates a generator for audio described by `header`.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the channel count of `header` is zero, if `options` asks for blocks
+    /// of zero frames, for no levels or for a factor lower than 2 between several levels, or if the
+    /// blocks of the coarsest level would span more than `u64::MAX` frames.
+    pub fn new(header: &Header, options: WaveformOptions) -> Result<Self, &'static str> {
+        if header.channel_count == 0 {
+            return Err("Channel count must not be zero");
+        }
+        if options.block_frames == 0 || options.levels == 0 {
+            return Err("Waveform must have blocks of at least one frame and at least one level");
+        }
+        if options.levels > 1 && options.factor < 2 {
+            return Err("Factor between waveform levels must be at least 2");
+        }
+
+        let mut levels = Vec::with_capacity(options.levels);
+        let mut block_frames = Some(options.block_frames);
+        for _ in 0..options.levels {
+            let frames = block_frames.ok_or("Waveform blocks span too many frames")?;
+            levels.push(Level {
+                block_frames: frames,
+                summaries: Vec::new(),
+            });
+            block_frames = frames.checked_mul(options.factor);
+        }
+
+        let channels = usize::from(header.channel_count);
+        Ok(WaveformGenerator {
+            waveform: Waveform {
+                sampling_rate: header.sampling_rate,
+                channel_count: header.channel_count,
+                frames: 0,
+                levels,
+            },
+            factor: options.factor,
+            current: vec![Accumulator::EMPTY; channels * options.levels],
+            fill: vec![0; options.levels],
+        })
+    }
+
+    /// Feeds interleaved frames to the generator. A trailing partial frame is ignored.
+    pub fn process(&mut self, frames: &BitDepth) {
+        self.process_samples(&f32::from_bit_depth(frames));
+    }
+
+    /// Feeds interleaved frames of float samples to the generator, like [`Self::process`].
+    pub(crate) fn process_samples(&mut self, samples: &[f32]) {
+        let channels = usize::from(self.waveform.channel_count);
+
+        for frame in samples.chunks_exact(channels) {
+            for (acc, &s) in self.current.iter_mut().zip(frame) {
+                acc.add(s);
+            }
+            self.waveform.frames += 1;
+
+            self.fill[0] += 1;
+            if self.fill[0] == self.waveform.levels[0].block_frames {
+                self.end_block(0);
+            }
+        }
+    }
+
+    /// Returns the summaries of all the frames fed to the generator, including the partial blocks
+    /// at the end.
+    #[must_use]
+    pub fn finish(mut self) -> Waveform {
+        for level in 0..self.fill.len() {
+            if self.fill[level] > 0 {
+                self.push_block(level);
+            }
+        }
+        self.waveform
+    }
+
+    /// Outputs the current block of `level`, and the blocks of coarser levels it completes.
+    fn end_block(&mut self, level: usize) {
+        self.push_block(level);
+        if let Some(fill) = self.fill.get(level + 1) {
+            if *fill == self.factor {
+                self.end_block(level + 1);
+            }
+        }
+    }
+
+    /// Outputs the current block of `level`, merges it into the current block of the next level and
+    /// starts a new one.
+    fn push_block(&mut self, level: usize) {
+        let channels = usize::from(self.waveform.channel_count);
+        let start = level * channels;
+
+        let (current, coarser) = self.current.split_at_mut(start + channels);
+        let current = &mut current[start..];
+        self.waveform.levels[level]
+            .summaries
+            .extend(current.iter().map(Accumulator::summary));
+        for (coarse, acc) in coarser.iter_mut().zip(current.iter()) {
+            coarse.merge(acc);
+        }
+        current.fill(Accumulator::EMPTY);
+
+        self.fill[level] = 0;
+        if let Some(fill) = self.fill.get_mut(level + 1) {
+            *fill += 1;
+        }
+    }
+}
+
+/// Summarizes the interleaved frames in `data`, described by `header`.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`WaveformGenerator::new`].
+pub fn waveform(
+    header: &Header,
+    data: &BitDepth,
+    options: WaveformOptions,
+) -> Result<Waveform, &'static str> {
+    let mut generator = WaveformGenerator::new(header, options)?;
+    generator.process(data);
+    Ok(generator.finish())
+}
+
+/// Summarizes the wave file in `reader`, decoding it in blocks.
+///
+/// ## Errors
+///
+/// This function fails under the same circumstances as [`WavReader::new`] and
+/// [`WaveformGenerator::new`], or if any error occurs from the `reader` during reading.
+pub fn waveform_reader<R>(reader: R, options: WaveformOptions) -> io::Result<Waveform>
+where
+    R: Read + Seek,
+{
+    let mut reader = WavReader::new(reader)?;
+    let mut generator = WaveformGenerator::new(reader.header(), options)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    while reader.position() < reader.frame_count() {
+        generator.process(&reader.read_frames(BLOCK_FRAMES)?);
+    }
+
+    Ok(generator.finish())
+}
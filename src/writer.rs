@@ -0,0 +1,194 @@
+//! Contains items for writing the `"data"` chunk of wave files incrementally.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Seek, SeekFrom, Write},
+};
+
+use crate::{chunk, layout, BitDepth, Chunk, Header};
+
+/// Writer streaming frames into a wave file, so that the audio never has to be held in memory as
+/// a whole.
+///
+/// The sizes in the RIFF header and the `"data"` chunk header are unknown until every frame has
+/// been written, so they are filled in by [`WavWriter::finish`], which must be called once done.
+///
+/// ## Example
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// use std::io::Cursor;
+/// use wav::{BitDepth, Header, WavWriter, WAV_FORMAT_PCM};
+///
+/// let header = Header::new(WAV_FORMAT_PCM, 1, 48_000, 16);
+/// let mut writer = WavWriter::new(Cursor::new(Vec::new()), header)?;
+/// for _ in 0..10 {
+///     writer.write_frames(&BitDepth::Sixteen(vec![0; 480]))?;
+/// }
+/// let file = writer.finish()?.into_inner();
+///
+/// let (_, data) = wav::read(&mut Cursor::new(file))?;
+/// assert_eq!(data.as_sixteen().map(Vec::len), Some(4_800));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WavWriter<W> {
+    writer: W,
+    header: Header,
+    start: u64,
+    data_header: u64,
+    data_len: u64,
+}
+
+impl<W> WavWriter<W>
+where
+    W: Write + Seek,
+{
+    /// Writes the RIFF header and the `"fmt "` chunk for `header` to `writer`, ready for frames to
+    /// be written.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if `header` fails [`Header::validate`] or describes an unsupported
+    /// format, or if any error occurs from the `writer` during writing.
+    pub fn new(writer: W, header: Header) -> io::Result<Self> {
+        Self::with_chunks(writer, header, &[])
+    }
+
+    /// Like [`WavWriter::new`], also writing the extra `chunks` between the `"fmt "` and `"data"`
+    /// chunks.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`WavWriter::new`], and additionally if
+    /// any of the `chunks` is a `"fmt "` or `"data"` chunk.
+    pub fn with_chunks(mut writer: W, header: Header, chunks: &[Chunk]) -> io::Result<Self> {
+        crate::check_write(header, chunks)?;
+        if header.sample_format().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Unsupported data format or bit depth",
+            ));
+        }
+
+        let h_vec = Vec::from(header);
+        let contents = layout(&h_vec, chunks, &[]);
+        let (riff, headers) = chunk::riff_layout(&contents)?;
+
+        let start = writer.stream_position()?;
+        writer.write_all(&riff)?;
+        for ((_, data), header) in contents.iter().zip(headers) {
+            writer.write_all(&header)?;
+            writer.write_all(data)?;
+            if data.len() % 2 == 1 {
+                writer.write_all(&[0])?;
+            }
+        }
+        let data_header = writer.stream_position()? - 8;
+
+        Ok(WavWriter {
+            writer,
+            header,
+            start,
+            data_header,
+            data_len: 0,
+        })
+    }
+
+    /// Returns the header of the wave file.
+    #[must_use]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns the number of frames written so far.
+    #[must_use]
+    pub fn frames_written(&self) -> u64 {
+        self.data_len / u64::from(self.header.bytes_per_sample)
+    }
+
+    /// Writes the given frames, made of interleaved samples for every channel, after those already
+    /// written.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the samples aren't in the format given by the header or don't make up
+    /// whole frames, if the `"data"` chunk would grow past 4GiB, or if any error occurs from the
+    /// `writer` during writing.
+    pub fn write_frames(&mut self, frames: &BitDepth) -> io::Result<()> {
+        if frames.sample_format() != self.header.sample_format() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Samples don't match the format of the header",
+            ));
+        }
+
+        let bytes = crate::encode_data(frames)?;
+        if bytes.len() % usize::from(self.header.bytes_per_sample) != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Samples don't make up whole frames",
+            ));
+        }
+
+        let data_len = self.data_len + bytes.len() as u64;
+        if u32::try_from(data_len).is_err() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Data too big"));
+        }
+
+        self.writer.write_all(&bytes)?;
+        self.data_len = data_len;
+
+        Ok(())
+    }
+
+    /// Fills in the sizes in the RIFF header and the `"data"` chunk header, returning the
+    /// underlying writer positioned at the end of the wave file.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails if the wave file has grown past 4GiB, or if any error occurs from the
+    /// `writer` during writing.
+    pub fn finish(self) -> io::Result<W> {
+        self.finish_with_chunks(&[])
+    }
+
+    /// Like [`WavWriter::finish`], also writing the extra `chunks` after the `"data"` chunk, for
+    /// chunks computed from the audio such as a [`crate::metadata::Levl`] peak envelope.
+    ///
+    /// ## Errors
+    ///
+    /// This function fails under the same circumstances as [`WavWriter::finish`], and additionally
+    /// if any of the `chunks` is a `"fmt "` or `"data"` chunk.
+    pub fn finish_with_chunks(mut self, chunks: &[Chunk]) -> io::Result<W> {
+        crate::check_write(self.header, chunks)?;
+        if self.data_len % 2 == 1 {
+            self.writer.write_all(&[0])?;
+        }
+
+        let contents: Vec<_> = chunks.iter().map(|c| (c.id, c.data.as_slice())).collect();
+        let (_, headers) = chunk::riff_layout(&contents)?;
+        for ((_, data), header) in contents.iter().zip(headers) {
+            self.writer.write_all(&header)?;
+            self.writer.write_all(data)?;
+            if data.len() % 2 == 1 {
+                self.writer.write_all(&[0])?;
+            }
+        }
+        let end = self.writer.stream_position()?;
+
+        let too_big = || io::Error::new(io::ErrorKind::InvalidData, "Data too big");
+        let riff_len = u32::try_from(end - self.start - 8).map_err(|_| too_big())?;
+        let data_len = u32::try_from(self.data_len).map_err(|_| too_big())?;
+
+        self.writer.seek(SeekFrom::Start(self.start + 4))?;
+        self.writer.write_all(&riff_len.to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(self.data_header + 4))?;
+        self.writer.write_all(&data_len.to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(end))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
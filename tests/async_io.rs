@@ -0,0 +1,95 @@
+#![cfg(feature = "async")]
+
+use std::io;
+
+use futures_executor::block_on;
+use futures_util::io::Cursor;
+
+use wav::{async_io, Chunk, ReadOptions};
+
+macro_rules! impl_async_test {
+    ( $( $F:ident: $f:expr ),* $(,)* ) => { $(
+        #[test]
+        fn $F() {
+            block_on(async {
+                let raw: &[u8] = include_bytes!($f);
+                let (header, data) = wav::read(&mut io::Cursor::new(raw)).unwrap();
+
+                let (async_header, async_data) = async_io::read(&mut Cursor::new(raw)).await.unwrap();
+                assert_eq!(async_header, header);
+                assert_eq!(async_data, data);
+
+                let mut out = Cursor::new(Vec::new());
+                async_io::write(header, &data, &mut out).await.unwrap();
+                assert_eq!(out.into_inner(), raw);
+
+                let mut reader = async_io::AsyncWavReader::new(Cursor::new(raw)).await.unwrap();
+                reader.seek(100).await.unwrap();
+                let (_, range) = wav::read_range(&mut io::Cursor::new(raw), 100, 50).unwrap();
+                assert_eq!(reader.read_frames(50).await.unwrap(), range);
+                assert_eq!(reader.position(), 150);
+            });
+        }
+    )* };
+}
+
+impl_async_test! {
+    async_sine_8bit_48khz: "../data/sine_8bit_48khz.wav",
+    async_sine_16bit_48khz: "../data/sine_16bit_48khz.wav",
+    async_sine_24bit_48khz: "../data/sine_24bit_48khz.wav",
+    async_sine_32bit_float_48khz: "../data/sine_32bit_float_48khz.wav",
+}
+
+#[test]
+fn async_matches_blocking_with_chunks_and_recovery() {
+    block_on(async {
+        let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+        let (header, data) = wav::read(&mut io::Cursor::new(raw)).unwrap();
+        let chunks = vec![Chunk::new(*b"note", b"odd".to_vec())];
+
+        let mut blocking = io::Cursor::new(Vec::new());
+        wav::write_with_chunks(header, &data, &chunks, &mut blocking).unwrap();
+        let mut out = Cursor::new(Vec::new());
+        async_io::write_with_chunks(header, &data, &chunks, &mut out)
+            .await
+            .unwrap();
+        assert_eq!(out.get_ref(), blocking.get_ref());
+
+        let truncated = &blocking.get_ref()[..blocking.get_ref().len() - 3];
+        let options = ReadOptions {
+            recover: true,
+            ..ReadOptions::default()
+        };
+        let expected = wav::read_with_options(&mut io::Cursor::new(truncated), options).unwrap();
+        let recovered = async_io::read_with_options(&mut Cursor::new(truncated), options)
+            .await
+            .unwrap();
+        assert_eq!(recovered, expected);
+    });
+}
+
+#[test]
+fn async_reads_wave_lists() {
+    block_on(async {
+        // One mono 16-bit sample followed by two frames of silence
+        let mut list = b"wavl".to_vec();
+        list.extend_from_slice(b"data\x02\0\0\0\x07\0slnt\x04\0\0\0\x02\0\0\0");
+        let fmt = Vec::from(wav::Header::new(wav::WAV_FORMAT_PCM, 1, 8_000, 16));
+
+        let mut raw = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        raw.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&fmt);
+        raw.extend_from_slice(b"LIST");
+        raw.extend_from_slice(&(list.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&list);
+        let riff_len = (raw.len() - 8) as u32;
+        raw[4..8].copy_from_slice(&riff_len.to_le_bytes());
+
+        let expected = wav::read(&mut io::Cursor::new(&raw)).unwrap();
+        assert_eq!(expected.1, wav::BitDepth::Sixteen(vec![7, 0, 0]));
+        assert_eq!(
+            async_io::read(&mut Cursor::new(&raw)).await.unwrap(),
+            expected
+        );
+    });
+}
@@ -0,0 +1,131 @@
+use std::io;
+
+use wav::{
+    header::{HeaderBuildError, WAV_FORMAT_EXTENSIBLE},
+    BitDepth, HeaderBuilder, SampleFormat, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM,
+};
+
+#[test]
+fn builder_picks_format_from_sample_format() {
+    let float = HeaderBuilder::new(SampleFormat::ThirtyTwoFloat)
+        .extensible(false)
+        .build()
+        .unwrap();
+    assert_eq!(float.audio_format, WAV_FORMAT_IEEE_FLOAT);
+    assert_eq!(float.bits_per_sample, 32);
+    assert_eq!(float.bytes_per_sample, 8);
+    assert_eq!(float.bytes_per_second, 384_000);
+
+    let mono = HeaderBuilder::new(SampleFormat::Eight)
+        .channel_count(1)
+        .sampling_rate(44_100)
+        .build()
+        .unwrap();
+    assert_eq!(mono.audio_format, WAV_FORMAT_PCM);
+    assert_eq!(mono.extension, None);
+    assert!(mono.validate().is_ok());
+}
+
+#[test]
+fn builder_chooses_extensible() {
+    let plain = HeaderBuilder::new(SampleFormat::TwentyFour)
+        .channel_count(6)
+        .build()
+        .unwrap();
+    assert_eq!(plain.audio_format, WAV_FORMAT_PCM);
+    assert_eq!(plain.extension, None);
+
+    let header = HeaderBuilder::new(SampleFormat::TwentyFour)
+        .valid_bits_per_sample(20)
+        .build()
+        .unwrap();
+    assert_eq!(header.audio_format, WAV_FORMAT_EXTENSIBLE);
+    assert_eq!(header.effective_format(), WAV_FORMAT_PCM);
+
+    let ext = header.extension.unwrap();
+    assert_eq!(ext.valid_bits_per_sample, 20);
+    assert_eq!(ext.channel_mask, 0x3);
+    assert!(header.validate().is_ok());
+
+    let header = HeaderBuilder::new(SampleFormat::Sixteen)
+        .channel_count(6)
+        .extensible(true)
+        .build()
+        .unwrap();
+    assert_eq!(header.extension.unwrap().channel_mask, 0x3F);
+}
+
+#[test]
+fn builder_accepts_partial_channel_masks() {
+    for mask in [0, 0x4] {
+        let header = HeaderBuilder::new(SampleFormat::Sixteen)
+            .channel_count(3)
+            .channel_mask(mask)
+            .build()
+            .unwrap();
+        assert_eq!(header.audio_format, WAV_FORMAT_EXTENSIBLE);
+        assert_eq!(header.extension.unwrap().channel_mask, mask);
+    }
+}
+
+#[test]
+fn builder_rejects_invalid_combinations() {
+    let build = |b: HeaderBuilder| b.build().unwrap_err();
+
+    assert_eq!(
+        build(HeaderBuilder::new(SampleFormat::Sixteen).channel_count(0)),
+        HeaderBuildError::ZeroChannels
+    );
+    assert_eq!(
+        build(HeaderBuilder::new(SampleFormat::Sixteen).sampling_rate(0)),
+        HeaderBuildError::ZeroSamplingRate
+    );
+    assert_eq!(
+        build(HeaderBuilder::new(SampleFormat::TwentyFour).channel_count(u16::MAX)),
+        HeaderBuildError::BlockAlignOverflow
+    );
+    assert_eq!(
+        build(HeaderBuilder::new(SampleFormat::ThirtyTwoFloat).sampling_rate(u32::MAX)),
+        HeaderBuildError::BytesPerSecondOverflow
+    );
+    assert_eq!(
+        build(HeaderBuilder::new(SampleFormat::Sixteen).valid_bits_per_sample(20)),
+        HeaderBuildError::InvalidValidBits {
+            valid: 20,
+            container: 16
+        }
+    );
+    assert_eq!(
+        build(HeaderBuilder::new(SampleFormat::Sixteen).channel_mask(0x7)),
+        HeaderBuildError::ChannelMaskMismatch {
+            channel_mask: 0x7,
+            channel_count: 2
+        }
+    );
+    assert_eq!(
+        build(
+            HeaderBuilder::new(SampleFormat::TwentyFour)
+                .valid_bits_per_sample(20)
+                .extensible(false)
+        ),
+        HeaderBuildError::ExtensibleRequired
+    );
+}
+
+#[test]
+fn extensible_round_trip() {
+    let header = HeaderBuilder::new(SampleFormat::TwentyFour)
+        .channel_count(4)
+        .valid_bits_per_sample(20)
+        .build()
+        .unwrap();
+    let data = BitDepth::TwentyFour((0..400).map(|i| i << 12).collect());
+
+    let mut out = io::Cursor::new(Vec::new());
+    wav::write(header, &data, &mut out).unwrap();
+    out.set_position(0);
+
+    let (read_header, read_data) = wav::read(&mut out).unwrap();
+    assert_eq!(read_header, header);
+    assert_eq!(read_data, data);
+}
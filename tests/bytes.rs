@@ -0,0 +1,35 @@
+use std::convert::TryFrom;
+
+use wav::{BitDepth, Header, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM};
+
+#[test]
+fn decode_matches_read() {
+    let raw: &[u8] = include_bytes!("../data/sine_24bit_48khz.wav");
+    let (header, data) = wav::read(&mut std::io::Cursor::new(raw)).unwrap();
+
+    // The test files hold only a 16-byte "fmt " chunk and the "data" chunk
+    let parsed = Header::try_from(&raw[20..36]).unwrap();
+    assert_eq!(parsed, header);
+    assert_eq!(BitDepth::decode(&parsed, &raw[44..]).unwrap(), data);
+}
+
+#[test]
+fn encode_decode_round_trip() {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 48_000, 24);
+    let data = BitDepth::TwentyFour(vec![0x7F_FF_FF_00, -0x100, 0, 0x1234_5600]);
+
+    let bytes = data.encode().unwrap();
+    assert_eq!(bytes.len(), 12);
+    assert_eq!(BitDepth::decode(&header, &bytes).unwrap(), data);
+}
+
+#[test]
+fn decode_rejects_unsupported_depth() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 1, 48_000, 64);
+
+    assert_eq!(
+        BitDepth::decode(&header, &[0; 8]),
+        Err("Unsupported IEEE Float bit depth")
+    );
+    assert_eq!(BitDepth::Empty.encode(), Err("Empty audio data given"));
+}
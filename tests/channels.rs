@@ -0,0 +1,118 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use wav::{
+    channels::{ChannelLayout, Matrix, Speaker},
+    BitDepth, Header, HeaderBuilder, SampleFormat, WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_PCM,
+};
+
+#[test]
+fn layout_from_header() {
+    let stereo = Header::new(WAV_FORMAT_PCM, 2, 48_000, 16);
+    assert_eq!(
+        ChannelLayout::from_header(&stereo),
+        Some(ChannelLayout::STEREO)
+    );
+
+    let surround = HeaderBuilder::new(SampleFormat::TwentyFour)
+        .channel_count(8)
+        .extensible(true)
+        .build()
+        .unwrap();
+    let layout = ChannelLayout::from_header(&surround).unwrap();
+    assert_eq!(layout, ChannelLayout::SURROUND_7_1);
+    assert_eq!(layout.index_of(Speaker::SideLeft), Some(6));
+    assert_eq!(
+        layout.speakers().take(4).collect::<Vec<_>>(),
+        vec![
+            Speaker::FrontLeft,
+            Speaker::FrontRight,
+            Speaker::FrontCenter,
+            Speaker::LowFrequency
+        ]
+    );
+
+    let mut unassigned = surround;
+    unassigned.extension.as_mut().unwrap().channel_mask = 0x3;
+    assert_eq!(ChannelLayout::from_header(&unassigned), None);
+}
+
+#[test]
+fn bs775_downmixes() {
+    let stereo = Matrix::downmix(ChannelLayout::SURROUND_5_1, ChannelLayout::STEREO).unwrap();
+    // FL FR FC LFE BL BR
+    let left = [1.0, 0.0, FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2, 0.0];
+    for (input, gain) in (0..).zip(left) {
+        assert_eq!(stereo.gain(0, input), gain);
+    }
+
+    let mono = Matrix::downmix(ChannelLayout::SURROUND_5_1, ChannelLayout::MONO).unwrap();
+    let expected = [FRAC_1_SQRT_2, FRAC_1_SQRT_2, 1.0, 0.0, 0.5, 0.5];
+    for (input, gain) in (0..).zip(expected) {
+        assert!((mono.gain(0, input) - gain).abs() < 1e-6);
+    }
+
+    // The sides of 7.1 fold into the backs of 5.1
+    let five = Matrix::downmix(ChannelLayout::SURROUND_7_1, ChannelLayout::SURROUND_5_1).unwrap();
+    assert_eq!(five.gain(4, 6), 1.0);
+    assert_eq!(five.gain(5, 7), 1.0);
+    assert_eq!(five.layout(), Some(ChannelLayout::SURROUND_5_1));
+}
+
+#[test]
+fn downmix_needs_somewhere_to_fold() {
+    let backs = ChannelLayout::from_speakers(&[Speaker::BackLeft, Speaker::BackRight]);
+    assert!(Matrix::downmix(ChannelLayout::STEREO, backs).is_err());
+}
+
+#[test]
+fn apply_updates_header() {
+    let header = HeaderBuilder::new(SampleFormat::Sixteen)
+        .channel_count(6)
+        .extensible(true)
+        .build()
+        .unwrap();
+    let data = BitDepth::Sixteen(vec![1_000, 2_000, 0, 0, 0, 0]);
+
+    let matrix = Matrix::downmix(ChannelLayout::SURROUND_5_1, ChannelLayout::STEREO).unwrap();
+    let (stereo, mixed) = matrix.apply(&header, &data).unwrap();
+    assert_eq!(stereo.channel_count, 2);
+    assert_eq!(stereo.bytes_per_sample, 4);
+    assert_eq!(stereo.extension.unwrap().channel_mask, 0x3);
+    assert!(stereo.validate().is_ok());
+    assert_eq!(mixed, BitDepth::Sixteen(vec![1_000, 2_000]));
+
+    // Plain headers stay plain
+    let plain = Header::new(WAV_FORMAT_PCM, 6, 48_000, 16);
+    let (stereo, _) = matrix.apply(&plain, &data).unwrap();
+    assert_eq!(stereo.audio_format, WAV_FORMAT_PCM);
+    assert_eq!(stereo.extension, None);
+}
+
+#[test]
+fn select_reorders_and_extracts() {
+    let header = Header::new(WAV_FORMAT_PCM, 3, 48_000, 8);
+    let data = BitDepth::Eight(vec![1, 2, 3, 4, 5, 6]);
+
+    let matrix = Matrix::select(3, &[2, 0, 0]).unwrap();
+    let (header, selected) = matrix.apply(&header, &data).unwrap();
+    assert_eq!(header.channel_count, 3);
+    assert_eq!(header.effective_format(), WAV_FORMAT_PCM);
+    assert_eq!(selected, BitDepth::Eight(vec![3, 1, 1, 6, 4, 4]));
+
+    assert!(Matrix::select(3, &[3]).is_err());
+    assert!(Matrix::new(2, 1, vec![0.5]).is_err());
+}
+
+#[test]
+fn select_clears_channel_mask() {
+    let header = HeaderBuilder::new(SampleFormat::TwentyFour)
+        .channel_count(6)
+        .extensible(true)
+        .build()
+        .unwrap();
+
+    let matrix = Matrix::select(6, &[2]).unwrap();
+    let header = matrix.header(&header).unwrap();
+    assert_eq!(header.audio_format, WAV_FORMAT_EXTENSIBLE);
+    assert_eq!(header.extension.unwrap().channel_mask, 0);
+}
@@ -0,0 +1,177 @@
+use std::{f64::consts::PI, io::Cursor};
+
+use wav::{
+    loudness::{self, Analyzer},
+    metadata::Bext,
+    BitDepth, Header, WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_IEEE_FLOAT,
+};
+
+/// Returns `secs` seconds of a sine wave of `freq` Hz peaking at `dbfs`, identical on each of the
+/// `channels` channels.
+fn sine(freq: f64, dbfs: f64, rate: u32, channels: usize, secs: f64) -> Vec<f32> {
+    let amplitude = 10f64.powf(dbfs / 20.0);
+    let len = (secs * f64::from(rate)) as usize;
+    (0..len)
+        .flat_map(|i| {
+            let s = amplitude * (2.0 * PI * freq * i as f64 / f64::from(rate)).sin();
+            std::iter::repeat(s as f32).take(channels)
+        })
+        .collect()
+}
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{} isn't within {} of {}",
+        actual,
+        tolerance,
+        expected
+    );
+}
+
+#[test]
+fn stereo_sine_measures_its_level() {
+    // EBU Tech 3341, test case 1
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32);
+    let data = BitDepth::ThirtyTwoFloat(sine(1_000.0, -23.0, 48_000, 2, 10.0));
+
+    let loudness = loudness::analyze(&header, &data).unwrap();
+    assert_close(loudness.integrated, -23.0, 0.1);
+    assert_close(loudness.max_momentary, -23.0, 0.1);
+    assert_close(loudness.max_short_term, -23.0, 0.1);
+    assert_close(loudness.loudness_range, 0.0, 0.1);
+    assert_close(loudness.true_peak, -23.0, 0.2);
+
+    assert_eq!(loudness.channels.len(), 2);
+    for levels in &loudness.channels {
+        assert_close(levels.sample_peak, -23.0, 0.01);
+        assert_close(levels.rms, -23.0 - 3.01, 0.01);
+    }
+}
+
+#[test]
+fn quiet_blocks_are_gated() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32);
+    let mut samples = sine(1_000.0, -23.0, 48_000, 2, 5.0);
+    samples.extend(sine(1_000.0, -80.0, 48_000, 2, 5.0));
+
+    // Without gating, the silent half would lower the loudness by 3 LU
+    let loudness = loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(samples)).unwrap();
+    assert_close(loudness.integrated, -23.0, 0.2);
+}
+
+#[test]
+fn loudness_range_spans_sections() {
+    // EBU Tech 3342, test case 1
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 16_000, 32);
+    let mut samples = sine(1_000.0, -20.0, 16_000, 2, 20.0);
+    samples.extend(sine(1_000.0, -30.0, 16_000, 2, 20.0));
+
+    let loudness = loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(samples)).unwrap();
+    assert_close(loudness.loudness_range, 10.0, 1.0);
+}
+
+#[test]
+fn true_peak_finds_peaks_between_samples() {
+    // A quarter of the sampling rate, sampled 45 degrees off its peaks
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 1, 48_000, 32);
+    let samples = (0..48_000)
+        .map(|i| (0.5 * (PI / 2.0 * f64::from(i) + PI / 4.0).sin()) as f32)
+        .collect();
+
+    let loudness = loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(samples)).unwrap();
+    assert_close(loudness.channels[0].sample_peak, -9.03, 0.01);
+    assert_close(loudness.true_peak, -6.02, 0.2);
+}
+
+#[test]
+fn lfe_channel_is_not_counted() {
+    let header = Header::builder(wav::SampleFormat::ThirtyTwoFloat)
+        .channel_count(6)
+        .sampling_rate(48_000)
+        .extensible(true)
+        .build()
+        .unwrap();
+    assert_eq!(header.audio_format, WAV_FORMAT_EXTENSIBLE);
+
+    let samples = sine(100.0, -10.0, 48_000, 1, 2.0)
+        .into_iter()
+        .flat_map(|s| [0.0, 0.0, 0.0, s, 0.0, 0.0])
+        .collect();
+    let loudness = loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(samples)).unwrap();
+    assert_eq!(loudness.integrated, f64::NEG_INFINITY);
+    assert_close(loudness.channels[3].sample_peak, -10.0, 0.01);
+}
+
+#[test]
+fn silence_is_negative_infinity() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 1, 48_000, 32);
+    let loudness =
+        loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(vec![0.0; 48_000])).unwrap();
+
+    assert_eq!(loudness.integrated, f64::NEG_INFINITY);
+    assert_eq!(loudness.max_momentary, f64::NEG_INFINITY);
+    assert_eq!(loudness.true_peak, f64::NEG_INFINITY);
+    assert_eq!(loudness.channels[0].rms, f64::NEG_INFINITY);
+    assert_eq!(loudness.loudness_range, 0.0);
+}
+
+#[test]
+fn blocks_match_whole() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 44_100, 32);
+    let samples = sine(1_000.0, -12.0, 44_100, 2, 4.0);
+
+    let whole = loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(samples.clone())).unwrap();
+
+    let mut analyzer = Analyzer::new(&header).unwrap();
+    for block in samples.chunks(2 * 1_234) {
+        analyzer.process(&BitDepth::ThirtyTwoFloat(block.to_vec()));
+    }
+    assert_close(analyzer.momentary(), -12.0, 0.1);
+    assert_close(analyzer.short_term(), -12.0, 0.1);
+    assert_eq!(analyzer.finish(), whole);
+}
+
+#[test]
+fn reader_matches_decoded_data() {
+    let raw: &[u8] = include_bytes!("../data/sine_24bit_48khz.wav");
+    let (header, data) = wav::read(&mut Cursor::new(raw)).unwrap();
+
+    let expected = loudness::analyze(&header, &data).unwrap();
+    assert_eq!(
+        loudness::analyze_reader(Cursor::new(raw)).unwrap(),
+        expected
+    );
+    assert!(expected.integrated.is_finite());
+}
+
+#[test]
+fn analyzer_rejects_empty_header() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 0, 48_000, 32);
+    assert!(Analyzer::new(&header).is_err());
+}
+
+#[test]
+fn loudness_is_written_to_bext() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32);
+    let data = BitDepth::ThirtyTwoFloat(sine(1_000.0, -23.0, 48_000, 2, 4.0));
+    let loudness = loudness::analyze(&header, &data).unwrap();
+
+    let mut bext = Bext {
+        version: 1,
+        ..Bext::default()
+    };
+    loudness.write_to(&mut bext);
+    assert_eq!(bext.version, 2);
+    assert!((-2_310..=-2_290).contains(&bext.loudness_value));
+    assert!((-2_320..=-2_280).contains(&bext.max_true_peak_level));
+    assert!((0..=10).contains(&bext.loudness_range));
+    assert_eq!(
+        bext.max_short_term_loudness,
+        (loudness.max_short_term * 100.0).round() as i16
+    );
+
+    let silent = loudness::analyze(&header, &BitDepth::ThirtyTwoFloat(Vec::new())).unwrap();
+    silent.write_to(&mut bext);
+    assert_eq!(bext.loudness_value, Bext::UNSET_LOUDNESS);
+}
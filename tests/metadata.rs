@@ -0,0 +1,1046 @@
+use std::{convert::TryFrom, io::Cursor};
+
+use wav::{
+    metadata::{
+        Acid, AudioContent, AudioId, AudioObject, AudioProgramme, Axml, Bext, Cart, Chna, Comment,
+        Cue, CuePoint, Id3, Id3Frame, Ixml, Levl, Peak, PeakFormat, Picture, Plst, PostTimer, Rate,
+        Segment, Speed, Track,
+    },
+    BitDepth, Chunk, Header, WAV_FORMAT_PCM,
+};
+
+fn bext() -> Bext {
+    Bext {
+        description: "Interview, take 3".to_string(),
+        originator: "Field recorder".to_string(),
+        originator_reference: "ABC123".to_string(),
+        origination_date: "2024-05-01".to_string(),
+        origination_time: "13:45:00".to_string(),
+        time_reference: 48_000 * 3_600 * 13,
+        umid: [7; 64],
+        loudness_value: -2_300,
+        loudness_range: 450,
+        max_true_peak_level: -100,
+        coding_history: "A=PCM,F=48000,W=24,M=stereo,T=recorder\r\n".to_string(),
+        ..Bext::default()
+    }
+}
+
+#[test]
+fn bext_round_trips_through_file() {
+    let chunk = Chunk::try_from(&bext()).unwrap();
+    assert_eq!(chunk.id, *b"bext");
+    assert_eq!(chunk.data.len(), 602 + bext().coding_history.len());
+
+    let header = Header::new(WAV_FORMAT_PCM, 1, 48_000, 16);
+    let mut file = Cursor::new(Vec::new());
+    wav::write_with_chunks(header, &BitDepth::Sixteen(vec![0; 10]), &[chunk], &mut file).unwrap();
+    file.set_position(0);
+
+    let chunks = wav::read_chunks(&mut file).unwrap();
+    let read = chunks.iter().find(|c| c.id == Bext::ID).unwrap();
+    assert_eq!(Bext::try_from(read), Ok(bext()));
+}
+
+#[test]
+fn bext_fields_are_at_their_offsets() {
+    let data = Chunk::try_from(&bext()).unwrap().data;
+    assert_eq!(&data[..17], b"Interview, take 3");
+    assert_eq!(data[17], 0);
+    assert_eq!(&data[320..330], b"2024-05-01");
+    assert_eq!(data[338..346], (48_000u64 * 3_600 * 13).to_le_bytes());
+    assert_eq!(data[346..348], 2u16.to_le_bytes());
+    assert_eq!(data[412..414], (-2_300i16).to_le_bytes());
+    assert_eq!(data[418..420], Bext::UNSET_LOUDNESS.to_le_bytes());
+    assert!(data[422..602].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn bext_rejects_invalid_chunks() {
+    assert!(Bext::try_from(&Chunk::new(*b"LIST", vec![0; 602])).is_err());
+    assert!(Bext::try_from(&Chunk::new(Bext::ID, vec![0; 601])).is_err());
+    assert_eq!(
+        Bext::try_from(&Chunk::new(Bext::ID, vec![0; 602])).map(|b| b.description),
+        Ok(String::new())
+    );
+
+    let too_long = Bext {
+        origination_date: "1 May 2024".to_string() + "!",
+        ..Bext::default()
+    };
+    assert!(Chunk::try_from(&too_long).is_err());
+}
+
+#[test]
+fn levl_is_generated_from_audio() {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 48_000, 16);
+    let data = BitDepth::Sixteen(vec![
+        100, -200, -16_384, 0, 32_767, 5, // first block
+        -32_768, 7, // partial last block
+    ]);
+
+    let levl = Levl::generate(&header, &data, 3).unwrap();
+    assert_eq!(levl.format, PeakFormat::Sixteen);
+    assert_eq!(levl.points_per_value, 2);
+    assert_eq!(levl.block_frames, 3);
+    assert_eq!(levl.channel_count, 2);
+    assert_eq!(levl.peak_of_peaks, Some(3));
+    assert_eq!(
+        levl.peaks,
+        [
+            Peak {
+                positive: 32_767,
+                negative: 16_384
+            },
+            Peak {
+                positive: 5,
+                negative: 200
+            },
+            Peak {
+                positive: 0,
+                negative: 32_768
+            },
+            Peak {
+                positive: 7,
+                negative: 0
+            },
+        ]
+    );
+
+    let eight = Header::new(WAV_FORMAT_PCM, 1, 8_000, 8);
+    let levl = Levl::generate(&eight, &BitDepth::Eight(vec![128, 255, 0]), 2).unwrap();
+    assert_eq!(levl.format, PeakFormat::Eight);
+    assert_eq!(levl.peaks[0].positive, 127);
+    assert_eq!(levl.peaks[1].negative, 128);
+}
+
+#[test]
+fn levl_round_trips() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let (header, data) = wav::read(&mut Cursor::new(raw)).unwrap();
+    let mut levl = Levl::generate(&header, &data, 256).unwrap();
+    levl.timestamp = "2024:05:01:13:45:00:000".to_string();
+
+    let chunk = Chunk::try_from(&levl).unwrap();
+    assert_eq!(chunk.id, *b"levl");
+    assert_eq!(chunk.data[28..32], 128u32.to_le_bytes());
+    assert_eq!(chunk.data.len(), 120 + levl.peaks.len() * 4);
+    assert_eq!(Levl::try_from(&chunk), Ok(levl.clone()));
+
+    let positive_only = Levl {
+        points_per_value: 1,
+        format: PeakFormat::Eight,
+        peaks: levl
+            .peaks
+            .iter()
+            .map(|p| Peak {
+                positive: p.positive >> 8,
+                negative: 0,
+            })
+            .collect(),
+        ..levl
+    };
+    let chunk = Chunk::try_from(&positive_only).unwrap();
+    assert_eq!(chunk.data.len(), 120 + positive_only.peaks.len());
+    assert_eq!(Levl::try_from(&chunk), Ok(positive_only));
+}
+
+#[test]
+fn levl_rejects_invalid_chunks() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 16);
+    let levl = Levl::generate(&header, &BitDepth::Sixteen(vec![1_000; 10]), 4).unwrap();
+    let valid = Chunk::try_from(&levl).unwrap().data;
+
+    let corrupt = |offset: usize, value: u32| {
+        let mut data = valid.clone();
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        Levl::try_from(&Chunk::new(Levl::ID, data))
+    };
+    assert!(corrupt(4, 3).is_err());
+    assert!(corrupt(8, 0).is_err());
+    assert!(corrupt(16, 0).is_err());
+    assert!(corrupt(20, 4).is_err());
+    assert!(corrupt(28, 64).is_err());
+    assert!(Levl::try_from(&Chunk::new(Levl::ID, valid[..100].to_vec())).is_err());
+    assert!(Levl::try_from(&Chunk::new(Bext::ID, valid)).is_err());
+
+    let too_high = Levl {
+        format: PeakFormat::Eight,
+        ..levl.clone()
+    };
+    assert!(Chunk::try_from(&too_high).is_err());
+    let partial = Levl {
+        channel_count: 2,
+        peaks: vec![Peak::default(); 3],
+        ..levl
+    };
+    assert!(Chunk::try_from(&partial).is_err());
+}
+
+fn cart() -> Cart {
+    Cart {
+        title: "Station ID".to_string(),
+        artist: "Imaging".to_string(),
+        cut_id: "ID0007".to_string(),
+        category: "IMAG".to_string(),
+        out_cue: "...the best music".to_string(),
+        start_date: "2024/01/01".to_string(),
+        start_time: "00:00:00".to_string(),
+        end_date: "2099/12/31".to_string(),
+        end_time: "23:59:59".to_string(),
+        producer_app_id: "Playout".to_string(),
+        level_reference: 32_768,
+        post_timers: vec![
+            PostTimer {
+                usage: *b"INT1",
+                value: 48_000,
+            },
+            PostTimer {
+                usage: *b"SEG1",
+                value: 240_000,
+            },
+        ],
+        url: "https://example.com/cuts/ID0007".to_string(),
+        tag_text: "Produced in studio B\r\n".to_string(),
+        ..Cart::default()
+    }
+}
+
+#[test]
+fn cart_round_trips() {
+    let chunk = Chunk::try_from(&cart()).unwrap();
+    assert_eq!(chunk.id, *b"cart");
+    assert_eq!(chunk.data.len(), 2048 + cart().tag_text.len());
+    assert_eq!(Cart::try_from(&chunk), Ok(cart()));
+}
+
+#[test]
+fn cart_fields_are_fixed_width() {
+    let data = Chunk::try_from(&cart()).unwrap().data;
+    assert_eq!(&data[..4], b"0101");
+    assert_eq!(&data[4..14], b"Station ID");
+    assert!(data[14..68].iter().all(|&b| b == 0));
+    assert_eq!(&data[132..138], b"ID0007");
+    assert_eq!(&data[452..462], b"2024/01/01");
+    assert_eq!(&data[480..488], b"23:59:59");
+    assert_eq!(data[680..684], 32_768i32.to_le_bytes());
+    assert_eq!(&data[684..688], b"INT1");
+    assert_eq!(data[688..692], 48_000u32.to_le_bytes());
+    assert_eq!(&data[692..696], b"SEG1");
+    assert!(data[700..1024].iter().all(|&b| b == 0));
+    assert_eq!(&data[1024..1055], b"https://example.com/cuts/ID0007");
+    assert_eq!(&data[2048..], b"Produced in studio B\r\n");
+
+    // Fields filling their whole width have no terminating NUL
+    let full = Cart {
+        title: "T".repeat(64),
+        ..Cart::default()
+    };
+    let chunk = Chunk::try_from(&full).unwrap();
+    assert_eq!(Cart::try_from(&chunk).map(|c| c.title), Ok(full.title));
+}
+
+#[test]
+fn cart_rejects_invalid_fields() {
+    let invalid = [
+        Cart {
+            title: "T".repeat(65),
+            ..Cart::default()
+        },
+        Cart {
+            artist: "Beyonc\u{e9}".to_string(),
+            ..Cart::default()
+        },
+        Cart {
+            post_timers: vec![PostTimer::default(); 9],
+            ..Cart::default()
+        },
+    ];
+    for cart in &invalid {
+        assert!(Chunk::try_from(cart).is_err());
+    }
+
+    assert!(Cart::try_from(&Chunk::new(Cart::ID, vec![0; 2047])).is_err());
+    assert!(Cart::try_from(&Chunk::new(Bext::ID, vec![0; 2048])).is_err());
+}
+
+/// Returns an ID3v2 frame of a tag of the given version.
+fn id3_frame(version: u8, id: &[u8; 4], flags: [u8; 2], data: &[u8]) -> Vec<u8> {
+    let len = data.len() as u32;
+    let mut frame = id.to_vec();
+    if version == 4 {
+        frame.extend_from_slice(&[0, 0, (len >> 7) as u8, (len & 0x7F) as u8]);
+    } else {
+        frame.extend_from_slice(&len.to_be_bytes());
+    }
+    frame.extend_from_slice(&flags);
+    frame.extend_from_slice(data);
+    frame
+}
+
+/// Returns an ID3v2 tag holding the given frames, followed by `padding` zero bytes.
+fn id3_tag(version: u8, flags: u8, body: &[u8], padding: usize) -> Vec<u8> {
+    let len = body.len() + padding;
+    let mut tag = b"ID3".to_vec();
+    tag.extend_from_slice(&[
+        version,
+        0,
+        flags,
+        0,
+        0,
+        (len >> 7) as u8,
+        (len & 0x7F) as u8,
+    ]);
+    tag.extend_from_slice(body);
+    tag.resize(tag.len() + padding, 0);
+    tag
+}
+
+fn id3() -> Id3 {
+    let mut tag = Id3::default();
+    tag.set_text(*b"TIT2", "Sigur Rós");
+    tag.set_text(*b"TPE1", "東京事変");
+    tag.set_text(*b"TALB", "Album");
+    tag.set_text(*b"TRCK", "3/12");
+    tag.set_text(*b"TYER", "1999");
+    tag.frames.push(Id3Frame::Comment(Comment {
+        language: *b"eng",
+        description: String::new(),
+        text: "Remastered".to_string(),
+    }));
+    tag.frames.push(Id3Frame::Picture(Picture {
+        mime_type: "image/png".to_string(),
+        picture_type: 3,
+        description: "Cover".to_string(),
+        data: vec![0x89, b'P', b'N', b'G', 0xFF, 0x00, 0xFF],
+    }));
+    tag.frames.push(Id3Frame::Other {
+        id: *b"PRIV",
+        flags: [0; 2],
+        data: b"owner\0data".to_vec(),
+    });
+    tag
+}
+
+#[test]
+fn id3_round_trips() {
+    let tag = id3();
+    assert_eq!(tag.title(), Some("Sigur Rós"));
+    assert_eq!(tag.artist(), Some("東京事変"));
+    assert_eq!(tag.album(), Some("Album"));
+    assert_eq!(tag.track(), Some(3));
+    assert_eq!(tag.year(), Some(1999));
+
+    let chunk = Chunk::try_from(&tag).unwrap();
+    assert_eq!(chunk.id, *b"id3 ");
+    assert_eq!(&chunk.data[..6], b"ID3\x03\x00\x00");
+    // Latin-1 where possible, UTF-16 otherwise
+    assert_eq!(&chunk.data[20..30], b"\x00Sigur R\xf3s");
+    assert_eq!(&chunk.data[40..43], b"\x01\xff\xfe");
+    assert_eq!(Id3::try_from(&chunk), Ok(tag.clone()));
+
+    let mut v4 = Id3 { version: 4, ..tag };
+    v4.set_text(*b"TPE1", "Artist A\0Artist B");
+    let chunk = Chunk::try_from(&v4).unwrap();
+    assert_eq!(&chunk.data[20..22], b"\x03S");
+    let parsed = Id3::try_from(&chunk).unwrap();
+    assert_eq!(parsed.artist(), Some("Artist A\0Artist B"));
+    assert_eq!(parsed, v4);
+}
+
+#[test]
+fn id3_survives_write_and_read() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 44_100, 16);
+    let mut file = Cursor::new(Vec::new());
+    let chunks = [Chunk::try_from(&id3()).unwrap()];
+    wav::write_with_chunks(header, &BitDepth::Sixteen(vec![0; 3]), &chunks, &mut file).unwrap();
+    file.set_position(0);
+
+    let chunks = wav::read_chunks(&mut file).unwrap();
+    let tag = Id3::try_from(&chunks[0]).unwrap();
+    assert_eq!(tag, id3());
+    assert_eq!(tag.pictures().next().unwrap().data.len(), 7);
+    assert_eq!(tag.comments().next().unwrap().text, "Remastered");
+
+    let upper = Chunk::new(*b"ID3 ", chunks[0].data.clone());
+    assert_eq!(Id3::try_from(&upper), Ok(tag));
+}
+
+#[test]
+fn id3_decodes_every_encoding() {
+    let mut body = Vec::new();
+    body.extend(id3_frame(
+        4,
+        b"TIT2",
+        [0; 2],
+        b"\x01\xfe\xff\x00H\x00i\x00\x00",
+    ));
+    body.extend(id3_frame(4, b"TPE1", [0; 2], b"\x02\x00A\x00\x00\x00B"));
+    body.extend(id3_frame(4, b"TALB", [0; 2], b"\x03Caf\xc3\xa9"));
+    body.extend(id3_frame(4, b"TXXX", [0; 2], b"\x00KEY\x00value"));
+    body.extend(id3_frame(
+        4,
+        b"COMM",
+        [0; 2],
+        b"\x01deu\xff\xfeD\x00\x00\x00\xff\xfeT\x00",
+    ));
+    let tag = Id3::try_from(id3_tag(4, 0, &body, 64).as_slice()).unwrap();
+
+    assert_eq!(tag.version, 4);
+    assert_eq!(tag.title(), Some("Hi"));
+    assert_eq!(tag.artist(), Some("A\0B"));
+    assert_eq!(tag.album(), Some("Café"));
+    assert_eq!(
+        tag.frames[3],
+        Id3Frame::Other {
+            id: *b"TXXX",
+            flags: [0; 2],
+            data: b"\x00KEY\x00value".to_vec()
+        }
+    );
+    assert_eq!(
+        tag.comments().next(),
+        Some(&Comment {
+            language: *b"deu",
+            description: "D".to_string(),
+            text: "T".to_string(),
+        })
+    );
+    assert_eq!(tag.frames.len(), 5);
+}
+
+#[test]
+fn id3_handles_unsynchronisation_and_extended_headers() {
+    // An extended header of 6 bytes, then a picture with a 0xFF byte followed by a zero byte, which
+    // unsynchronisation follows with another zero byte not counted in the frame size
+    let mut body = vec![0, 0, 0, 6, 0, 0, 0, 0, 0, 0];
+    let mut frame = id3_frame(
+        3,
+        b"APIC",
+        [0; 2],
+        b"\x00image/jpeg\x00\x03\x00\xff\xd8\xff\x00",
+    );
+    frame.insert(27, 0);
+    body.extend(frame);
+    let tag = Id3::try_from(id3_tag(3, 0xC0, &body, 0).as_slice()).unwrap();
+    assert_eq!(
+        tag.pictures().next().unwrap().data,
+        [0xFF, 0xD8, 0xFF, 0x00]
+    );
+
+    // Compressed frames are kept as stored
+    let body = id3_frame(3, b"TIT2", [0, 0x80], b"\x00\x00\x00\x05compressed");
+    let tag = Id3::try_from(id3_tag(3, 0, &body, 0).as_slice()).unwrap();
+    assert_eq!(tag.title(), None);
+    assert!(matches!(
+        tag.frames[0],
+        Id3Frame::Other {
+            flags: [0, 0x80],
+            ..
+        }
+    ));
+    let chunk = Chunk::try_from(&tag).unwrap();
+    assert_eq!(&chunk.data[10..], body.as_slice());
+}
+
+#[test]
+fn id3_keeps_frame_status_flags() {
+    // A title to discard when the tag is altered, and a read-only comment
+    let mut body = id3_frame(3, b"TIT2", [0x40, 0], b"\x00Title");
+    body.extend(id3_frame(3, b"COMM", [0x20, 0], b"\x00engDesc\x00Text"));
+    let tag = Id3::try_from(id3_tag(3, 0, &body, 0).as_slice()).unwrap();
+    assert!(matches!(
+        tag.frames[0],
+        Id3Frame::Other {
+            id: [b'T', b'I', b'T', b'2'],
+            flags: [0x40, 0],
+            ..
+        }
+    ));
+    assert!(matches!(
+        tag.frames[1],
+        Id3Frame::Other {
+            flags: [0x20, 0],
+            ..
+        }
+    ));
+
+    let chunk = Chunk::try_from(&tag).unwrap();
+    assert_eq!(&chunk.data[10..], body.as_slice());
+}
+
+#[test]
+fn id3_rejects_invalid_tags() {
+    let frame = id3_frame(3, b"TIT2", [0; 2], b"\x00Title");
+    assert!(Id3::try_from(&b"ID3"[..]).is_err());
+    assert!(Id3::try_from(&id3_tag(2, 0, &frame, 0)[..]).is_err());
+    assert!(Id3::try_from(&id3_tag(3, 0, &frame, 0)[..20]).is_err());
+
+    let mut truncated = id3_tag(3, 0, &frame, 0);
+    truncated[17] = 50;
+    assert!(Id3::try_from(truncated.as_slice()).is_err());
+
+    let unknown_encoding = id3_frame(3, b"TIT2", [0; 2], b"\x07Title");
+    assert!(Id3::try_from(id3_tag(3, 0, &unknown_encoding, 0).as_slice()).is_err());
+
+    let chunk = Chunk::new(*b"LIST", id3_tag(3, 0, &frame, 0));
+    assert!(Id3::try_from(&chunk).is_err());
+    assert!(Chunk::try_from(&Id3 {
+        version: 2,
+        ..Id3::default()
+    })
+    .is_err());
+}
+
+#[test]
+fn acid_round_trips() {
+    let acid = Acid {
+        one_shot: false,
+        root_note: Some(57),
+        stretch: true,
+        disk_based: false,
+        beats: 16,
+        meter_numerator: 3,
+        meter_denominator: 4,
+        tempo: 93.5,
+    };
+
+    let chunk = Chunk::from(&acid);
+    assert_eq!(chunk.id, *b"acid");
+    assert_eq!(chunk.data.len(), 24);
+    assert_eq!(chunk.data[..4], 0x06u32.to_le_bytes());
+    assert_eq!(chunk.data[4..6], 57u16.to_le_bytes());
+    assert_eq!(chunk.data[12..16], 16u32.to_le_bytes());
+    assert_eq!(chunk.data[16..20], [4, 0, 3, 0]);
+    assert_eq!(chunk.data[20..24], 93.5f32.to_le_bytes());
+    assert_eq!(Acid::try_from(&chunk), Ok(acid));
+
+    let one_shot = Acid {
+        one_shot: true,
+        root_note: None,
+        stretch: false,
+        disk_based: true,
+        ..acid
+    };
+    let chunk = Chunk::from(&one_shot);
+    assert_eq!(chunk.data[..4], 0x09u32.to_le_bytes());
+    assert_eq!(Acid::try_from(&chunk), Ok(one_shot));
+}
+
+#[test]
+fn acid_reads_files_written_by_acid() {
+    // Flags with an unknown bit, and the usual values of the unknown fields
+    let data = [
+        0x12, 0, 0, 0, 0x3C, 0, 0, 0x80, 0, 0, 0, 0, 4, 0, 0, 0, 4, 0, 4, 0, 0, 0, 0xF0, 0x42,
+    ];
+    let acid = Acid::try_from(&Chunk::new(Acid::ID, data.to_vec())).unwrap();
+    assert_eq!(
+        acid,
+        Acid {
+            one_shot: false,
+            root_note: Some(60),
+            stretch: false,
+            disk_based: false,
+            beats: 4,
+            meter_numerator: 4,
+            meter_denominator: 4,
+            tempo: 120.0,
+        }
+    );
+
+    assert!(Acid::try_from(&Chunk::new(Acid::ID, data[..23].to_vec())).is_err());
+    assert!(Acid::try_from(&Chunk::new(Bext::ID, data.to_vec())).is_err());
+}
+
+const IXML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE BWFXML>
+<!-- Written by a location recorder -->
+<BWFXML>
+	<IXML_VERSION>2.10</IXML_VERSION>
+	<PROJECT>Night &amp; Day</PROJECT>
+	<SCENE>12A</SCENE>
+	<TAKE>3</TAKE>
+	<TAPE>240501</TAPE>
+	<CIRCLED>TRUE</CIRCLED>
+	<NOTE><![CDATA[Plane <overhead>]]></NOTE>
+	<SPEED>
+		<NOTE>Pulled down</NOTE>
+		<MASTER_SPEED>24000/1001</MASTER_SPEED>
+		<CURRENT_SPEED>24000/1001</CURRENT_SPEED>
+		<TIMECODE_RATE>24000/1001</TIMECODE_RATE>
+		<TIMECODE_FLAG>NDF</TIMECODE_FLAG>
+		<FILE_SAMPLE_RATE>48000</FILE_SAMPLE_RATE>
+		<AUDIO_BIT_DEPTH>24</AUDIO_BIT_DEPTH>
+		<TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_HI>1</TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_HI>
+		<TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_LO>16</TIMESTAMP_SAMPLES_SINCE_MIDNIGHT_LO>
+		<TIMESTAMP_SAMPLE_RATE>48000</TIMESTAMP_SAMPLE_RATE>
+	</SPEED>
+	<TRACK_LIST>
+		<TRACK_COUNT>2</TRACK_COUNT>
+		<TRACK>
+			<CHANNEL_INDEX>1</CHANNEL_INDEX>
+			<INTERLEAVE_INDEX>1</INTERLEAVE_INDEX>
+			<NAME>Boom</NAME>
+		</TRACK>
+		<TRACK>
+			<CHANNEL_INDEX>4</CHANNEL_INDEX>
+			<INTERLEAVE_INDEX>2</INTERLEAVE_INDEX>
+			<NAME>Lav &#x2F; Anna</NAME>
+			<FUNCTION>LAV</FUNCTION>
+		</TRACK>
+	</TRACK_LIST>
+	<USER vendor='recorder'>FAV=1</USER>
+</BWFXML>
+"#;
+
+#[test]
+fn ixml_reads_recorder_metadata() {
+    let mut data = IXML.as_bytes().to_vec();
+    data.extend_from_slice(&[0; 3]);
+    let ixml = Ixml::try_from(&Chunk::new(Ixml::ID, data)).unwrap();
+
+    assert_eq!(ixml.project(), Some("Night & Day"));
+    assert_eq!(ixml.scene(), Some("12A"));
+    assert_eq!(ixml.take(), Some("3"));
+    assert_eq!(ixml.tape(), Some("240501"));
+    assert_eq!(ixml.circled(), Some(true));
+    assert_eq!(ixml.note(), Some("Plane <overhead>"));
+    assert_eq!(ixml.text("SPEED/NOTE"), Some("Pulled down"));
+    assert_eq!(ixml.text("USER"), Some("FAV=1"));
+    assert_eq!(ixml.text("HISTORY"), None);
+
+    let film = Rate::new(24_000, 1_001);
+    assert!((film.as_f64() - 23.976).abs() < 1e-3);
+    let film = Some(film);
+    assert_eq!(
+        ixml.speed(),
+        Speed {
+            master_speed: film,
+            current_speed: film,
+            timecode_rate: film,
+            drop_frame: Some(false),
+            file_sample_rate: Some(48_000),
+            audio_bit_depth: Some(24),
+            digitizer_sample_rate: None,
+            timestamp: Some((1 << 32) + 16),
+            timestamp_sample_rate: Some(48_000),
+        }
+    );
+
+    assert_eq!(
+        ixml.tracks(),
+        vec![
+            Track {
+                channel_index: 1,
+                interleave_index: 1,
+                name: "Boom".to_string(),
+                function: String::new(),
+            },
+            Track {
+                channel_index: 4,
+                interleave_index: 2,
+                name: "Lav / Anna".to_string(),
+                function: "LAV".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn ixml_keeps_unknown_elements_when_written() {
+    let ixml = Ixml::from_xml(IXML).unwrap();
+    let chunk = Chunk::from(&ixml);
+    assert_eq!(chunk.id, *b"iXML");
+
+    let text = std::str::from_utf8(&chunk.data).unwrap();
+    assert!(text.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<BWFXML>\n"));
+    assert!(text.contains("\t<PROJECT>Night &amp; Day</PROJECT>\n"));
+    assert!(text.contains("\t<USER vendor=\"recorder\">FAV=1</USER>\n"));
+    assert_eq!(Ixml::try_from(&chunk), Ok(ixml));
+}
+
+#[test]
+fn ixml_authoring() {
+    let mut ixml = Ixml::default();
+    assert_eq!(ixml.text("IXML_VERSION"), Some("2.10"));
+    ixml.set_text("PROJECT", "Pilot");
+    ixml.set_text("SCENE", "1");
+    ixml.set_text("TAKE", "2");
+    ixml.set_text("CIRCLED", "FALSE");
+    ixml.set_text("HISTORY/ORIGINAL_FILENAME", "T002.WAV");
+
+    let speed = Speed {
+        timecode_rate: Some(Rate::new(30_000, 1_001)),
+        drop_frame: Some(true),
+        file_sample_rate: Some(48_000),
+        timestamp: Some(48_000 * 3_600 * 10),
+        timestamp_sample_rate: Some(48_000),
+        ..Speed::default()
+    };
+    ixml.set_speed(&speed);
+    let tracks = vec![
+        Track {
+            channel_index: 1,
+            interleave_index: 1,
+            name: "Mix L".to_string(),
+            function: "LEFT".to_string(),
+        },
+        Track {
+            channel_index: 2,
+            interleave_index: 2,
+            name: "Mix R".to_string(),
+            function: "RIGHT".to_string(),
+        },
+    ];
+    ixml.set_tracks(&tracks);
+
+    let read = Ixml::try_from(&Chunk::from(&ixml)).unwrap();
+    assert_eq!(read, ixml);
+    assert_eq!(read.project(), Some("Pilot"));
+    assert_eq!(read.circled(), Some(false));
+    assert_eq!(read.text("HISTORY/ORIGINAL_FILENAME"), Some("T002.WAV"));
+    assert_eq!(read.speed(), speed);
+    assert_eq!(read.tracks(), tracks);
+    assert_eq!(read.text("TRACK_LIST/TRACK_COUNT"), Some("2"));
+
+    // Replacing the speed keeps the other children of the element
+    let mut ixml = Ixml::from_xml(IXML).unwrap();
+    ixml.set_speed(&speed);
+    assert_eq!(ixml.speed(), speed);
+    assert_eq!(ixml.text("SPEED/NOTE"), Some("Pulled down"));
+    assert_eq!(ixml.text("SPEED/MASTER_SPEED"), None);
+
+    ixml.set_tracks(&tracks[..1]);
+    assert_eq!(ixml.tracks(), tracks[..1]);
+    ixml.remove("SPEED/NOTE");
+    ixml.remove("USER");
+    ixml.remove("MISSING/ELEMENT");
+    assert_eq!(ixml.text("SPEED/NOTE"), None);
+    assert_eq!(ixml.text("USER"), None);
+    assert_eq!(ixml.text("MISSING"), None);
+}
+
+#[test]
+fn ixml_rejects_invalid_documents() {
+    for xml in [
+        "",
+        "<BWFXML><SCENE>1</TAKE></BWFXML>",
+        "<BWFXML><SCENE>1</SCENE>",
+        "<BWFXML a=1/>",
+        "<BWFXML>&unknown;</BWFXML>",
+        "<BWFXML/><BWFXML/>",
+        "<ADM/>",
+    ] {
+        assert!(Ixml::from_xml(xml).is_err(), "{}", xml);
+    }
+    assert!(Ixml::try_from(&Chunk::new(Ixml::ID, vec![0xFF, b'<'])).is_err());
+    assert!(Ixml::try_from(&Chunk::new(Bext::ID, IXML.as_bytes().to_vec())).is_err());
+}
+
+#[test]
+fn ixml_limits_nesting_depth() {
+    let nested = |depth: usize| {
+        format!(
+            "<BWFXML>{}{}</BWFXML>",
+            "<a>".repeat(depth),
+            "</a>".repeat(depth)
+        )
+    };
+    assert!(Ixml::from_xml(&nested(200)).is_ok());
+    assert!(Ixml::from_xml(&nested(1_000)).is_err());
+    assert!(Ixml::from_xml(&format!("<BWFXML>{}", "<a>".repeat(200_000))).is_err());
+}
+
+fn audio_id(track_index: u16, uid: u32, track_format: &str, pack_format: &str) -> AudioId {
+    AudioId {
+        track_index,
+        uid: format!("ATU_{:08}", uid),
+        track_format_ref: track_format.to_string(),
+        pack_format_ref: pack_format.to_string(),
+    }
+}
+
+#[test]
+fn chna_round_trips() {
+    let chna = Chna {
+        ids: vec![
+            audio_id(1, 1, "AT_00010001_01", "AP_00010002"),
+            audio_id(2, 2, "AT_00010002_01", "AP_00010002"),
+            audio_id(3, 3, "AT_00031001_01", "AP_00031001"),
+            audio_id(3, 4, "AT_00031002_01", "AP_00031002"),
+        ],
+    };
+    assert_eq!(chna.track_count(), 3);
+
+    let chunk = Chunk::try_from(&chna).unwrap();
+    assert_eq!(chunk.id, *b"chna");
+    assert_eq!(chunk.data.len(), 4 + 4 * 40);
+    assert_eq!(chunk.data[..4], [3, 0, 4, 0]);
+    assert_eq!(&chunk.data[4..44], {
+        let mut id = vec![1, 0];
+        id.extend_from_slice(b"ATU_00000001AT_00010001_01AP_00010002\0");
+        id
+    });
+    assert_eq!(Chna::try_from(&chunk), Ok(chna));
+}
+
+#[test]
+fn chna_skips_unused_ids() {
+    let mut data = vec![1, 0, 1, 0];
+    data.extend_from_slice(&[1, 0]);
+    data.extend_from_slice(b"ATU_00000001AT_00010003_01AP_00010001\0");
+    // Slots reserved for later use
+    data.resize(4 + 3 * 40, 0);
+
+    let chna = Chna::try_from(&Chunk::new(Chna::ID, data)).unwrap();
+    assert_eq!(chna.ids, [audio_id(1, 1, "AT_00010003_01", "AP_00010001")]);
+
+    assert!(Chna::try_from(&Chunk::new(Chna::ID, vec![0; 3])).is_err());
+    assert!(Chna::try_from(&Chunk::new(Axml::ID, vec![0; 4])).is_err());
+}
+
+#[test]
+fn chna_rejects_invalid_ids() {
+    let mut chna = Chna {
+        ids: vec![audio_id(0, 1, "AT_00010003_01", "AP_00010001")],
+    };
+    assert!(Chunk::try_from(&chna).is_err());
+    chna.ids[0].track_index = 1;
+    chna.ids[0].track_format_ref = "AT_00010003_01_".to_string();
+    assert!(Chunk::try_from(&chna).is_err());
+    chna.ids[0].track_format_ref = "AT_0001000é_1".to_string();
+    assert!(Chunk::try_from(&chna).is_err());
+}
+
+const AXML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ebuCoreMain xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns="urn:ebu:metadata-schema:ebuCore_2014">
+  <coreMetadata>
+    <format>
+      <adm:audioFormatExtended xmlns:adm="urn:ebu:metadata-schema:ebuCore_2014">
+        <adm:audioProgramme audioProgrammeID="APR_1001" audioProgrammeName="Main mix" start="00:00:00.00000">
+          <adm:audioContentIDRef>ACO_1001</adm:audioContentIDRef>
+          <adm:audioContentIDRef>ACO_1002</adm:audioContentIDRef>
+        </adm:audioProgramme>
+        <adm:audioContent audioContentID="ACO_1001" audioContentName="Bed">
+          <adm:audioObjectIDRef>AO_1001</adm:audioObjectIDRef>
+        </adm:audioContent>
+        <adm:audioContent audioContentID="ACO_1002" audioContentName="Dialogue">
+          <adm:audioObjectIDRef>AO_1002</adm:audioObjectIDRef>
+        </adm:audioContent>
+        <adm:audioObject audioObjectID="AO_1001" audioObjectName="Stereo bed">
+          <adm:audioPackFormatIDRef>AP_00010002</adm:audioPackFormatIDRef>
+          <adm:audioTrackUIDRef>ATU_00000001</adm:audioTrackUIDRef>
+          <adm:audioTrackUIDRef>ATU_00000002</adm:audioTrackUIDRef>
+        </adm:audioObject>
+        <adm:audioObject audioObjectID="AO_1002" audioObjectName="Voice &amp; FX">
+          <adm:audioPackFormatIDRef>AP_00031001</adm:audioPackFormatIDRef>
+          <adm:audioTrackUIDRef>ATU_00000003</adm:audioTrackUIDRef>
+        </adm:audioObject>
+        <adm:audioTrackUID UID="ATU_00000001" sampleRate="48000" bitDepth="24"/>
+      </adm:audioFormatExtended>
+    </format>
+  </coreMetadata>
+</ebuCoreMain>
+"#;
+
+#[test]
+fn axml_reads_the_audio_definition_model() {
+    let mut data = AXML.as_bytes().to_vec();
+    data.push(0);
+    let axml = Axml::try_from(&Chunk::new(Axml::ID, data)).unwrap();
+    assert_eq!(axml.xml, AXML);
+
+    let adm = axml.adm().unwrap();
+    assert_eq!(
+        adm.programmes,
+        [AudioProgramme {
+            id: "APR_1001".to_string(),
+            name: "Main mix".to_string(),
+            content_refs: vec!["ACO_1001".to_string(), "ACO_1002".to_string()],
+        }]
+    );
+    assert_eq!(
+        adm.contents[1],
+        AudioContent {
+            id: "ACO_1002".to_string(),
+            name: "Dialogue".to_string(),
+            object_refs: vec!["AO_1002".to_string()],
+        }
+    );
+    assert_eq!(adm.contents.len(), 2);
+    assert_eq!(
+        adm.objects,
+        [
+            AudioObject {
+                id: "AO_1001".to_string(),
+                name: "Stereo bed".to_string(),
+                pack_format_refs: vec!["AP_00010002".to_string()],
+                track_uid_refs: vec!["ATU_00000001".to_string(), "ATU_00000002".to_string()],
+            },
+            AudioObject {
+                id: "AO_1002".to_string(),
+                name: "Voice & FX".to_string(),
+                pack_format_refs: vec!["AP_00031001".to_string()],
+                track_uid_refs: vec!["ATU_00000003".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn axml_is_written_as_stored() {
+    let axml = Axml::new(AXML);
+    let chunk = Chunk::from(&axml);
+    assert_eq!(chunk.id, *b"axml");
+    assert_eq!(chunk.data, AXML.as_bytes());
+    assert_eq!(Axml::try_from(&chunk), Ok(axml));
+
+    assert!(Axml::new("<ebuCoreMain/>").adm().is_err());
+    assert!(Axml::new("<audioFormatExtended>").adm().is_err());
+    assert_eq!(
+        Axml::new("<audioFormatExtended/>")
+            .adm()
+            .map(|adm| adm.objects),
+        Ok(Vec::new())
+    );
+    assert!(Axml::try_from(&Chunk::new(Axml::ID, vec![0xC3])).is_err());
+}
+
+#[test]
+fn cue_round_trips() {
+    let cue = Cue {
+        points: vec![
+            CuePoint::new(1, 0),
+            CuePoint::new(7, 48_000),
+            CuePoint {
+                id: 2,
+                position: 100,
+                chunk_id: *b"slnt",
+                chunk_start: 36,
+                block_start: 0,
+                sample_offset: 5,
+            },
+        ],
+    };
+
+    let chunk = Chunk::try_from(&cue).unwrap();
+    assert_eq!(chunk.id, *b"cue ");
+    assert_eq!(chunk.data.len(), 4 + 3 * 24);
+    assert_eq!(chunk.data[..4], 3u32.to_le_bytes());
+    assert_eq!(chunk.data[28..36], [7, 0, 0, 0, 0x80, 0xBB, 0, 0]);
+    assert_eq!(&chunk.data[36..40], b"data");
+    assert_eq!(chunk.data[48..52], 48_000u32.to_le_bytes());
+    assert_eq!(Cue::try_from(&chunk), Ok(cue.clone()));
+    assert_eq!(cue.point(2).map(|p| p.sample_offset), Some(5));
+    assert_eq!(cue.point(3), None);
+
+    let duplicate = Cue {
+        points: vec![CuePoint::new(1, 0), CuePoint::new(1, 10)],
+    };
+    assert!(Chunk::try_from(&duplicate).is_err());
+    assert!(Cue::try_from(&Chunk::new(
+        Cue::ID,
+        chunk.data[..chunk.data.len() - 1].to_vec()
+    ))
+    .is_err());
+    assert!(Cue::try_from(&Chunk::new(Plst::ID, chunk.data)).is_err());
+}
+
+fn playlist() -> (Header, BitDepth, Cue, Plst) {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 8_000, 16);
+    let data = BitDepth::Sixteen((0..20).collect());
+    let cue = Cue {
+        points: vec![
+            CuePoint::new(1, 0),
+            CuePoint::new(2, 4),
+            CuePoint::new(3, 8),
+        ],
+    };
+    let plst = Plst {
+        segments: vec![
+            Segment {
+                cue_id: 2,
+                length: 2,
+                loops: 3,
+            },
+            Segment {
+                cue_id: 3,
+                length: 2,
+                loops: 0,
+            },
+            Segment {
+                cue_id: 1,
+                length: 0,
+                loops: u32::MAX,
+            },
+            Segment {
+                cue_id: 1,
+                length: 1,
+                loops: 1,
+            },
+        ],
+    };
+    (header, data, cue, plst)
+}
+
+#[test]
+fn plst_round_trips() {
+    let (_, _, _, plst) = playlist();
+
+    let chunk = Chunk::try_from(&plst).unwrap();
+    assert_eq!(chunk.id, *b"plst");
+    assert_eq!(chunk.data.len(), 4 + 4 * 12);
+    assert_eq!(
+        chunk.data[..16],
+        [4, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0]
+    );
+    assert_eq!(Plst::try_from(&chunk), Ok(plst));
+
+    assert!(Plst::try_from(&Chunk::new(Plst::ID, vec![1, 0, 0, 0])).is_err());
+    assert!(Plst::try_from(&Chunk::new(Cue::ID, vec![0; 4])).is_err());
+}
+
+#[test]
+fn plst_renders_segments_in_order() {
+    let (header, data, cue, plst) = playlist();
+
+    let rendered = plst.render(&cue, &header, &data).unwrap();
+    assert_eq!(
+        rendered,
+        BitDepth::Sixteen(vec![8, 9, 10, 11, 8, 9, 10, 11, 8, 9, 10, 11, 0, 1])
+    );
+
+    let samples = data.as_sixteen().unwrap();
+    let mut frames = plst.frames(&cue, &header, samples).unwrap();
+    assert_eq!(frames.size_hint(), (7, Some(7)));
+    assert_eq!(frames.next(), Some(&[8, 9][..]));
+    assert_eq!(frames.next(), Some(&[10, 11][..]));
+    assert_eq!(frames.next(), Some(&[8, 9][..]));
+    assert_eq!(frames.size_hint(), (4, Some(4)));
+    let rest: Vec<_> = frames.collect();
+    assert_eq!(rest.len(), 4);
+    assert_eq!(rest[3], [0, 1]);
+}
+
+#[test]
+fn plst_rejects_invalid_segments() {
+    let (header, data, cue, mut plst) = playlist();
+
+    plst.segments[1].length = 3;
+    assert!(plst.render(&cue, &header, &data).is_err());
+    plst.segments[1].cue_id = 9;
+    assert!(plst
+        .frames(&cue, &header, data.as_sixteen().unwrap())
+        .is_err());
+}
+
+#[test]
+fn plst_rejects_cue_points_in_later_wave_list_chunks() {
+    let (header, data, mut cue, plst) = playlist();
+
+    cue.points[0].chunk_start = 20;
+    assert!(plst.render(&cue, &header, &data).is_err());
+    assert!(plst
+        .frames(&cue, &header, data.as_sixteen().unwrap())
+        .is_err());
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "mmap")]
+
+use std::fs::File;
+
+use wav::mmap::{MmapWav, Samples};
+
+macro_rules! impl_mmap_test {
+    ( $( $F:ident: $f:expr, $borrowed:expr ),* $(,)* ) => { $(
+        #[test]
+        fn $F() {
+            let (header, data) = wav::read(&mut File::open($f).unwrap()).unwrap();
+
+            let wav = unsafe { MmapWav::open($f) }.unwrap();
+            assert_eq!(*wav.header(), header);
+
+            let samples = wav.samples().unwrap();
+            assert_eq!(samples.is_borrowed(), $borrowed);
+            assert_eq!(samples.into_owned(), data);
+
+            let (_, range) = wav::read_range(&mut File::open($f).unwrap(), 10, 20).unwrap();
+            assert_eq!(wav.frames(10, 20).unwrap().into_owned(), range);
+        }
+    )* };
+}
+
+impl_mmap_test! {
+    mmap_sine_8bit_48khz: "data/sine_8bit_48khz.wav", true,
+    mmap_sine_16bit_48khz: "data/sine_16bit_48khz.wav", cfg!(target_endian = "little"),
+    mmap_sine_24bit_48khz: "data/sine_24bit_48khz.wav", false,
+    mmap_sine_32bit_float_48khz: "data/sine_32bit_float_48khz.wav", cfg!(target_endian = "little"),
+}
+
+#[test]
+fn mmap_decoded_samples_are_owned() {
+    let wav = unsafe { MmapWav::open("data/sine_24bit_48khz.wav") }.unwrap();
+    assert!(matches!(wav.samples().unwrap(), Samples::Decoded(..)));
+}
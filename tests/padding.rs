@@ -0,0 +1,126 @@
+use std::io;
+
+use wav::{BitDepth, Chunk, HeaderBuilder, SampleFormat, Warning};
+
+fn mono_8bit() -> wav::Header {
+    HeaderBuilder::new(SampleFormat::Eight)
+        .channel_count(1)
+        .build()
+        .unwrap()
+}
+
+/// Builds a wave file by hand from the given chunks, appending a pad byte to odd-length chunks
+/// only if `pad` is set.
+fn build(chunks: &[(&[u8; 4], &[u8])], pad: bool) -> Vec<u8> {
+    let mut body = b"WAVE".to_vec();
+    for (id, data) in chunks {
+        body.extend_from_slice(*id);
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        if pad && data.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+
+    let mut raw = b"RIFF".to_vec();
+    raw.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    raw.extend(body);
+    raw
+}
+
+#[test]
+fn write_pads_odd_chunks() {
+    let data = BitDepth::Eight(vec![1, 2, 3, 4, 5]);
+    let extra = vec![Chunk::new(*b"note", b"odd".to_vec())];
+
+    let mut out = io::Cursor::new(Vec::new());
+    wav::write_with_chunks(mono_8bit(), &data, &extra, &mut out).unwrap();
+    let raw = out.into_inner();
+
+    let fmt = Vec::from(mono_8bit());
+    let expected = build(
+        &[
+            (b"fmt ", &fmt),
+            (b"note", b"odd"),
+            (b"data", &[1, 2, 3, 4, 5]),
+        ],
+        true,
+    );
+    assert_eq!(raw, expected);
+    assert_eq!(raw.len() % 2, 0);
+
+    let (_, read_data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(&raw), wav::ReadOptions::default()).unwrap();
+    assert_eq!(read_data, data);
+    assert!(warnings.is_empty());
+    assert_eq!(wav::read_chunks(&mut io::Cursor::new(&raw)).unwrap(), extra);
+}
+
+#[test]
+fn chunks_after_odd_data_are_found() {
+    let fmt = Vec::from(mono_8bit());
+    let raw = build(
+        &[(b"fmt ", &fmt), (b"data", &[1, 2, 3]), (b"tail", b"end!")],
+        true,
+    );
+
+    let (_, data) = wav::read(&mut io::Cursor::new(&raw)).unwrap();
+    assert_eq!(data, BitDepth::Eight(vec![1, 2, 3]));
+    assert_eq!(
+        wav::read_chunks(&mut io::Cursor::new(&raw)).unwrap(),
+        vec![Chunk::new(*b"tail", b"end!".to_vec())]
+    );
+}
+
+#[test]
+fn missing_pad_bytes_are_detected() {
+    let fmt = Vec::from(mono_8bit());
+    let raw = build(
+        &[
+            (b"fmt ", &fmt),
+            (b"note", b"odd"),
+            (b"data", &[1, 2, 3]),
+            (b"tail", b"end!"),
+        ],
+        false,
+    );
+
+    let (_, data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(&raw), wav::ReadOptions::default()).unwrap();
+    assert_eq!(data, BitDepth::Eight(vec![1, 2, 3]));
+    assert_eq!(
+        warnings,
+        vec![
+            Warning::MissingPadByte { id: *b"note" },
+            Warning::MissingPadByte { id: *b"data" },
+        ]
+    );
+    assert_eq!(
+        wav::read_chunks(&mut io::Cursor::new(&raw)).unwrap(),
+        vec![
+            Chunk::new(*b"note", b"odd".to_vec()),
+            Chunk::new(*b"tail", b"end!".to_vec())
+        ]
+    );
+}
+
+#[test]
+fn missing_pad_before_printable_size_is_detected() {
+    // The low byte of the following chunk's size, 0x41, is printable, so the bytes after a pad
+    // byte would also look like a chunk ID
+    let fmt = Vec::from(mono_8bit());
+    let tail = [7; 0x41];
+    let raw = build(
+        &[(b"fmt ", &fmt), (b"data", &[1, 2, 3]), (b"tail", &tail)],
+        false,
+    );
+
+    let (_, data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(&raw), wav::ReadOptions::default()).unwrap();
+    assert_eq!(data, BitDepth::Eight(vec![1, 2, 3]));
+    assert_eq!(warnings, vec![Warning::MissingPadByte { id: *b"data" }]);
+    assert_eq!(
+        wav::read_chunks(&mut io::Cursor::new(&raw)).unwrap(),
+        vec![Chunk::new(*b"tail", tail.to_vec())]
+    );
+}
@@ -0,0 +1,43 @@
+use std::{convert::TryFrom, io};
+
+use wav::BitDepth;
+
+fn slice_frames(data: &BitDepth, channels: usize, start: usize, count: usize) -> BitDepth {
+    let range = start * channels..(start + count) * channels;
+    match data {
+        BitDepth::Eight(v) => BitDepth::Eight(v[range].to_vec()),
+        BitDepth::Sixteen(v) => BitDepth::Sixteen(v[range].to_vec()),
+        BitDepth::TwentyFour(v) => BitDepth::TwentyFour(v[range].to_vec()),
+        BitDepth::ThirtyTwoFloat(v) => BitDepth::ThirtyTwoFloat(v[range].to_vec()),
+        BitDepth::Empty => BitDepth::Empty,
+    }
+}
+
+macro_rules! impl_range_test {
+    ( $( $F:ident: $f:expr ),* $(,)* ) => { $(
+        #[test]
+        fn $F() {
+            let raw: &[u8] = include_bytes!($f);
+            let (header, data) = wav::read(&mut io::Cursor::new(raw)).unwrap();
+            let channels = usize::from(header.channel_count);
+
+            let (range_header, range) = wav::read_range(&mut io::Cursor::new(raw), 100, 250).unwrap();
+            assert_eq!(range_header, header);
+            assert_eq!(range, slice_frames(&data, channels, 100, 250));
+
+            let mut reader = wav::WavReader::new(io::Cursor::new(raw)).unwrap();
+            let frames = usize::try_from(reader.frame_count()).unwrap();
+            reader.seek(reader.frame_count() - 10).unwrap();
+            assert_eq!(reader.read_frames(100).unwrap(), slice_frames(&data, channels, frames - 10, 10));
+            assert_eq!(reader.position(), reader.frame_count());
+            assert!(reader.seek(reader.frame_count() + 1).is_err());
+        }
+    )* };
+}
+
+impl_range_test! {
+    range_sine_8bit_48khz: "../data/sine_8bit_48khz.wav",
+    range_sine_16bit_48khz: "../data/sine_16bit_48khz.wav",
+    range_sine_24bit_48khz: "../data/sine_24bit_48khz.wav",
+    range_sine_32bit_float_48khz: "../data/sine_32bit_float_48khz.wav",
+}
@@ -0,0 +1,119 @@
+use std::io;
+
+use wav::{BitDepth, ReadOptions, Warning};
+
+const RAW: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+
+fn recover() -> ReadOptions {
+    ReadOptions {
+        recover: true,
+        ..ReadOptions::default()
+    }
+}
+
+/// Returns the offset of the contents of the `"data"` chunk in `raw`.
+fn data_offset(raw: &[u8]) -> usize {
+    raw.windows(4).position(|w| w == b"data").unwrap() + 8
+}
+
+#[test]
+fn recover_unfinalized_file() {
+    let mut raw = RAW.to_vec();
+    let offset = data_offset(&raw);
+    raw[4..8].copy_from_slice(&0u32.to_le_bytes());
+    raw[offset - 4..offset].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    assert!(wav::read(&mut io::Cursor::new(&raw)).is_err());
+
+    let (_, data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(&raw), recover()).unwrap();
+    let (_, expected) = wav::read(&mut io::Cursor::new(RAW)).unwrap();
+    assert_eq!(data, expected);
+
+    let recovered = (raw.len() - offset) as u64;
+    assert_eq!(
+        warnings,
+        vec![
+            Warning::RiffSizeMismatch {
+                declared: 0,
+                actual: raw.len() as u64 - 8
+            },
+            Warning::ChunkSizeClamped {
+                id: *b"data",
+                declared: u32::MAX,
+                recovered
+            },
+        ]
+    );
+
+    let reader = wav::WavReader::with_options(io::Cursor::new(&raw), recover()).unwrap();
+    assert_eq!(reader.frame_count(), recovered / 4);
+}
+
+#[test]
+fn recover_truncated_file() {
+    let raw = &RAW[..RAW.len() - 5];
+
+    assert!(wav::read(&mut io::Cursor::new(raw)).is_err());
+
+    let (_, data, warnings) = wav::read_with_options(&mut io::Cursor::new(raw), recover()).unwrap();
+    let (_, expected) = wav::read(&mut io::Cursor::new(RAW)).unwrap();
+
+    let (data, expected) = (
+        data.try_into_sixteen().unwrap(),
+        expected.try_into_sixteen().unwrap(),
+    );
+    assert_eq!(data.len(), expected.len() - 4);
+    assert_eq!(data[..], expected[..data.len()]);
+
+    assert!(warnings.contains(&Warning::PartialFrameDropped { bytes: 3 }));
+    assert!(warnings
+        .iter()
+        .any(|w| matches!(w, Warning::ChunkSizeClamped { id, .. } if id == b"data")));
+}
+
+#[test]
+fn recover_leaves_valid_file_alone() {
+    let (_, data, warnings) = wav::read_with_options(&mut io::Cursor::new(RAW), recover()).unwrap();
+
+    assert!(warnings.is_empty());
+    assert!(matches!(data, BitDepth::Sixteen(..)));
+}
+
+#[test]
+fn recover_data_chunk_of_size_zero() {
+    let mut raw = RAW.to_vec();
+    let offset = data_offset(&raw);
+    raw[offset - 4..offset].copy_from_slice(&0u32.to_le_bytes());
+
+    let (_, data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(&raw), recover()).unwrap();
+    let (_, expected) = wav::read(&mut io::Cursor::new(RAW)).unwrap();
+    assert_eq!(data, expected);
+    assert_eq!(
+        warnings,
+        vec![Warning::ChunkSizeClamped {
+            id: *b"data",
+            declared: 0,
+            recovered: (raw.len() - offset) as u64
+        }]
+    );
+}
+
+#[test]
+fn recover_keeps_empty_data_chunk() {
+    let mut raw = RAW[..data_offset(RAW)].to_vec();
+    let len = raw.len();
+    raw[len - 4..].copy_from_slice(&0u32.to_le_bytes());
+    raw.extend_from_slice(b"LIST\x08\x00\x00\x00INFOtest");
+    let riff_len = (raw.len() - 8) as u32;
+    raw[4..8].copy_from_slice(&riff_len.to_le_bytes());
+
+    let (_, data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(&raw), recover()).unwrap();
+    assert_eq!(data, BitDepth::Sixteen(Vec::new()));
+    assert!(warnings.is_empty());
+
+    let reader = wav::WavReader::with_options(io::Cursor::new(&raw), recover()).unwrap();
+    assert_eq!(reader.frame_count(), 0);
+}
@@ -0,0 +1,103 @@
+use std::{f64::consts::PI, io::Cursor};
+
+use wav::{
+    resample::{self, Quality, Resampler},
+    BitDepth, Header, WAV_FORMAT_IEEE_FLOAT,
+};
+
+/// Returns `len` frames of a mono sine wave of `freq` Hz at `rate` Hz.
+fn sine(freq: f64, rate: u32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| (0.5 * (2.0 * PI * freq * i as f64 / f64::from(rate)).sin()) as f32)
+        .collect()
+}
+
+/// Returns the largest difference between the two signals, away from their edges.
+fn max_error(a: &[f32], b: &[f32]) -> f32 {
+    let edge = a.len() / 10;
+    a[edge..a.len() - edge]
+        .iter()
+        .zip(&b[edge..])
+        .map(|(x, y)| (x - y).abs())
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn resample_updates_header_and_length() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let (header, data) = wav::read(&mut Cursor::new(raw)).unwrap();
+
+    let (resampled, out) = resample::resample(&header, &data, 44_100, Quality::Low).unwrap();
+    assert_eq!(resampled.sampling_rate, 44_100);
+    assert_eq!(resampled.bytes_per_second, 176_400);
+    assert_eq!(resampled.bytes_per_sample, header.bytes_per_sample);
+    assert!(resampled.validate().is_ok());
+    assert_eq!(out.as_sixteen().map(Vec::len), Some(2 * 44_100));
+}
+
+#[test]
+fn resampling_preserves_sine() {
+    for &(from, to, quality) in &[
+        (48_000, 44_100, Quality::High),
+        (44_100, 96_000, Quality::Medium),
+        (96_000, 44_100, Quality::Medium),
+        (44_100, 48_001, Quality::Low),
+    ] {
+        let mut resampler = Resampler::new(from, to, 1, quality).unwrap();
+        let mut out = resampler.process(&sine(1_000.0, from, 4_800));
+        out.extend(resampler.finish());
+
+        let expected = sine(1_000.0, to, out.len());
+        assert_eq!(out.len(), (4_800 * to as usize).div_ceil(from as usize));
+        assert!(
+            max_error(&out, &expected) < 1e-3,
+            "{from} -> {to}: {}",
+            max_error(&out, &expected)
+        );
+    }
+}
+
+#[test]
+fn blocks_match_whole() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32);
+    let input: Vec<f32> = sine(440.0, 48_000, 20_000)
+        .into_iter()
+        .flat_map(|s| vec![s, -s])
+        .collect();
+
+    let (_, whole) = resample::resample(
+        &header,
+        &BitDepth::ThirtyTwoFloat(input.clone()),
+        22_050,
+        Quality::High,
+    )
+    .unwrap();
+
+    let mut resampler = Resampler::new(48_000, 22_050, 2, Quality::High).unwrap();
+    let mut blocks = Vec::new();
+    for block in input.chunks(2 * 997) {
+        blocks.extend(resampler.process(block));
+    }
+    blocks.extend(resampler.finish());
+
+    assert_eq!(BitDepth::ThirtyTwoFloat(blocks), whole);
+}
+
+#[test]
+fn same_rate_is_identity() {
+    let input = sine(1_000.0, 48_000, 1_000);
+    let mut resampler = Resampler::new(48_000, 48_000, 1, Quality::High).unwrap();
+
+    let mut out = resampler.process(&input);
+    out.extend(resampler.finish());
+    assert_eq!(out, input);
+}
+
+#[test]
+fn rejects_zero_rates() {
+    assert!(Resampler::new(0, 44_100, 2, Quality::Medium).is_err());
+    assert!(Resampler::new(48_000, 44_100, 0, Quality::Medium).is_err());
+
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32);
+    assert!(resample::resampled_header(&header, 0).is_err());
+}
@@ -0,0 +1,114 @@
+use std::io::Cursor;
+
+use wav::{BitDepth, Header, WavReader, WAV_FORMAT_PCM};
+
+/// Appends a chunk to `out` with the 32-bit size `size`, followed by a pad byte if its length is
+/// odd.
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], size: u32, data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Returns the contents of a `"ds64"` chunk with the given sizes and table of chunk sizes.
+fn ds64(riff_len: u64, data_len: u64, table: &[(&[u8; 4], u64)]) -> Vec<u8> {
+    let mut ds64 = riff_len.to_le_bytes().to_vec();
+    ds64.extend_from_slice(&data_len.to_le_bytes());
+    ds64.extend_from_slice(&(data_len / 4).to_le_bytes());
+    ds64.extend_from_slice(&(table.len() as u32).to_le_bytes());
+    for (id, len) in table {
+        ds64.extend_from_slice(*id);
+        ds64.extend_from_slice(&len.to_le_bytes());
+    }
+    ds64
+}
+
+/// Returns an RF64 or BW64 file whose `"data"` and `"axml"` chunks have their sizes in the
+/// `"ds64"` chunk.
+fn large_file(form: &[u8; 4], samples: &[i16], xml: &[u8]) -> Vec<u8> {
+    let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let fmt = Vec::from(Header::new(WAV_FORMAT_PCM, 2, 48_000, 16));
+
+    // The "ds64" chunk doesn't depend on the size of the RIFF data, only its contents do
+    let ds64_len = ds64(0, 0, &[(b"axml", 0)]).len();
+    let riff_len = 4 + (8 + ds64_len) + (8 + fmt.len()) + (8 + data.len()) + (8 + xml.len());
+    let table = [(b"axml", xml.len() as u64)];
+
+    let mut body = b"WAVE".to_vec();
+    push_chunk(
+        &mut body,
+        b"ds64",
+        ds64_len as u32,
+        &ds64(riff_len as u64, data.len() as u64, &table),
+    );
+    push_chunk(&mut body, b"fmt ", fmt.len() as u32, &fmt);
+    push_chunk(&mut body, b"data", u32::MAX, &data);
+    push_chunk(&mut body, b"axml", u32::MAX, xml);
+
+    let mut raw = form.to_vec();
+    raw.extend_from_slice(&u32::MAX.to_le_bytes());
+    raw.extend_from_slice(&body);
+    raw
+}
+
+#[test]
+fn large_files_are_read_with_ds64_sizes() {
+    let xml = b"<ebuCoreMain/>";
+    for form in [b"RF64", b"BW64"] {
+        let raw = large_file(form, &[1, -1, 2, -2], xml);
+
+        let (header, data) = wav::read(&mut Cursor::new(&raw)).unwrap();
+        assert_eq!(header.channel_count, 2);
+        assert_eq!(data, BitDepth::Sixteen(vec![1, -1, 2, -2]));
+
+        let chunks = wav::list_chunks(&mut Cursor::new(&raw)).unwrap();
+        let lens: Vec<_> = chunks.iter().map(|c| (c.id, c.len)).collect();
+        assert_eq!(
+            lens,
+            [
+                (*b"ds64", 40),
+                (*b"fmt ", 16),
+                (*b"data", 8),
+                (*b"axml", 14)
+            ]
+        );
+
+        let chunks = wav::read_chunks(&mut Cursor::new(&raw)).unwrap();
+        assert_eq!(chunks.len(), 1, "only the axml chunk is kept");
+        assert_eq!(chunks[0].data, xml);
+
+        let mut reader = WavReader::new(Cursor::new(&raw)).unwrap();
+        assert_eq!(reader.frame_count(), 2);
+        assert_eq!(
+            reader.read_frames(2).unwrap(),
+            BitDepth::Sixteen(vec![1, -1, 2, -2])
+        );
+    }
+}
+
+#[test]
+fn short_ds64_chunk_is_rejected() {
+    let mut raw = large_file(b"RF64", &[1, -1], b"");
+    // Cut the "ds64" chunk down to the size of the RIFF data
+    raw[16..20].copy_from_slice(&8_u32.to_le_bytes());
+    assert!(wav::read(&mut Cursor::new(&raw)).is_err());
+}
+
+#[test]
+fn other_forms_are_rejected() {
+    let mut raw = large_file(b"RF64", &[1, -1], b"");
+    raw[0..4].copy_from_slice(b"RIFX");
+    assert!(wav::read(&mut Cursor::new(&raw)).is_err());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_reads_large_files() {
+    let raw = large_file(b"BW64", &[1, -1], b"");
+    let mut cursor = futures_util::io::Cursor::new(&raw);
+    let (_, data) = futures_executor::block_on(wav::async_io::read(&mut cursor)).unwrap();
+    assert_eq!(data, BitDepth::Sixteen(vec![1, -1]));
+}
@@ -0,0 +1,114 @@
+use std::{borrow::Cow, io};
+
+use wav::{BitDepth, Header, Sample, SampleFormat, I24, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM};
+
+const FILES: [&[u8]; 4] = [
+    include_bytes!("../data/sine_8bit_48khz.wav"),
+    include_bytes!("../data/sine_16bit_48khz.wav"),
+    include_bytes!("../data/sine_24bit_48khz.wav"),
+    include_bytes!("../data/sine_32bit_float_48khz.wav"),
+];
+
+#[test]
+fn read_as_matches_convert() {
+    for raw in FILES {
+        let (header, data) = wav::read(&mut io::Cursor::new(raw)).unwrap();
+        let read = |format| data.convert(format);
+
+        let (h, samples) = wav::read_as::<u8, _>(&mut io::Cursor::new(raw)).unwrap();
+        assert_eq!(h, header);
+        assert_eq!(BitDepth::Eight(samples), read(SampleFormat::Eight));
+        let (_, samples) = wav::read_as::<i16, _>(&mut io::Cursor::new(raw)).unwrap();
+        assert_eq!(BitDepth::Sixteen(samples), read(SampleFormat::Sixteen));
+        let (_, samples) = wav::read_as::<i32, _>(&mut io::Cursor::new(raw)).unwrap();
+        assert_eq!(
+            BitDepth::TwentyFour(samples),
+            read(SampleFormat::TwentyFour)
+        );
+        let (_, samples) = wav::read_as::<f32, _>(&mut io::Cursor::new(raw)).unwrap();
+        assert_eq!(
+            BitDepth::ThirtyTwoFloat(samples),
+            read(SampleFormat::ThirtyTwoFloat)
+        );
+    }
+}
+
+#[test]
+fn twenty_four_bit_samples() {
+    let data = BitDepth::TwentyFour(vec![0x7FFF_FF00, -0x100, i32::MIN]);
+    assert_eq!(*I24::from_bit_depth(&data), [I24::MAX, I24(-1), I24::MIN]);
+    assert_eq!(*i32::from_bit_depth(&data), [0x7FFF_FF00, -0x100, i32::MIN]);
+
+    // Out of range values are clamped, and the low bits of `i32` samples dropped
+    assert_eq!(
+        I24::to_bit_depth(&[I24(1 << 23), I24(-1), I24(i32::MIN)]),
+        BitDepth::TwentyFour(vec![0x7FFF_FF00, -0x100, i32::MIN])
+    );
+    assert_eq!(
+        i32::to_bit_depth(&[0x7FFF_FFFF, 0x1FF]),
+        BitDepth::TwentyFour(vec![0x7FFF_FF00, 0x100])
+    );
+}
+
+#[test]
+fn float_samples() {
+    let data = BitDepth::Eight(vec![0, 64, 128, 255]);
+    assert_eq!(
+        *f64::from_bit_depth(&data),
+        [-1.0, -0.5, 0.0, 127.0 / 128.0]
+    );
+    assert_eq!(
+        f64::to_bit_depth(&[0.25, -1.0]),
+        BitDepth::ThirtyTwoFloat(vec![0.25, -1.0])
+    );
+    assert!(f32::from_bit_depth(&BitDepth::Empty).is_empty());
+}
+
+#[test]
+fn samples_of_the_same_format_are_borrowed() {
+    let data = BitDepth::Sixteen(vec![1, -1]);
+    assert!(matches!(i16::from_bit_depth(&data), Cow::Borrowed([1, -1])));
+    assert!(matches!(f32::from_bit_depth(&data), Cow::Owned(_)));
+
+    let data = BitDepth::ThirtyTwoFloat(vec![0.5]);
+    assert!(matches!(f32::from_bit_depth(&data), Cow::Borrowed(_)));
+    assert!(matches!(f64::from_bit_depth(&data), Cow::Owned(_)));
+}
+
+#[test]
+fn write_from_converts_to_the_header_format() {
+    let samples = [0.0, 0.5, -0.25, -1.0];
+    for (header, expected) in [
+        (
+            Header::new(WAV_FORMAT_PCM, 2, 48_000, 8),
+            BitDepth::Eight(vec![128, 192, 96, 0]),
+        ),
+        (
+            Header::new(WAV_FORMAT_PCM, 2, 48_000, 16),
+            BitDepth::Sixteen(vec![0, 16_384, -8_192, -32_768]),
+        ),
+        (
+            Header::new(WAV_FORMAT_PCM, 2, 48_000, 24),
+            BitDepth::TwentyFour(vec![0, 1 << 30, -(1 << 29), i32::MIN]),
+        ),
+        (
+            Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32),
+            BitDepth::ThirtyTwoFloat(vec![0.0, 0.5, -0.25, -1.0]),
+        ),
+    ] {
+        let mut out = io::Cursor::new(Vec::new());
+        wav::write_from::<f64, _>(header, &samples, &mut out).unwrap();
+        let (read_header, data) = wav::read(&mut io::Cursor::new(out.get_ref())).unwrap();
+        assert_eq!(read_header, header);
+        assert_eq!(data, expected);
+
+        let (_, read) = wav::read_as::<f64, _>(&mut io::Cursor::new(out.into_inner())).unwrap();
+        for (a, b) in read.iter().zip(&samples) {
+            assert!((a - b).abs() < 1.0 / 128.0, "{} {}", a, b);
+        }
+    }
+
+    let mut out = io::Cursor::new(Vec::new());
+    let header = Header::new(WAV_FORMAT_PCM, 1, 48_000, 12);
+    assert!(wav::write_from(header, &[0i16], &mut out).is_err());
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "serde")]
+
+use std::io;
+
+use wav::{
+    header::FormatExtension, metadata::Bext, BitDepth, Header, WAV_FORMAT_EXTENSIBLE,
+    WAV_FORMAT_PCM,
+};
+
+#[test]
+fn header_json_names_format_tags() {
+    let mut header = Header::new(WAV_FORMAT_EXTENSIBLE, 2, 48_000, 24);
+    header.extension = Some(FormatExtension::new(20, 0x3, WAV_FORMAT_PCM));
+
+    let json = serde_json::to_value(header).unwrap();
+    assert_eq!(json["audio_format"], "extensible");
+    assert_eq!(
+        json["extension"]["sub_format"],
+        "00000001-0000-0010-8000-00aa00389b71"
+    );
+    assert_eq!(serde_json::from_value::<Header>(json).unwrap(), header);
+}
+
+#[test]
+fn header_json_keeps_unknown_format_tags() {
+    let header = Header::new(0x55, 2, 44_100, 16);
+
+    let json = serde_json::to_string(&header).unwrap();
+    assert!(json.contains("\"audio_format\":85"));
+    assert_eq!(serde_json::from_str::<Header>(&json).unwrap(), header);
+
+    let invalid = json.replace("85", "\"mp3\"");
+    assert!(serde_json::from_str::<Header>(&invalid).is_err());
+}
+
+#[test]
+fn bit_depth_bincode_round_trip() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let (header, data) = wav::read(&mut io::Cursor::new(raw)).unwrap();
+
+    let bytes = bincode::serialize(&(header, &data)).unwrap();
+    let (decoded_header, decoded): (Header, BitDepth) = bincode::deserialize(&bytes).unwrap();
+
+    assert_eq!(decoded_header, header);
+    assert_eq!(decoded, data);
+}
+
+#[test]
+fn bext_round_trips_with_umid() {
+    let bext = Bext {
+        description: "Take 1".to_string(),
+        umid: [3; 64],
+        ..Bext::default()
+    };
+
+    let json = serde_json::to_string(&bext).unwrap();
+    assert_eq!(serde_json::from_str::<Bext>(&json).unwrap(), bext);
+    let bytes = bincode::serialize(&bext).unwrap();
+    assert_eq!(bincode::deserialize::<Bext>(&bytes).unwrap(), bext);
+
+    let short = json.replace("[3,3,", "[");
+    assert!(serde_json::from_str::<Bext>(&short).is_err());
+}
+
+#[test]
+fn waveform_round_trips() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let options = wav::waveform::WaveformOptions {
+        block_frames: 1_000,
+        levels: 2,
+        factor: 10,
+    };
+    let overview = wav::waveform::waveform_reader(io::Cursor::new(raw), options).unwrap();
+
+    let json = serde_json::to_string(&overview).unwrap();
+    assert_eq!(
+        serde_json::from_str::<wav::waveform::Waveform>(&json).unwrap(),
+        overview
+    );
+}
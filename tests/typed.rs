@@ -0,0 +1,73 @@
+use std::{convert::TryFrom, io};
+
+use wav::{BitDepth, Header, Wav, I24, WAV_FORMAT_PCM};
+
+#[test]
+fn reads_matching_files() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let (header, data) = wav::read(&mut io::Cursor::new(raw)).unwrap();
+
+    let wav = Wav::<i16, 2>::read(&mut io::Cursor::new(raw)).unwrap();
+    assert_eq!(*wav.header(), header);
+    assert_eq!(wav.sampling_rate(), 48_000);
+    assert_eq!(BitDepth::Sixteen(wav.frames.concat()), data);
+    assert_eq!(wav.to_parts(), (header, data));
+}
+
+#[test]
+fn rejects_mismatched_files() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let read_err = |e: io::Error| e.kind();
+
+    let mono = Wav::<i16, 1>::read(&mut io::Cursor::new(raw));
+    assert_eq!(mono.map_err(read_err), Err(io::ErrorKind::InvalidData));
+    let float = Wav::<f32, 2>::read(&mut io::Cursor::new(raw));
+    assert_eq!(float.map_err(read_err), Err(io::ErrorKind::InvalidData));
+    let twenty_four = Wav::<I24, 2>::read(&mut io::Cursor::new(raw));
+    assert_eq!(
+        twenty_four.map_err(read_err),
+        Err(io::ErrorKind::InvalidData)
+    );
+}
+
+#[test]
+fn converts_from_parts() {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 8_000, 24);
+    let data = BitDepth::TwentyFour(vec![0x100, -0x100, 0x7FFF_FF00, i32::MIN]);
+
+    let wav = Wav::<I24, 2>::try_from((header, data.clone())).unwrap();
+    assert_eq!(wav.frames, [[I24(1), I24(-1)], [I24::MAX, I24::MIN]]);
+    assert_eq!(<(Header, BitDepth)>::from(&wav), (header, data.clone()));
+
+    // Both types of 24-bit samples match a 24-bit file
+    let wav = Wav::<i32, 2>::from_parts(header, &data).unwrap();
+    assert_eq!(wav.frames, [[0x100, -0x100], [0x7FFF_FF00, i32::MIN]]);
+
+    // Empty audio has no frames
+    let wav = Wav::<i32, 2>::from_parts(header, &BitDepth::Empty).unwrap();
+    assert!(wav.frames.is_empty());
+
+    let partial = BitDepth::TwentyFour(vec![0x100, -0x100, 0x100]);
+    assert!(Wav::<i32, 2>::from_parts(header, &partial).is_err());
+    let sixteen = BitDepth::Sixteen(vec![1, -1]);
+    assert!(Wav::<i32, 2>::from_parts(header, &sixteen).is_err());
+    assert!(Wav::<i32, 1>::from_parts(header, &data).is_err());
+}
+
+#[test]
+fn writes_typed_audio() {
+    let mut wav = Wav::<f32, 2>::new(44_100, vec![[0.5, -0.5]]).unwrap();
+    wav.frames.push([0.25, 1.0]);
+    assert_eq!(wav.header().channel_count, 2);
+    assert_eq!(wav.header().bits_per_sample, 32);
+
+    let mut out = io::Cursor::new(Vec::new());
+    wav.write(&mut out).unwrap();
+    out.set_position(0);
+    assert_eq!(Wav::<f32, 2>::read(&mut out).unwrap(), wav);
+
+    assert!(Wav::<u8, 1>::new(0, Vec::new()).is_err());
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 8);
+    assert!(Wav::<u8, 1>::with_header(header, vec![[128]]).is_ok());
+    assert!(Wav::<i16, 1>::with_header(header, vec![[0]]).is_err());
+}
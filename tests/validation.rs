@@ -0,0 +1,113 @@
+use std::io;
+
+use wav::{
+    header::{HeaderIssue, InvalidHeader},
+    ReadOptions, Validation, Warning,
+};
+
+/// Returns the 16-bit test file with its byte rate, stored at offset 28, overwritten.
+fn corrupted_byte_rate() -> Vec<u8> {
+    let mut raw = include_bytes!("../data/sine_16bit_48khz.wav").to_vec();
+    raw[28..32].copy_from_slice(&1234u32.to_le_bytes());
+    raw
+}
+
+fn options(validation: Validation) -> ReadOptions {
+    ReadOptions {
+        validation,
+        ..ReadOptions::default()
+    }
+}
+
+#[test]
+fn unchecked_accepts_bad_header() {
+    let raw = corrupted_byte_rate();
+    let (header, _, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(raw), options(Validation::Unchecked)).unwrap();
+
+    assert_eq!(header.bytes_per_second, 1234);
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn strict_rejects_bad_header() {
+    let raw = corrupted_byte_rate();
+    let err =
+        wav::read_with_options(&mut io::Cursor::new(raw), options(Validation::Strict)).unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let invalid = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<InvalidHeader>()
+        .unwrap();
+    assert_eq!(
+        invalid.issues,
+        vec![HeaderIssue::BytesPerSecondMismatch {
+            expected: 192_000,
+            found: 1234
+        }]
+    );
+}
+
+#[test]
+fn lenient_repairs_bad_header() {
+    let raw = corrupted_byte_rate();
+    let (header, data, warnings) =
+        wav::read_with_options(&mut io::Cursor::new(raw), options(Validation::Lenient)).unwrap();
+
+    assert_eq!(header.bytes_per_second, 192_000);
+    assert!(data.is_sixteen());
+    assert_eq!(
+        warnings,
+        vec![Warning::HeaderRepaired(
+            HeaderIssue::BytesPerSecondMismatch {
+                expected: 192_000,
+                found: 1234
+            }
+        )]
+    );
+}
+
+#[test]
+fn validate_reports_every_issue() {
+    let mut header = wav::Header::new(wav::WAV_FORMAT_PCM, 0, 0, 16);
+    header.bytes_per_sample = 4;
+
+    assert_eq!(
+        header.validate(),
+        Err(vec![
+            HeaderIssue::ZeroChannels,
+            HeaderIssue::ZeroSamplingRate,
+            HeaderIssue::BlockAlignMismatch {
+                expected: 0,
+                found: 4
+            },
+        ])
+    );
+    assert!(header.repair().is_err());
+}
+
+#[test]
+fn write_rejects_bad_header() {
+    let header = wav::Header::new(wav::WAV_FORMAT_PCM, 0, 48_000, 16);
+    let data = wav::BitDepth::Sixteen(vec![0; 16]);
+
+    let err = wav::write(header, &data, &mut io::Cursor::new(Vec::new())).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn new_derives_fields_validate_expects() {
+    let header = wav::Header::new(wav::WAV_FORMAT_PCM, 2, 48_000, 12);
+    assert_eq!(header.bytes_per_sample, 4);
+    assert_eq!(header.bytes_per_second, 192_000);
+    assert!(header.validate().is_ok());
+
+    let header = wav::Header::new(wav::WAV_FORMAT_PCM, u16::MAX, 48_000, 32);
+    assert_eq!(header.bytes_per_sample, 0);
+    assert_eq!(
+        header.validate(),
+        Err(vec![HeaderIssue::BlockAlignOverflow])
+    );
+}
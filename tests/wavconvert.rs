@@ -0,0 +1,224 @@
+use std::{convert::TryFrom, fs, path::PathBuf, process::Command};
+
+use wav::{
+    metadata::{Bext, Cart, Cue, CuePoint, Levl, PostTimer},
+    BitDepth, Chunk, Header, HeaderBuilder, SampleFormat, WAV_FORMAT_EXTENSIBLE, WAV_FORMAT_PCM,
+};
+
+/// Returns an empty directory for the test to write to.
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wavconvert-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn wavconvert(args: &[&str]) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_wavconvert"))
+        .args(args)
+        .status()
+        .unwrap()
+        .success()
+}
+
+#[test]
+fn converts_format_and_channels() {
+    let out = out_dir("single").join("mono.wav");
+    assert!(wavconvert(&[
+        "--format",
+        "float",
+        "--channels",
+        "1",
+        "data/sine_16bit_48khz.wav",
+        out.to_str().unwrap(),
+    ]));
+
+    let (header, data) = wav::read(&mut fs::File::open(&out).unwrap()).unwrap();
+    assert_eq!(header.sample_format(), Some(SampleFormat::ThirtyTwoFloat));
+    assert_eq!(header.channel_count, 1);
+
+    let (_, original) =
+        wav::read(&mut fs::File::open("data/sine_16bit_48khz.wav").unwrap()).unwrap();
+    let original = original.convert(SampleFormat::ThirtyTwoFloat);
+    let left: Vec<f32> = original
+        .as_thirty_two_float()
+        .unwrap()
+        .chunks_exact(2)
+        .map(|f| std::f32::consts::FRAC_1_SQRT_2 * f[0] + std::f32::consts::FRAC_1_SQRT_2 * f[1])
+        .collect();
+    assert_eq!(data, BitDepth::ThirtyTwoFloat(left));
+}
+
+#[test]
+fn converts_directories() {
+    let out = out_dir("batch");
+    assert!(wavconvert(&[
+        "--format",
+        "24",
+        "data",
+        out.to_str().unwrap()
+    ]));
+
+    let (header, data) =
+        wav::read(&mut fs::File::open(out.join("sine_8bit_48khz.wav")).unwrap()).unwrap();
+    assert_eq!(header.sample_format(), Some(SampleFormat::TwentyFour));
+    assert_eq!(header.audio_format, WAV_FORMAT_PCM);
+    assert_eq!(header.channel_count, 2);
+    assert_eq!(data.as_twenty_four().map(Vec::len), Some(96_000));
+}
+
+#[test]
+fn keeps_partial_channel_masks() {
+    let dir = out_dir("mask");
+    let (input, output) = (dir.join("in.wav"), dir.join("out.wav"));
+
+    let header = HeaderBuilder::new(SampleFormat::Sixteen)
+        .channel_count(3)
+        .channel_mask(0x3)
+        .build()
+        .unwrap();
+    let data = BitDepth::Sixteen(vec![0; 300]);
+    wav::write(header, &data, &mut fs::File::create(&input).unwrap()).unwrap();
+
+    assert!(wavconvert(&[
+        "--format",
+        "24",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]));
+    let (header, _) = wav::read(&mut fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(header.audio_format, WAV_FORMAT_EXTENSIBLE);
+    assert_eq!(header.extension.unwrap().channel_mask, 0x3);
+}
+
+#[test]
+fn keeps_cart_chunk() {
+    let dir = out_dir("cart");
+    let (input, output) = (dir.join("in.wav"), dir.join("out.wav"));
+
+    let cart = Cart {
+        title: "Morning jingle".to_string(),
+        cut_id: "J0042".to_string(),
+        post_timers: vec![PostTimer {
+            usage: *b"INT1",
+            value: 12_000,
+        }],
+        ..Cart::default()
+    };
+    let (header, data) =
+        wav::read(&mut fs::File::open("data/sine_16bit_48khz.wav").unwrap()).unwrap();
+    let chunks = [Chunk::try_from(&cart).unwrap()];
+    wav::write_with_chunks(
+        header,
+        &data,
+        &chunks,
+        &mut fs::File::create(&input).unwrap(),
+    )
+    .unwrap();
+
+    assert!(wavconvert(&[
+        "--format",
+        "24",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]));
+    let chunks = wav::read_chunks(&mut fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(Cart::try_from(&chunks[0]), Ok(cart));
+}
+
+#[test]
+fn updates_chunks_when_resampling() {
+    let dir = out_dir("resample");
+    let (input, output) = (dir.join("in.wav"), dir.join("out.wav"));
+
+    let cue = Cue {
+        points: vec![CuePoint::new(1, 48_000)],
+    };
+    let bext = Bext {
+        description: "Interview".to_string(),
+        time_reference: 96_000,
+        loudness_value: -2300,
+        ..Bext::default()
+    };
+    let (header, data) =
+        wav::read(&mut fs::File::open("data/sine_16bit_48khz.wav").unwrap()).unwrap();
+    let chunks = [
+        Chunk::try_from(&cue).unwrap(),
+        Chunk::try_from(&bext).unwrap(),
+        Chunk::new(Levl::ID, vec![0; 128]),
+    ];
+    wav::write_with_chunks(
+        header,
+        &data,
+        &chunks,
+        &mut fs::File::create(&input).unwrap(),
+    )
+    .unwrap();
+
+    assert!(wavconvert(&[
+        "--rate",
+        "24000",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]));
+    let chunks = wav::read_chunks(&mut fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(chunks.len(), 2, "levl chunk wasn't dropped");
+    assert_eq!(
+        Cue::try_from(&chunks[0]).unwrap().points,
+        vec![CuePoint::new(1, 24_000)]
+    );
+    let converted = Bext::try_from(&chunks[1]).unwrap();
+    assert_eq!(converted.description, bext.description);
+    assert_eq!(converted.time_reference, 48_000);
+    assert_eq!(converted.loudness_value, Bext::UNSET_LOUDNESS);
+}
+
+#[test]
+fn converts_wave_lists() {
+    fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+        out.extend_from_slice(id);
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+    }
+
+    let dir = out_dir("wavl");
+    let (input, output) = (dir.join("in.wav"), dir.join("out.wav"));
+
+    let mut list = b"wavl".to_vec();
+    push_chunk(&mut list, b"data", &[1, 0, 2, 0]);
+    push_chunk(&mut list, b"slnt", &2_u32.to_le_bytes());
+    let mut body = b"WAVE".to_vec();
+    push_chunk(
+        &mut body,
+        b"fmt ",
+        &Vec::from(Header::new(WAV_FORMAT_PCM, 1, 48_000, 16)),
+    );
+    push_chunk(&mut body, b"LIST", &list);
+    let mut raw = b"RIFF".to_vec();
+    raw.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    raw.extend_from_slice(&body);
+    fs::write(&input, raw).unwrap();
+
+    assert!(wavconvert(&[
+        "--format",
+        "24",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ]));
+    let (header, data) = wav::read(&mut fs::File::open(&output).unwrap()).unwrap();
+    assert_eq!(header.sample_format(), Some(SampleFormat::TwentyFour));
+    assert_eq!(data, BitDepth::TwentyFour(vec![1 << 16, 2 << 16, 0, 0]));
+    let chunks = wav::read_chunks(&mut fs::File::open(&output).unwrap()).unwrap();
+    assert!(chunks.is_empty(), "wave list was copied: {:?}", chunks);
+}
+
+#[test]
+fn refuses_to_overwrite_input() {
+    assert!(!wavconvert(&["data/sine.wav", "data/sine.wav"]));
+    assert!(!wavconvert(&[
+        "--format",
+        "12",
+        "data/sine.wav",
+        "unused.wav"
+    ]));
+}
@@ -0,0 +1,132 @@
+use std::io;
+
+use wav::{BitDepth, Header, ReadOptions, Warning, WAV_FORMAT_PCM};
+
+/// Appends a chunk to `out`, followed by a pad byte if its length is odd.
+fn push_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Returns a wave file whose audio is stored as a wave list of the given chunks.
+fn wave_list_file(header: Header, chunks: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut list = b"wavl".to_vec();
+    for (id, data) in chunks {
+        push_chunk(&mut list, id, data);
+    }
+
+    let mut body = b"WAVE".to_vec();
+    push_chunk(&mut body, b"fmt ", &Vec::from(header));
+    push_chunk(&mut body, b"LIST", &list);
+
+    let mut raw = b"RIFF".to_vec();
+    raw.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    raw.extend_from_slice(&body);
+    raw
+}
+
+fn samples(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+fn silence(frames: u32) -> Vec<u8> {
+    frames.to_le_bytes().to_vec()
+}
+
+#[test]
+fn wave_list_is_expanded() {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 48_000, 16);
+    let raw = wave_list_file(
+        header,
+        &[
+            (b"data", samples(&[1, -1, 2, -2])),
+            (b"slnt", silence(3)),
+            (b"cue ", vec![0; 4]),
+            (b"data", samples(&[3, -3])),
+            (b"slnt", silence(1)),
+        ],
+    );
+
+    let (read_header, data) = wav::read(&mut io::Cursor::new(&raw)).unwrap();
+    assert_eq!(read_header, header);
+    assert_eq!(
+        data,
+        BitDepth::Sixteen(vec![1, -1, 2, -2, 0, 0, 0, 0, 0, 0, 3, -3, 0, 0])
+    );
+    assert_eq!(
+        wav::read_header(&mut io::Cursor::new(&raw)).unwrap(),
+        header
+    );
+}
+
+#[test]
+fn wave_list_silence_of_unsigned_samples() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 8);
+    let raw = wave_list_file(
+        header,
+        &[
+            (b"slnt", silence(2)),
+            (b"data", vec![10, 20, 30]),
+            (b"slnt", silence(1)),
+        ],
+    );
+
+    let (_, data) = wav::read(&mut io::Cursor::new(&raw)).unwrap();
+    assert_eq!(data, BitDepth::Eight(vec![128, 128, 10, 20, 30, 128]));
+}
+
+#[test]
+fn wave_list_partial_frames() {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 48_000, 16);
+    let mut first = samples(&[1, -1, 2]);
+    first.push(0);
+    let raw = wave_list_file(header, &[(b"data", first), (b"data", samples(&[3, -3]))]);
+
+    assert!(wav::read(&mut io::Cursor::new(&raw)).is_err());
+
+    let options = ReadOptions {
+        recover: true,
+        ..ReadOptions::default()
+    };
+    let (_, data, warnings) = wav::read_with_options(&mut io::Cursor::new(&raw), options).unwrap();
+    assert_eq!(data, BitDepth::Sixteen(vec![1, -1, 3, -3]));
+    assert_eq!(warnings, [Warning::PartialFrameDropped { bytes: 3 }]);
+}
+
+#[test]
+fn wave_list_silence_is_bounded() {
+    let raw = wave_list_file(
+        Header::new(WAV_FORMAT_PCM, 2, 48_000, 16),
+        &[(b"slnt", silence(u32::MAX))],
+    );
+    assert!(wav::read(&mut io::Cursor::new(&raw)).is_err());
+
+    // Each chunk is within the bound, but not their sum
+    let raw = wave_list_file(
+        Header::new(WAV_FORMAT_PCM, 1, 48_000, 8),
+        &[
+            (b"slnt", silence(0x9000_0000)),
+            (b"slnt", silence(0x9000_0000)),
+        ],
+    );
+    assert!(wav::read(&mut io::Cursor::new(&raw)).is_err());
+}
+
+#[test]
+fn missing_audio_data() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 48_000, 16);
+    let mut raw = wave_list_file(header, &[(b"data", samples(&[1]))]);
+    // A `LIST` of another type holds no audio
+    let list = raw.windows(4).position(|w| w == b"wavl").unwrap();
+    raw[list..list + 4].copy_from_slice(b"INFO");
+
+    assert!(wav::read(&mut io::Cursor::new(&raw)).is_err());
+    assert!(wav::read_header(&mut io::Cursor::new(&raw)).is_ok());
+
+    let raw = wave_list_file(header, &[(b"slnt", vec![1, 0])]);
+    assert!(wav::read(&mut io::Cursor::new(&raw)).is_err());
+}
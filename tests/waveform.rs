@@ -0,0 +1,155 @@
+use std::io::Cursor;
+
+use wav::{
+    waveform::{self, Summary, WaveformGenerator, WaveformOptions},
+    BitDepth, Header, SampleFormat, WAV_FORMAT_IEEE_FLOAT, WAV_FORMAT_PCM,
+};
+
+fn options(block_frames: u64, levels: usize, factor: u64) -> WaveformOptions {
+    WaveformOptions {
+        block_frames,
+        levels,
+        factor,
+    }
+}
+
+#[test]
+fn blocks_summarize_each_channel() {
+    let header = Header::new(WAV_FORMAT_IEEE_FLOAT, 2, 48_000, 32);
+    let data = BitDepth::ThirtyTwoFloat(vec![
+        0.5, -0.25, -0.5, 0.25, 0.5, 0.0, -0.5, 1.0, // first block
+        0.25, 0.0, // partial last block
+    ]);
+
+    let overview = waveform::waveform(&header, &data, options(4, 1, 4)).unwrap();
+    assert_eq!(overview.frames, 5);
+    assert_eq!(overview.channel_count, 2);
+    let level = &overview.levels[0];
+    assert_eq!(level.summaries.len(), 4);
+
+    assert_eq!(
+        level.block(0, 2).unwrap(),
+        &[
+            Summary {
+                min: -0.5,
+                max: 0.5,
+                rms: 0.5
+            },
+            Summary {
+                min: -0.25,
+                max: 1.0,
+                rms: (1.125f32 / 4.0).sqrt()
+            },
+        ]
+    );
+    assert_eq!(
+        level.block(1, 2).unwrap()[0],
+        Summary {
+            min: 0.25,
+            max: 0.25,
+            rms: 0.25
+        }
+    );
+    assert!(level.block(2, 2).is_none());
+}
+
+#[test]
+fn coarser_levels_merge_blocks() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 16);
+    let samples: Vec<i16> = (0..1_000)
+        .map(|i| ((i * 37) % 2_001 - 1_000) as i16)
+        .collect();
+    let data = BitDepth::Sixteen(samples.clone());
+
+    let overview = waveform::waveform(&header, &data, options(10, 3, 4)).unwrap();
+    let block_frames: Vec<u64> = overview.levels.iter().map(|l| l.block_frames).collect();
+    assert_eq!(block_frames, [10, 40, 160]);
+    let lens: Vec<usize> = overview.levels.iter().map(|l| l.summaries.len()).collect();
+    assert_eq!(lens, [100, 25, 7]);
+
+    // Every level matches summarizing its blocks directly
+    for level in &overview.levels {
+        let direct = waveform::waveform(&header, &data, options(level.block_frames, 1, 2)).unwrap();
+        for (a, b) in level.summaries.iter().zip(&direct.levels[0].summaries) {
+            assert_eq!(a.min, b.min);
+            assert_eq!(a.max, b.max);
+            assert!((a.rms - b.rms).abs() < 1e-6);
+        }
+        assert_eq!(level.summaries.len(), direct.levels[0].summaries.len());
+    }
+
+    assert_eq!(overview.level_for(100).unwrap().block_frames, 40);
+    assert_eq!(overview.level_for(1_000).unwrap().block_frames, 160);
+    assert_eq!(overview.level_for(1).unwrap().block_frames, 10);
+}
+
+#[test]
+fn blocks_match_whole() {
+    let raw: &[u8] = include_bytes!("../data/sine_24bit_48khz.wav");
+    let (header, data) = wav::read(&mut Cursor::new(raw)).unwrap();
+    let samples = data.as_twenty_four().unwrap();
+
+    let whole = waveform::waveform(&header, &data, options(100, 4, 3)).unwrap();
+
+    let mut generator = WaveformGenerator::new(&header, options(100, 4, 3)).unwrap();
+    let step = 2 * usize::from(header.channel_count) * 777;
+    for block in samples.chunks(step) {
+        generator.process(&BitDepth::TwentyFour(block.to_vec()));
+    }
+    assert_eq!(generator.finish(), whole);
+
+    assert_eq!(
+        waveform::waveform_reader(Cursor::new(raw), options(100, 4, 3)).unwrap(),
+        whole
+    );
+}
+
+#[test]
+fn every_format_gives_the_same_overview() {
+    let raw: &[u8] = include_bytes!("../data/sine_32bit_float_48khz.wav");
+    let (header, data) = wav::read(&mut Cursor::new(raw)).unwrap();
+    let reference = waveform::waveform(&header, &data, options(480, 1, 2)).unwrap();
+    let reference = &reference.levels[0].summaries;
+    assert!(reference.iter().all(|s| s.max > 0.1 && s.min < -0.1));
+
+    for &format in &[
+        SampleFormat::Eight,
+        SampleFormat::Sixteen,
+        SampleFormat::TwentyFour,
+    ] {
+        let header = Header::builder(format)
+            .channel_count(header.channel_count)
+            .sampling_rate(header.sampling_rate)
+            .build()
+            .unwrap();
+        let data = data.convert(format);
+        let overview = waveform::waveform(&header, &data, options(480, 1, 2)).unwrap();
+
+        let summaries = &overview.levels[0].summaries;
+        assert_eq!(summaries.len(), reference.len());
+        for (a, b) in summaries.iter().zip(reference) {
+            assert!((a.min - b.min).abs() < 0.01);
+            assert!((a.max - b.max).abs() < 0.01);
+            assert!((a.rms - b.rms).abs() < 0.01);
+        }
+    }
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 16);
+    assert!(WaveformGenerator::new(&header, options(0, 1, 2)).is_err());
+    assert!(WaveformGenerator::new(&header, options(10, 0, 2)).is_err());
+    assert!(WaveformGenerator::new(&header, options(10, 2, 1)).is_err());
+    assert!(WaveformGenerator::new(&header, options(10, 1, 1)).is_ok());
+    assert!(WaveformGenerator::new(&header, options(u64::MAX / 2, 3, 2)).is_err());
+    assert!(WaveformGenerator::new(
+        &Header::new(WAV_FORMAT_PCM, 0, 8_000, 16),
+        WaveformOptions::default()
+    )
+    .is_err());
+
+    let empty = waveform::waveform(&header, &BitDepth::Empty, WaveformOptions::default()).unwrap();
+    assert_eq!(empty.frames, 0);
+    assert!(empty.levels[0].summaries.is_empty());
+}
@@ -0,0 +1,129 @@
+use std::{convert::TryFrom, fs::File, path::PathBuf, process::Command};
+
+use wav::{
+    metadata::{Bext, Cart, Cue, CuePoint, Ixml},
+    Chunk,
+};
+
+/// Writes a copy of the 16-bit test file with a chunk of every kind of metadata `wavinfo` prints.
+fn file_with_metadata(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("wavinfo-{}-{name}.wav", std::process::id()));
+
+    let bext = Bext {
+        description: "Dawn chorus".to_string(),
+        loudness_value: -2_300,
+        ..Bext::default()
+    };
+    let cart = Cart {
+        title: "Morning jingle".to_string(),
+        ..Cart::default()
+    };
+    let mut ixml = Ixml::default();
+    ixml.set_text("SCENE", "12A");
+    let cue = Cue {
+        points: vec![CuePoint::new(7, 24_000)],
+    };
+    let info = b"INFOINAM\x06\x00\x00\x00Title\x00".to_vec();
+    let chunks = [
+        Chunk::try_from(&bext).unwrap(),
+        Chunk::try_from(&cart).unwrap(),
+        Chunk::from(&ixml),
+        Chunk::try_from(&cue).unwrap(),
+        Chunk::new(*b"LIST", info),
+    ];
+
+    let (header, data) = wav::read(&mut File::open("data/sine_16bit_48khz.wav").unwrap()).unwrap();
+    wav::write_with_chunks(header, &data, &chunks, &mut File::create(&path).unwrap()).unwrap();
+    path
+}
+
+#[test]
+fn read_header_matches_read() {
+    let (header, _) =
+        wav::read(&mut File::open("data/sine_32bit_float_48khz.wav").unwrap()).unwrap();
+    let only_header =
+        wav::read_header(&mut File::open("data/sine_32bit_float_48khz.wav").unwrap()).unwrap();
+
+    assert_eq!(only_header, header);
+}
+
+#[test]
+fn list_chunks_includes_fmt_and_data() {
+    let chunks = wav::list_chunks(&mut File::open("data/sine_8bit_48khz.wav").unwrap()).unwrap();
+
+    let ids: Vec<_> = chunks.iter().map(|c| c.id).collect();
+    assert_eq!(ids, vec![*b"fmt ", *b"data"]);
+    assert_eq!(chunks[1].offset, 36);
+    assert_eq!(chunks[1].data_offset(), 44);
+    assert_eq!(chunks[1].len, 96_000);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn wavinfo_prints_json() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wavinfo"))
+        .args(["--json", "data/sine_16bit_48khz.wav"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("\"format\":\"pcm\""));
+    assert!(stdout.contains("\"frames\":48000"));
+    assert!(stdout.contains("{\"id\":\"data\",\"offset\":36,\"size\":192000}"));
+}
+
+#[test]
+fn wavinfo_prints_metadata() {
+    let path = file_with_metadata("text");
+    let output = Command::new(env!("CARGO_BIN_EXE_wavinfo"))
+        .arg(&path)
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for line in [
+        "    Description:  Dawn chorus",
+        "    Loudness:     -23.00 LUFS",
+        "    Title:        Morning jingle",
+        "    Scene:        12A",
+        "    INAM:         Title",
+        "    7:            frame 24000",
+    ] {
+        assert!(stdout.contains(line), "{} missing from {}", line, stdout);
+    }
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn wavinfo_prints_metadata_as_json() {
+    let path = file_with_metadata("json");
+    let output = Command::new(env!("CARGO_BIN_EXE_wavinfo"))
+        .arg("--json")
+        .arg(&path)
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let metadata = &json["metadata"];
+    assert_eq!(metadata["bext"]["description"], "Dawn chorus");
+    assert_eq!(metadata["cart"]["title"], "Morning jingle");
+    assert_eq!(metadata["ixml"]["scene"], "12A");
+    assert_eq!(metadata["info"][0]["text"], "Title");
+    assert_eq!(metadata["cue"]["points"][0]["sample_offset"], 24_000);
+}
+
+#[test]
+fn wavinfo_fails_on_missing_file() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wavinfo"))
+        .arg("data/missing.wav")
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("data/missing.wav"));
+}
@@ -0,0 +1,136 @@
+use std::{
+    convert::TryFrom,
+    io::{self, Cursor},
+};
+
+use wav::{
+    metadata::Levl,
+    waveform::{WaveformGenerator, WaveformOptions},
+    BitDepth, Chunk, Header, SampleFormat, WavReader, WavWriter, WAV_FORMAT_PCM,
+};
+
+#[test]
+fn streamed_matches_write() {
+    let raw: &[u8] = include_bytes!("../data/sine_24bit_48khz.wav");
+    let mut reader = WavReader::new(Cursor::new(raw)).unwrap();
+    let header = *reader.header();
+    let chunks = vec![Chunk::new(*b"note", b"odd".to_vec())];
+
+    let mut writer = WavWriter::with_chunks(Cursor::new(Vec::new()), header, &chunks).unwrap();
+    while reader.position() < reader.frame_count() {
+        writer
+            .write_frames(&reader.read_frames(1_000).unwrap())
+            .unwrap();
+    }
+    assert_eq!(writer.frames_written(), 48_000);
+    let streamed = writer.finish().unwrap().into_inner();
+
+    let (_, data) = wav::read(&mut Cursor::new(raw)).unwrap();
+    let mut written = Cursor::new(Vec::new());
+    wav::write_with_chunks(header, &data, &chunks, &mut written).unwrap();
+
+    assert_eq!(streamed, written.into_inner());
+}
+
+#[test]
+fn odd_data_is_padded() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 8);
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), header).unwrap();
+    writer
+        .write_frames(&BitDepth::Eight(vec![1, 2, 3]))
+        .unwrap();
+    let file = writer.finish().unwrap().into_inner();
+
+    assert_eq!(file.len(), 48);
+    assert_eq!(&file[4..8], &40u32.to_le_bytes());
+    let (_, data) = wav::read(&mut Cursor::new(file)).unwrap();
+    assert_eq!(data, BitDepth::Eight(vec![1, 2, 3]));
+}
+
+#[test]
+fn write_frames_rejects_mismatched_samples() {
+    let header = Header::new(WAV_FORMAT_PCM, 2, 48_000, 16);
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), header).unwrap();
+
+    let err = writer
+        .write_frames(&BitDepth::TwentyFour(vec![0; 2]))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let err = writer
+        .write_frames(&BitDepth::Sixteen(vec![0; 3]))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(writer.frames_written(), 0);
+}
+
+#[test]
+fn convert_between_formats() {
+    let sixteen = BitDepth::Sixteen(vec![i16::MIN, -1, 0, 1, i16::MAX]);
+
+    let twenty_four = sixteen.convert(SampleFormat::TwentyFour);
+    assert_eq!(
+        twenty_four,
+        BitDepth::TwentyFour(vec![i32::MIN, -0x1_0000, 0, 0x1_0000, 0x7FFF_0000])
+    );
+    assert_eq!(twenty_four.convert(SampleFormat::Sixteen), sixteen);
+
+    let float = sixteen.convert(SampleFormat::ThirtyTwoFloat);
+    assert_eq!(float.as_thirty_two_float().unwrap()[0], -1.0);
+    assert_eq!(float.convert(SampleFormat::Sixteen), sixteen);
+
+    let clipped = BitDepth::ThirtyTwoFloat(vec![-2.0, 0.5, 2.0]);
+    assert_eq!(
+        clipped.convert(SampleFormat::Eight),
+        BitDepth::Eight(vec![0, 192, 255])
+    );
+    assert_eq!(
+        BitDepth::Empty.convert(SampleFormat::Eight),
+        BitDepth::Empty
+    );
+}
+
+#[test]
+fn chunks_are_written_after_streamed_data() {
+    let raw: &[u8] = include_bytes!("../data/sine_16bit_48khz.wav");
+    let mut reader = WavReader::new(Cursor::new(raw)).unwrap();
+    let header = *reader.header();
+
+    let mut writer = WavWriter::new(Cursor::new(Vec::new()), header).unwrap();
+    let mut generator = WaveformGenerator::new(&header, WaveformOptions::default()).unwrap();
+    while reader.position() < reader.frame_count() {
+        let frames = reader.read_frames(1_000).unwrap();
+        generator.process(&frames);
+        writer.write_frames(&frames).unwrap();
+    }
+    let overview = generator.finish();
+    let levl = Levl::from_level(&header, &overview.levels[0]).unwrap();
+    let chunks = [
+        Chunk::try_from(&levl).unwrap(),
+        Chunk::new(*b"note", b"odd".to_vec()),
+    ];
+    let mut file = writer.finish_with_chunks(&chunks).unwrap();
+
+    let ids: Vec<[u8; 4]> = wav::list_chunks(&mut file)
+        .unwrap()
+        .iter()
+        .map(|c| c.id)
+        .collect();
+    assert_eq!(ids, [*b"fmt ", *b"data", *b"levl", *b"note"]);
+    assert_eq!(wav::read_chunks(&mut file).unwrap(), chunks);
+
+    let file = file.into_inner();
+    assert_eq!(&file[4..8], &(file.len() as u32 - 8).to_le_bytes());
+    let (_, data) = wav::read(&mut Cursor::new(file)).unwrap();
+    assert_eq!(data, wav::read(&mut Cursor::new(raw)).unwrap().1);
+}
+
+#[test]
+fn finish_with_chunks_rejects_data_chunks() {
+    let header = Header::new(WAV_FORMAT_PCM, 1, 8_000, 8);
+    let writer = WavWriter::new(Cursor::new(Vec::new()), header).unwrap();
+    let err = writer
+        .finish_with_chunks(&[Chunk::new(*b"data", Vec::new())])
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}